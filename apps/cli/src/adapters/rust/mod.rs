@@ -52,10 +52,10 @@ impl PluginAdapter for RustAdapter {
         use notify::Watcher;
         watcher.watch(dir, notify::RecursiveMode::Recursive)?;
 
-        cliclack::log::info(format!("Watching Rust project at {:?}", dir))?;
+        crate::core::output::log::info(format!("Watching Rust project at {:?}", dir))?;
 
         if let Err(e) = self.build(dir, super::BuildOptions::default()).await {
-            cliclack::log::error(format!("Build failed: {}", e))?;
+            crate::core::output::log::error(format!("Build failed: {}", e))?;
         }
 
         loop {
@@ -63,11 +63,11 @@ impl PluginAdapter for RustAdapter {
                 tokio::time::sleep(std::time::Duration::from_millis(500)).await;
                 while rx.try_recv().is_ok() {}
 
-                cliclack::log::step("Change detected...")?;
+                crate::core::output::log::step("Change detected...")?;
                 if let Err(e) = self.build(dir, super::BuildOptions::default()).await {
-                    cliclack::log::error(format!("Build failed: {}", e))?;
+                    crate::core::output::log::error(format!("Build failed: {}", e))?;
                 } else {
-                    cliclack::log::success("Build complete.")?;
+                    crate::core::output::log::success("Build complete.")?;
                 }
             }
         }
@@ -38,7 +38,7 @@ impl PluginAdapter for TsAdapter {
 
         // Node tools usually have their own watch mode (npm run watch).
         // But for consistency, we trigger rebuilds.
-        cliclack::log::info("Watching TypeScript project (Not full implemented)")?;
+        crate::core::output::log::info("Watching TypeScript project (Not full implemented)")?;
         Ok(())
     }
 
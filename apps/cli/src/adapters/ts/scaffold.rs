@@ -120,7 +120,7 @@ build().catch(err => {
         .output()
         .is_err()
     {
-        cliclack::log::warning(
+        crate::core::output::log::warning(
             "Failed to install dependencies. Please run 'npm install' manually.",
         )?;
     }
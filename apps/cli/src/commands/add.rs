@@ -0,0 +1,93 @@
+use anyhow::Result;
+use clap::Parser;
+use env_manifest::ManifestEditor;
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+pub struct AddCommand {
+    /// Dependencies to add, as `name` or `name@requirement` (e.g. node@^20)
+    #[arg(required = true)]
+    pub packages: Vec<String>,
+
+    /// Add to dev-dependencies
+    #[arg(long, conflicts_with_all = ["test", "build", "group"])]
+    pub dev: bool,
+
+    /// Add to test-dependencies
+    #[arg(long, conflicts_with_all = ["build", "group"])]
+    pub test: bool,
+
+    /// Add to build-dependencies
+    #[arg(long, conflicts_with = "group")]
+    pub build: bool,
+
+    /// Add to a dependency group
+    #[arg(long)]
+    pub group: Option<String>,
+
+    /// Path to the environment file (env.toml/json/yaml)
+    #[arg(short, long)]
+    pub path: Option<PathBuf>,
+}
+
+impl AddCommand {
+    pub async fn execute(self) -> Result<()> {
+        let path = match &self.path {
+            Some(p) => p.clone(),
+            None => crate::utils::loader::find_manifest(&std::env::current_dir()?)?,
+        };
+        let mut editor = ManifestEditor::open(&path)?;
+
+        let section: Vec<&str> = if self.dev {
+            vec!["dev-dependencies"]
+        } else if self.test {
+            vec!["test-dependencies"]
+        } else if self.build {
+            vec!["build-dependencies"]
+        } else if let Some(group) = &self.group {
+            vec!["group", group.as_str(), "dependencies"]
+        } else {
+            vec!["dependencies"]
+        };
+
+        for spec in &self.packages {
+            let (name, version) = parse_spec(spec);
+            editor.set_dependency(&section, name, version)?;
+            crate::core::output::log::success(format!(
+                "Added {} {} to {}",
+                console::style(name).bold(),
+                version,
+                section.join(".")
+            ))?;
+        }
+
+        crate::utils::loader::save_manifest(&editor)?;
+        Ok(())
+    }
+}
+
+/// Split `node@^20` into name and requirement; a bare name means any version. A leading
+/// `@` belongs to a scoped npm name (`@types/node@^20`).
+fn parse_spec(spec: &str) -> (&str, &str) {
+    let scope = usize::from(spec.starts_with('@'));
+    match spec[scope..].rsplit_once('@') {
+        Some((name, version)) if !name.is_empty() && !version.is_empty() => {
+            (&spec[..scope + name.len()], version)
+        }
+        _ => (spec.trim_end_matches('@'), "*"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_spec() {
+        assert_eq!(parse_spec("node@^20"), ("node", "^20"));
+        assert_eq!(parse_spec("node"), ("node", "*"));
+        assert_eq!(parse_spec("node@"), ("node", "*"));
+        assert_eq!(parse_spec("@types/node@^20"), ("@types/node", "^20"));
+        assert_eq!(parse_spec("@types/node"), ("@types/node", "*"));
+    }
+}
@@ -58,7 +58,7 @@ impl BundleCommand {
             ));
         }
 
-        cliclack::log::info(format!("Project Directory: {:?}", project_dir))?;
+        crate::core::output::log::info(format!("Project Directory: {:?}", project_dir))?;
 
         // 1. Build (Requires Cargo.toml for compilation, regardless of metadata source)
         self.build_component(&project_dir)?;
@@ -74,7 +74,7 @@ impl BundleCommand {
         let bundle_dir = project_dir.join("dist");
         if bundle_dir.exists() {
             std::fs::remove_dir_all(&bundle_dir)?;
-            cliclack::log::info("Cleaned previous dist artifacts")?;
+            crate::core::output::log::info("Cleaned previous dist artifacts")?;
         }
         std::fs::create_dir(&bundle_dir)?;
 
@@ -101,20 +101,20 @@ impl BundleCommand {
             }
         };
 
-        cliclack::log::success(format!("Artifact found: {:?}", wasm_path))?;
+        crate::core::output::log::success(format!("Artifact found: {:?}", wasm_path))?;
         let dest = bundle_dir.join("artifact.wasm");
 
         if self.no_optimize {
             std::fs::copy(&wasm_path, &dest)?;
-            cliclack::log::warning("Optimization skipped by user.")?;
+            crate::core::output::log::warning("Optimization skipped by user.")?;
         } else {
             match self.optimize_wasm(&wasm_path, &bundle_dir) {
                 Ok(opt_path) => {
                     std::fs::rename(opt_path, &dest)?;
-                    cliclack::log::success("Optimized with wasm-tools strip.")?;
+                    crate::core::output::log::success("Optimized with wasm-tools strip.")?;
                 }
                 Err(e) => {
-                    cliclack::log::warning(format!(
+                    crate::core::output::log::warning(format!(
                         "Optimization failed ({}). Using unoptimized.",
                         e
                     ))?;
@@ -127,19 +127,19 @@ impl BundleCommand {
         let metadata_path = bundle_dir.join("metadata.json");
         let metadata_json = serde_json::to_string_pretty(&manifest)?;
         std::fs::write(&metadata_path, metadata_json)?;
-        cliclack::log::info("Generated metadata.json (from env.toml/Cargo.toml)")?;
+        crate::core::output::log::info("Generated metadata.json (from env.toml/Cargo.toml)")?;
 
         // --- Step C: SBOM ---
         self.generate_sbom(&project_dir, &bundle_dir)?;
 
-        cliclack::log::success(format!("Bundle created at {:?}", bundle_dir))?;
-        cliclack::outro("Bundle complete.")?;
+        crate::core::output::log::success(format!("Bundle created at {:?}", bundle_dir))?;
+        crate::core::output::outro("Bundle complete.")?;
 
         Ok(bundle_dir)
     }
 
     fn build_component(&self, dir: &Path) -> Result<()> {
-        cliclack::log::step("Building component (release)...")?;
+        crate::core::output::log::step("Building component (release)...")?;
         let status = Command::new("cargo")
             .arg("component")
             .arg("build")
@@ -363,7 +363,7 @@ impl BundleCommand {
         };
 
         if should_generate {
-            cliclack::log::step("Generating SBOM (cargo sbom)...")?;
+            crate::core::output::log::step("Generating SBOM (cargo sbom)...")?;
 
             // Fix for monorepos: cargo-sbom needs Cargo.lock in the current dir
             let local_lock = project_dir.join("Cargo.lock");
@@ -395,15 +395,17 @@ impl BundleCommand {
                     // Verify size
                     let meta = std::fs::metadata(&sbom_path)?;
                     if s.success() && meta.len() > 0 {
-                        cliclack::log::success("Generated sbom.spdx.json")?;
+                        crate::core::output::log::success("Generated sbom.spdx.json")?;
                         return Ok(());
                     } else {
                         // Failure logic
-                        cliclack::log::warning("cargo-sbom failed or produced empty file.")?;
+                        crate::core::output::log::warning(
+                            "cargo-sbom failed or produced empty file.",
+                        )?;
                     }
                 }
                 Err(_) => {
-                    cliclack::log::warning("Failed to execute cargo-sbom")?;
+                    crate::core::output::log::warning("Failed to execute cargo-sbom")?;
                 }
             }
         }
@@ -438,9 +440,9 @@ impl BundleCommand {
 
         if let Some(l) = valid_lock {
             std::fs::copy(&l, bundle_dir.join("Cargo.lock"))?;
-            cliclack::log::info("Included Cargo.lock (SBOM fallback)")?;
+            crate::core::output::log::info("Included Cargo.lock (SBOM fallback)")?;
         } else {
-            cliclack::log::warning("No Cargo.lock found in project or workspace root.")?;
+            crate::core::output::log::warning("No Cargo.lock found in project or workspace root.")?;
         }
         Ok(())
     }
@@ -449,7 +451,7 @@ impl BundleCommand {
     fn optimize_wasm(&self, input: &Path, output_dir: &Path) -> Result<PathBuf> {
         let output = output_dir.join("plugin.opt.wasm"); // Temp name
 
-        cliclack::log::step("Optimizing Wasm (wasm-tools strip)...")?;
+        crate::core::output::log::step("Optimizing Wasm (wasm-tools strip)...")?;
 
         match Command::new("wasm-tools").arg("--version").output() {
             Ok(_) => self.run_strip(input, &output),
@@ -458,7 +460,7 @@ impl BundleCommand {
                     .initial_value(true)
                     .interact()?;
                 if install {
-                    cliclack::log::step("Installing wasm-tools...")?;
+                    crate::core::output::log::step("Installing wasm-tools...")?;
                     let status = Command::new("brew")
                         .arg("install")
                         .arg("wasm-tools")
@@ -496,7 +498,8 @@ impl BundleCommand {
             if let Some(p) = current {
                 let l = p.join("Cargo.lock");
                 if l.exists() {
-                    cliclack::log::info(format!("Found workspace lockfile at: {:?}", l)).ok();
+                    crate::core::output::log::info(format!("Found workspace lockfile at: {:?}", l))
+                        .ok();
                     return Some(l);
                 }
                 current = p.parent();
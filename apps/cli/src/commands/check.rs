@@ -0,0 +1,87 @@
+use anyhow::Result;
+use clap::Parser;
+use domain::system::PlatformDetector;
+use env_manifest::{ManifestSource, ManifestValidator, ValidationLevel};
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+pub struct CheckCommand {
+    /// Path to the environment file (env.toml/json/yaml)
+    #[arg(short, long)]
+    pub path: Option<PathBuf>,
+
+    /// Print diagnostics as JSON (for editors and CI)
+    #[arg(long)]
+    pub json: bool,
+
+    /// Skip checking `platform` constraints against this machine
+    #[arg(long)]
+    pub no_platform: bool,
+}
+
+impl CheckCommand {
+    pub async fn execute(self) -> Result<()> {
+        let path = match &self.path {
+            Some(p) => p.clone(),
+            None => crate::utils::loader::find_manifest(&std::env::current_dir()?)?,
+        };
+        let source = ManifestSource::read(&path)?;
+
+        let result = match source.validate() {
+            Ok((manifest, mut result)) => {
+                if !self.no_platform {
+                    let platform = PlatformDetector::detect().target();
+                    ManifestValidator::check_platform(&manifest, &platform, &mut result);
+                    source.annotate(&mut result);
+                }
+                result
+            }
+            Err(e) => {
+                if self.json {
+                    let error = serde_json::json!({
+                        "format": e.format,
+                        "message": e.message,
+                        "location": e.span,
+                    });
+                    println!("{}", serde_json::to_string_pretty(&error)?);
+                } else {
+                    eprintln!("{}\n", crate::utils::diagnostics::render_parse_error(&e));
+                }
+                anyhow::bail!("{} could not be parsed", path.display());
+            }
+        };
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&result)?);
+        } else {
+            for issue in &result.issues {
+                eprintln!("{}\n", crate::utils::diagnostics::render_issue(issue));
+            }
+        }
+
+        let count = |level| result.issues.iter().filter(|i| i.level == level).count();
+        let (errors, warnings) = (
+            count(ValidationLevel::Error),
+            count(ValidationLevel::Warning),
+        );
+
+        if result.has_errors() {
+            anyhow::bail!(
+                "{} has {} error(s) and {} warning(s)",
+                path.display(),
+                errors,
+                warnings
+            );
+        }
+
+        if !self.json {
+            eprintln!(
+                "{} {} ({} warning(s))",
+                console::style("✔").green().bold(),
+                path.display(),
+                warnings
+            );
+        }
+        Ok(())
+    }
+}
@@ -25,11 +25,11 @@ impl DevCommand {
             console::style(env!("CARGO_PKG_VERSION")).dim()
         ))?;
 
-        cliclack::log::info(format!("Watching path: {:?}", self.path))?;
+        crate::core::output::log::info(format!("Watching path: {:?}", self.path))?;
 
         // Initial run
         if let Err(e) = self.run_iteration().await {
-            cliclack::log::error(format!("Initial run failed: {}", e))?;
+            crate::core::output::log::error(format!("Initial run failed: {}", e))?;
         }
 
         // Setup watcher
@@ -60,10 +60,10 @@ impl DevCommand {
                 // Clear any pending events
                 while rx.try_recv().is_ok() {}
 
-                cliclack::log::step("Change detected! Re-running plugin...")?;
+                crate::core::output::log::step("Change detected! Re-running plugin...")?;
 
                 if let Err(e) = self.run_iteration().await {
-                    cliclack::log::error(format!("Dev iteration failed: {}", e))?;
+                    crate::core::output::log::error(format!("Dev iteration failed: {}", e))?;
                 }
             }
         }
@@ -74,7 +74,7 @@ impl DevCommand {
 
         // If we find one, we let it build and tell us where the Wasm is.
         if let Ok(adapter) = crate::adapters::get_adapter(&self.path) {
-            cliclack::log::info(format!("Detected language: {}", adapter.name()))?;
+            crate::core::output::log::info(format!("Detected language: {}", adapter.name()))?;
 
             match adapter
                 .build(&self.path, crate::adapters::BuildOptions::default())
@@ -82,15 +82,20 @@ impl DevCommand {
             {
                 Ok(path) => {
                     wasm_path = path;
-                    cliclack::log::success(format!("Build successful: {:?}", wasm_path))?;
+                    crate::core::output::log::success(format!(
+                        "Build successful: {:?}",
+                        wasm_path
+                    ))?;
                 }
                 Err(e) => {
-                    cliclack::log::error(format!("Build failed: {}", e))?;
+                    crate::core::output::log::error(format!("Build failed: {}", e))?;
                     anyhow::bail!("Build failed");
                 }
             }
         } else {
-            cliclack::log::warning("No language adapter detected. Watching static Wasm file...")?;
+            crate::core::output::log::warning(
+                "No language adapter detected. Watching static Wasm file...",
+            )?;
         }
 
         // We now have a valid wasm_path (either from build or arg)
@@ -100,10 +105,14 @@ impl DevCommand {
             dry_run: true,
             project_root: Some(self.path.clone()),
             yes: true,
+            rescan: false,
+            non_interactive: false,
+            policy: None,
+            decisions: None,
         };
 
         if let Err(e) = resolve_cmd.execute().await {
-            cliclack::log::error(format!("Resolution failed: {}", e))?;
+            crate::core::output::log::error(format!("Resolution failed: {}", e))?;
         }
 
         Ok(())
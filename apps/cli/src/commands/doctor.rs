@@ -16,7 +16,7 @@ pub struct DoctorCommand {
 impl DoctorCommand {
     pub async fn execute(self) -> Result<()> {
         cliclack::intro(console::style("EnvArchitect Doctor").bold())?;
-        cliclack::log::info("Initializing embedded Physician...")?;
+        crate::core::output::log::info("Initializing embedded Physician...")?;
 
         let root = self
             .path
@@ -26,20 +26,21 @@ impl DoctorCommand {
         let absolute_root = std::fs::canonicalize(&root).unwrap_or(root.clone());
 
         if let Ok(adapter) = crate::adapters::get_adapter(&absolute_root) {
-            cliclack::log::info(format!(
+            crate::core::output::log::info(format!(
                 "Checking toolchain for language: {}",
                 adapter.name()
             ))?;
             match adapter.check_health().await {
-                Ok(_) => {
-                    cliclack::log::success(format!("Toolchain for {} is healthy.", adapter.name()))?
-                }
+                Ok(_) => crate::core::output::log::success(format!(
+                    "Toolchain for {} is healthy.",
+                    adapter.name()
+                ))?,
                 Err(e) => {
-                    cliclack::log::error(format!("Toolchain issues: {}", e))?;
+                    crate::core::output::log::error(format!("Toolchain issues: {}", e))?;
                 }
             }
         } else {
-            cliclack::log::warning(
+            crate::core::output::log::warning(
                 "No supported project language detected. Skipping toolchain check.",
             )?;
         }
@@ -74,7 +75,9 @@ impl DoctorCommand {
         const DOCTOR_WASM: &[u8] = &[]; // TODO: Restore when doctor plugin is built
 
         if DOCTOR_WASM.is_empty() {
-            cliclack::log::warning("Doctor plugin not built. Skipping Wasm diagnostics.")?;
+            crate::core::output::log::warning(
+                "Doctor plugin not built. Skipping Wasm diagnostics.",
+            )?;
             return Ok(());
         }
 
@@ -101,10 +104,10 @@ impl DoctorCommand {
 
         match plugin.call_resolve(&mut store, &context).await? {
             Ok(_) => {
-                cliclack::log::success("Diagnostics Complete.")?;
+                crate::core::output::log::success("Diagnostics Complete.")?;
             }
             Err(e) => {
-                cliclack::log::error(format!("Plugin Execution Failed: {}", e))?;
+                crate::core::output::log::error(format!("Plugin Execution Failed: {}", e))?;
             }
         }
 
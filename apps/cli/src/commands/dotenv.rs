@@ -0,0 +1,100 @@
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+pub struct DotenvCommand {
+    #[command(subcommand)]
+    pub action: DotenvAction,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DotenvAction {
+    /// Write the project's effective environment as a `.env` file
+    Export {
+        /// File to write (defaults to stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Include a service's own env
+        #[arg(long)]
+        service: Option<String>,
+
+        /// Write secret values instead of leaving them out
+        #[arg(long)]
+        include_secrets: bool,
+
+        /// Path to the project root
+        #[arg(long, short)]
+        project_root: Option<PathBuf>,
+    },
+}
+
+impl DotenvCommand {
+    pub async fn execute(self) -> Result<()> {
+        match self.action {
+            DotenvAction::Export {
+                output,
+                service,
+                include_secrets,
+                project_root,
+            } => {
+                let root = project_root
+                    .unwrap_or_else(|| std::env::current_dir().unwrap_or(PathBuf::from(".")));
+                let environment = crate::core::environment::ProjectEnvironment::load(&root)?;
+                let env = environment.resolve(service.as_deref())?;
+
+                // Only what the project defines; the inherited process env stays out.
+                let keys: BTreeSet<String> = environment
+                    .layers(service.as_deref())?
+                    .into_iter()
+                    .flat_map(|layer| layer.into_keys())
+                    .collect();
+
+                let mut vars = Vec::new();
+                let mut withheld = Vec::new();
+                for key in &keys {
+                    let Some(value) = env.get(key) else {
+                        continue;
+                    };
+                    if !include_secrets && env.mask(value) != value {
+                        withheld.push(key.as_str());
+                        continue;
+                    }
+                    vars.push((key.as_str(), value));
+                }
+
+                let written = vars.len();
+                let mut content = env_manifest::to_dotenv(vars);
+                for key in &withheld {
+                    content.push_str(&format!(
+                        "# {} holds a secret; pass --include-secrets to write it\n",
+                        key
+                    ));
+                }
+
+                match output {
+                    Some(path) => {
+                        std::fs::write(&path, &content)
+                            .with_context(|| format!("Failed to write {}", path.display()))?;
+                        crate::core::output::log::success(format!(
+                            "Wrote {} variable(s) to {}",
+                            written,
+                            console::style(path.display()).bold()
+                        ))?;
+                        if !withheld.is_empty() {
+                            crate::core::output::log::warning(format!(
+                                "Left out secret(s): {}",
+                                withheld.join(", ")
+                            ))?;
+                        }
+                    }
+                    None => print!("{}", content),
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use domain::dependency::{ChannelIndex, ReleaseIndex};
+use env_manifest::{Ecosystem, VersionConstraint, VersionRange};
+
+#[derive(Parser, Debug)]
+pub struct ImpactCommand {
+    /// Tool and version being replaced, e.g. `node@18`
+    pub target: String,
+
+    /// Version replacing it; projects that also accept this one are unaffected
+    #[arg(long)]
+    pub to: Option<String>,
+
+    /// Print the affected projects as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+impl ImpactCommand {
+    pub async fn execute(self) -> Result<()> {
+        let (tool, version) = self
+            .target
+            .split_once('@')
+            .filter(|(tool, version)| !tool.is_empty() && !version.is_empty())
+            .context("Expected <tool>@<version>, e.g. node@18")?;
+
+        // The registry's channels as last fetched; `impact` itself stays offline.
+        let index = ReleaseIndex::default_path()
+            .map(|path| ReleaseIndex::open(&path))
+            .unwrap_or_default();
+        let channels = ChannelIndex::new().with_releases(&index);
+        let ecosystem = Ecosystem::for_dependency(tool, None);
+        let range = |raw: &str| -> Result<VersionRange> {
+            Ok(VersionConstraint::parse(raw)?.resolve(tool, ecosystem, &channels)?)
+        };
+        let replaced = range(version)?;
+        let replacement = self.to.as_deref().map(range).transpose()?;
+
+        let index = crate::core::project_index::load()?;
+        let broken = index.broken_by(tool, &replaced, replacement.as_ref(), &channels);
+
+        if self.json {
+            let projects: Vec<_> = broken
+                .iter()
+                .map(|dependent| {
+                    serde_json::json!({
+                        "name": dependent.project.name,
+                        "root": dependent.root,
+                        "requirement": dependent.requirement,
+                    })
+                })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&projects)?);
+            return Ok(());
+        }
+
+        let users = index.dependents(tool).len();
+        if broken.is_empty() {
+            eprintln!(
+                "{} No registered project would break ({} of {} use {})",
+                console::style("✔").green().bold(),
+                users,
+                index.projects().count(),
+                tool
+            );
+            return Ok(());
+        }
+
+        eprintln!(
+            "{} {} of {} project(s) using {} would break if {}@{} were replaced{}:\n",
+            console::style("!").yellow().bold(),
+            broken.len(),
+            users,
+            tool,
+            tool,
+            version,
+            self.to
+                .as_ref()
+                .map(|to| format!(" by {}", to))
+                .unwrap_or_default()
+        );
+        for dependent in &broken {
+            eprintln!(
+                "  {} {} {}",
+                console::style(&dependent.project.name).bold(),
+                console::style(format!("requires {}", dependent.requirement)).yellow(),
+                console::style(dependent.root.display()).dim()
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use env_manifest::{ManifestEditor, ManifestFormat, PinImport, CURRENT_SCHEMA_VERSION};
+use std::path::PathBuf;
+
+/// Dependency sections an imported tool may already be declared in.
+const SECTIONS: &[&str] = &[
+    "dependencies",
+    "dev-dependencies",
+    "test-dependencies",
+    "build-dependencies",
+];
+
+#[derive(Parser, Debug)]
+pub struct ImportCommand {
+    /// Directory holding the pin files (.nvmrc, .tool-versions, go.mod, ...)
+    #[arg(long, short)]
+    pub project_root: Option<PathBuf>,
+
+    /// Replace versions already declared in the manifest
+    #[arg(long)]
+    pub overwrite: bool,
+
+    /// Show what would be imported without writing the manifest
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+impl ImportCommand {
+    pub async fn execute(self) -> Result<()> {
+        let root = self
+            .project_root
+            .unwrap_or_else(|| std::env::current_dir().unwrap_or(PathBuf::from(".")));
+        let import = PinImport::scan(&root)
+            .with_context(|| format!("Failed to read pin files in {}", root.display()))?;
+
+        for (file, reason) in &import.skipped {
+            crate::core::output::log::warning(format!("{}: skipped {}", file, reason))?;
+        }
+        for conflict in import.conflicts() {
+            crate::core::output::log::warning(format!(
+                "{}: {} wants {} but {} pins {}; keeping {}",
+                console::style(conflict.tool).bold(),
+                conflict.other.source,
+                conflict.other.version,
+                conflict.chosen.source,
+                conflict.chosen.version,
+                conflict.chosen.version
+            ))?;
+        }
+
+        let chosen = import.chosen();
+        if chosen.is_empty() {
+            crate::core::output::log::info(format!("No version pins found in {}", root.display()))?;
+            return Ok(());
+        }
+
+        let (mut editor, created) = match crate::utils::loader::find_manifest(&root) {
+            Ok(path) => (ManifestEditor::open(&path)?, false),
+            Err(_) => {
+                let mut editor = ManifestEditor::new("", ManifestFormat::Toml)?;
+                editor.set(&["schema-version"], CURRENT_SCHEMA_VERSION)?;
+                let name = std::fs::canonicalize(&root)
+                    .ok()
+                    .and_then(|p| p.file_name().map(|n| n.to_string_lossy().into_owned()))
+                    .unwrap_or_else(|| "my-env".to_string());
+                editor.set(&["project", "name"], name)?;
+                editor.set(&["project", "version"], "0.1.0")?;
+                editor.path = Some(root.join("env.toml"));
+                (editor, true)
+            }
+        };
+
+        let mut lines = Vec::new();
+        let mut changed = created;
+        for (tool, pin) in &chosen {
+            let declared = SECTIONS
+                .iter()
+                .find(|section| editor.contains(&[section, tool]));
+            let section = match declared {
+                Some(section) => {
+                    let current = editor
+                        .get(&[section, tool, "version"])
+                        .or_else(|| editor.get(&[section, tool]));
+                    if current.as_ref().and_then(|v| v.as_str()) == Some(pin.version.as_str()) {
+                        lines.push(format!(
+                            "{} {} {}",
+                            tool,
+                            pin.version,
+                            console::style("(already declared)").dim()
+                        ));
+                        continue;
+                    }
+                    if !self.overwrite {
+                        lines.push(format!(
+                            "{} {}",
+                            tool,
+                            console::style(format!(
+                                "kept {}.{} ({} wants {}; pass --overwrite)",
+                                section, tool, pin.source, pin.version
+                            ))
+                            .dim()
+                        ));
+                        continue;
+                    }
+                    *section
+                }
+                None => "dependencies",
+            };
+            editor.set_dependency(&[section], tool, pin.version.as_str())?;
+            changed = true;
+            lines.push(format!(
+                "{} {} {}",
+                tool,
+                pin.version,
+                console::style(format!("({})", pin.source)).dim()
+            ));
+        }
+        crate::core::output::note("Imported pins", lines.join("\n"))?;
+
+        let path = editor.path.clone().unwrap_or_default();
+        if !changed {
+            crate::core::output::log::info(format!("{} is already up to date", path.display()))?;
+            return Ok(());
+        }
+        if self.dry_run {
+            crate::core::output::log::info(format!("Would write {}", path.display()))?;
+            return Ok(());
+        }
+
+        crate::utils::loader::save_manifest(&editor)?;
+        crate::core::output::log::success(format!(
+            "{} {}",
+            if created { "Created" } else { "Updated" },
+            console::style(path.display()).bold()
+        ))?;
+        Ok(())
+    }
+}
@@ -36,11 +36,11 @@ impl InitCommand {
             _ => anyhow::bail!("Unsupported language: {}", plugin),
         };
 
-        cliclack::log::step(format!("Initializing {} project...", plugin))?;
+        crate::core::output::log::step(format!("Initializing {} project...", plugin))?;
         let cwd = std::env::current_dir()?;
         adapter.scaffold(&cwd, &self.name).await?;
 
-        cliclack::outro(format!("Initialized {} plugin project!", plugin))?;
+        crate::core::output::outro(format!("Initialized {} plugin project!", plugin))?;
 
         Ok(())
     }
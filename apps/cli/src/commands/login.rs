@@ -1,6 +1,7 @@
+use crate::core::output::{log, outro};
 use anyhow::{Context, Result};
 use clap::Args;
-use cliclack::{intro, log, outro, spinner};
+use cliclack::{intro, spinner};
 use keyring::Entry;
 use reqwest::Client;
 use shared::dto::{AuthDeviceResponse, TokenResponse};
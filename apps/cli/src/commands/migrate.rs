@@ -0,0 +1,58 @@
+use anyhow::Result;
+use clap::Parser;
+use env_manifest::{ManifestEditor, ManifestMigrator};
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+pub struct MigrateCommand {
+    /// Path to the environment file (env.toml/json/yaml)
+    #[arg(short, long)]
+    pub path: Option<PathBuf>,
+
+    /// Show the steps that would run without rewriting the file
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+impl MigrateCommand {
+    pub async fn execute(self) -> Result<()> {
+        let path = match &self.path {
+            Some(p) => p.clone(),
+            None => crate::utils::loader::find_manifest(&std::env::current_dir()?)?,
+        };
+        let mut editor = ManifestEditor::open(&path)?;
+        let report = ManifestMigrator::migrate(&mut editor)?;
+
+        if report.is_empty() {
+            crate::core::output::log::info(format!(
+                "{} is already at schema version {}",
+                path.display(),
+                report.to
+            ))?;
+            return Ok(());
+        }
+
+        for step in &report.applied {
+            crate::core::output::log::step(*step)?;
+        }
+
+        if self.dry_run {
+            crate::core::output::log::info(format!(
+                "Would upgrade {} from schema version {} to {}",
+                path.display(),
+                report.from,
+                report.to
+            ))?;
+            return Ok(());
+        }
+
+        crate::utils::loader::save_manifest(&editor)?;
+        crate::core::output::log::success(format!(
+            "Upgraded {} from schema version {} to {}",
+            console::style(path.display()).bold(),
+            report.from,
+            report.to
+        ))?;
+        Ok(())
+    }
+}
@@ -1,11 +1,21 @@
+pub mod add;
 pub mod bundle;
+pub mod check;
 pub mod dev;
 pub mod doctor;
+pub mod dotenv;
+pub mod impact;
+pub mod import;
 pub mod init;
 pub mod login;
+pub mod migrate;
 pub mod publish;
+pub mod remove;
 pub mod resolve;
 pub mod run;
+pub mod secrets;
+pub mod set;
 pub mod shell;
 pub mod shim;
+pub mod show;
 pub mod whoami;
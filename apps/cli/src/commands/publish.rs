@@ -83,7 +83,7 @@ impl PublishCommand {
         let mut ghcr_token = if let Ok(t) = std::env::var("GITHUB_TOKEN") {
             Some(t)
         } else {
-            cliclack::log::step("Fetching GHCR token from server...")?;
+            crate::core::output::log::step("Fetching GHCR token from server...")?;
             let api_url =
                 std::env::var("REGISTRY_API_URL").unwrap_or("http://localhost:3000".to_string());
             let client = reqwest::Client::new();
@@ -104,11 +104,11 @@ impl PublishCommand {
         };
 
         if ghcr_token.is_none() {
-            cliclack::log::warning("GitHub Container Registry (GHCR) requires a Personal Access Token (PAT) for direct push.")?;
-            cliclack::log::info("Why? GitHub restricts third-party Apps from pushing directly to namespaces for security.")?;
+            crate::core::output::log::warning("GitHub Container Registry (GHCR) requires a Personal Access Token (PAT) for direct push.")?;
+            crate::core::output::log::info("Why? GitHub restricts third-party Apps from pushing directly to namespaces for security.")?;
 
             let shortcut_url = "https://github.com/settings/tokens/new?scopes=write:packages&description=EnvArchitect+Publish+Token";
-            cliclack::log::info(format!(
+            crate::core::output::log::info(format!(
                 "Please create a 'Classic PAT' with 'write:packages' scope here:\n  {}",
                 shortcut_url
             ))?;
@@ -135,9 +135,9 @@ impl PublishCommand {
                 .await?;
 
             if res.status().is_success() {
-                cliclack::log::success("PAT securely stored on server.")?;
+                crate::core::output::log::success("PAT securely stored on server.")?;
             } else {
-                cliclack::log::error(
+                crate::core::output::log::error(
                     "Failed to store PAT on server. Using it for this session only.",
                 )?;
             }
@@ -167,7 +167,7 @@ impl PublishCommand {
         )
         .await?;
 
-        cliclack::outro("Publishing complete! 🎉")?;
+        crate::core::output::outro("Publishing complete! 🎉")?;
         Ok(())
     }
 
@@ -265,13 +265,13 @@ impl PublishCommand {
         let auth = if let Some(t) = ghcr_token {
             RegistryAuth::Basic(username.to_string(), t)
         } else {
-            cliclack::log::warning(
+            crate::core::output::log::warning(
                 "No GITHUB_TOKEN or server-provided token. Auth will likely fail for GHCR.",
             )?;
             RegistryAuth::Anonymous
         };
 
-        cliclack::log::step(format!("Pushing to {}...", target_url))?;
+        crate::core::output::log::step(format!("Pushing to {}...", target_url))?;
 
         let reference: Reference = target_url.parse().context("Invalid registry URL")?;
         let client = Client::new(oci_client::client::ClientConfig::default());
@@ -400,7 +400,7 @@ impl PublishCommand {
         {
             Ok(_res) => Ok(target_url),
             Err(e) => {
-                cliclack::log::error(format!("Failed to push to registry: {}", e))?;
+                crate::core::output::log::error(format!("Failed to push to registry: {}", e))?;
                 Err(e.into())
             }
         }
@@ -416,7 +416,7 @@ impl PublishCommand {
     ) -> Result<()> {
         use crate::keys::{generate_or_load_signing_key, sign_bytes_base64};
 
-        cliclack::log::step("Preparing upload to registry...")?;
+        crate::core::output::log::step("Preparing upload to registry...")?;
 
         // 1. Load Signing Key and Sign Artifact
         let signing_key = generate_or_load_signing_key()
@@ -448,7 +448,7 @@ impl PublishCommand {
         // 4. Build Multipart Form
         let client = reqwest::Client::new();
 
-        cliclack::log::step(format!("Publishing to {}...", api_url))?;
+        crate::core::output::log::step(format!("Publishing to {}...", api_url))?;
 
         let metadata_json = serde_json::to_string(&payload)?;
 
@@ -483,27 +483,30 @@ impl PublishCommand {
 
         if res.status().is_success() {
             let response: serde_json::Value = res.json().await?;
-            cliclack::log::success("Published successfully!")?;
+            crate::core::output::log::success("Published successfully!")?;
 
             if let Some(id) = response.get("id") {
-                cliclack::log::info(format!("Component ID: {}", id))?;
+                crate::core::output::log::info(format!("Component ID: {}", id))?;
             }
             if let Some(msg) = response.get("message") {
                 let msg_str = msg.as_str().unwrap_or("");
                 if !msg_str.contains("Published successfully") {
-                    cliclack::log::info(format!("{}", msg))?;
+                    crate::core::output::log::info(format!("{}", msg))?;
                 }
             }
 
             // Professional Success Message & Visibility Warning
-            cliclack::log::info("Artifact analysis initiated (Notary Scan).")?;
-            cliclack::log::warning(
+            crate::core::output::log::info("Artifact analysis initiated (Notary Scan).")?;
+            crate::core::output::log::warning(
                 "Note: Package is Private by default. Make it Public in GitHub Settings to share.",
             )?;
         } else {
             let status = res.status();
             let error_text = res.text().await.unwrap_or_default();
-            cliclack::log::error(format!("Failed to publish ({}): {}", status, error_text))?;
+            crate::core::output::log::error(format!(
+                "Failed to publish ({}): {}",
+                status, error_text
+            ))?;
             return Err(anyhow::anyhow!("Publish failed"));
         }
 
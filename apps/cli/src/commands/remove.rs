@@ -0,0 +1,94 @@
+use anyhow::Result;
+use clap::Parser;
+use env_manifest::ManifestEditor;
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+pub struct RemoveCommand {
+    /// Dependencies to remove
+    #[arg(required = true)]
+    pub packages: Vec<String>,
+
+    /// Only remove from dev-dependencies
+    #[arg(long)]
+    pub dev: bool,
+
+    /// Only remove from test-dependencies
+    #[arg(long)]
+    pub test: bool,
+
+    /// Only remove from build-dependencies
+    #[arg(long)]
+    pub build: bool,
+
+    /// Only remove from a dependency group
+    #[arg(long)]
+    pub group: Option<String>,
+
+    /// Path to the environment file (env.toml/json/yaml)
+    #[arg(short, long)]
+    pub path: Option<PathBuf>,
+}
+
+impl RemoveCommand {
+    pub async fn execute(self) -> Result<()> {
+        let path = match &self.path {
+            Some(p) => p.clone(),
+            None => crate::utils::loader::find_manifest(&std::env::current_dir()?)?,
+        };
+        let manifest = crate::utils::loader::load_manifest(&path)?;
+        let mut editor = ManifestEditor::open(&path)?;
+
+        // Without a filter, look in every section and group.
+        let restricted = self.dev || self.test || self.build || self.group.is_some();
+        let mut sections: Vec<Vec<&str>> = Vec::new();
+        if !restricted {
+            sections.push(vec!["dependencies"]);
+        }
+        if self.dev || !restricted {
+            sections.push(vec!["dev-dependencies"]);
+        }
+        if self.test || !restricted {
+            sections.push(vec!["test-dependencies"]);
+        }
+        if self.build || !restricted {
+            sections.push(vec!["build-dependencies"]);
+        }
+        match &self.group {
+            Some(group) => sections.push(vec!["group", group.as_str(), "dependencies"]),
+            None if !restricted => {
+                let mut groups: Vec<&String> = manifest.group.keys().collect();
+                groups.sort();
+                sections.extend(
+                    groups
+                        .into_iter()
+                        .map(|g| vec!["group", g.as_str(), "dependencies"]),
+                );
+            }
+            None => {}
+        }
+
+        for name in &self.packages {
+            let mut removed_from = Vec::new();
+            for section in &sections {
+                let mut key = section.clone();
+                key.push(name);
+                if editor.remove(&key)? {
+                    removed_from.push(section.join("."));
+                }
+            }
+
+            if removed_from.is_empty() {
+                anyhow::bail!("'{}' is not a dependency of this project", name);
+            }
+            crate::core::output::log::success(format!(
+                "Removed {} from {}",
+                console::style(name).bold(),
+                removed_from.join(", ")
+            ))?;
+        }
+
+        crate::utils::loader::save_manifest(&editor)?;
+        Ok(())
+    }
+}
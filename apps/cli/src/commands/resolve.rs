@@ -1,13 +1,15 @@
 use anyhow::{Context, Result};
 use clap::Parser;
 use serde_json;
-use std::path::PathBuf;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 use wasmtime::component::{Component, Linker};
 use wasmtime::{Config, Engine, Store};
 
 use crate::host::bindings::Plugin;
 use crate::host::state::HostState;
-use domain::dependency::ConsensusEngine;
+use domain::dependency::{ConsensusEngine, ReleaseIndex};
+use domain::security::tuf::RepositoryVerifier;
 use domain::security::VerificationService;
 use domain::system::StoreManager;
 
@@ -27,6 +29,23 @@ pub struct ResolveCommand {
     /// Skip confirmation
     #[arg(long, short = 'y')]
     pub yes: bool,
+
+    /// Ignore cached tool versions and probe every binary again
+    #[arg(long)]
+    pub rescan: bool,
+
+    /// Settle conflicts from the resolution policy instead of prompting
+    /// (implied when stdin is not a terminal)
+    #[arg(long)]
+    pub non_interactive: bool,
+
+    /// Resolution policy file (TOML, JSON or YAML) overriding the manifest's
+    #[arg(long)]
+    pub policy: Option<PathBuf>,
+
+    /// Write the decisions taken without prompting to this file as JSON
+    #[arg(long)]
+    pub decisions: Option<PathBuf>,
 }
 
 impl ResolveCommand {
@@ -44,9 +63,12 @@ impl ResolveCommand {
             console::style("v0.1.0").dim()
         ))?;
 
-        if !self.yes {
+        let interactive = !self.non_interactive && std::io::stdin().is_terminal();
+        let policy_file = self.policy.as_deref().map(load_policy).transpose()?;
+
+        if !self.yes && interactive {
             if !cliclack::confirm("Start environment resolution?").interact()? {
-                cliclack::log::error("Operation cancelled.")?;
+                crate::core::output::log::error("Operation cancelled.")?;
                 return Ok(());
             }
         }
@@ -142,7 +164,23 @@ impl ResolveCommand {
         let plugin = Plugin::instantiate_async(&mut store, &component, &linker).await?;
 
         spinner.start("Discovering system tools...");
-        let mut registry = domain::system::InstalledToolsRegistry::new();
+        // Families: built-ins, then the user's, then the project's.
+        let user_config = crate::core::user_config::load()?;
+        let mut registry =
+            domain::system::InstalledToolsRegistry::new().with_families(&user_config.families);
+        let project = crate::utils::loader::find_and_load_manifest(&absolute_root)
+            .ok()
+            .map(|(_, manifest)| manifest);
+        if let Some(project) = &project {
+            registry = registry.with_families(&project.families);
+        }
+        if let Some(path) = domain::system::ScanCache::default_path() {
+            let mut cache = domain::system::ScanCache::open(path, domain::system::DEFAULT_SCAN_TTL);
+            if self.rescan {
+                cache.clear();
+            }
+            registry = registry.with_cache(cache);
+        }
         let _ = registry.scan();
         let mut system_tools = std::collections::HashMap::new();
 
@@ -204,48 +242,138 @@ impl ResolveCommand {
                 let valid_json: serde_json::Value = serde_json::from_str(&output.plan_json)
                     .unwrap_or_else(|_| serde_json::Value::String(output.plan_json));
 
-                cliclack::log::info("Install Plan:")?;
+                crate::core::output::log::info("Install Plan:")?;
 
-                cliclack::log::info(serde_json::to_string_pretty(&valid_json)?)?;
+                crate::core::output::log::info(serde_json::to_string_pretty(&valid_json)?)?;
 
                 if let Some(manifest_node) = valid_json.get("manifest").cloned() {
                     if let Ok(manifest) = serde_json::from_value::<env_manifest::EnhancedManifest>(
                         manifest_node.clone(),
                     ) {
                         let platform = domain::system::PlatformDetector::detect();
-                        let mut registry = domain::system::InstalledToolsRegistry::new();
-
-                        let _ = registry.scan();
-                        let resolver =
-                            domain::intelligence::ConflictResolver::new(platform, registry);
-
-                        cliclack::log::step("Analyzing for system conflicts (V2 Intelligence)...")?;
+                        // The project's families are registered already; only add what
+                        // the plan declares on top of them.
+                        let plan_families: std::collections::HashMap<_, _> = manifest
+                            .families
+                            .iter()
+                            .filter(|(name, _)| {
+                                !project
+                                    .as_ref()
+                                    .is_some_and(|p| p.families.contains_key(*name))
+                            })
+                            .map(|(name, family)| (name.clone(), family.clone()))
+                            .collect();
+                        let registry = registry.with_families(&plan_families);
+                        let mut metrics =
+                            domain::intelligence::MetricsDetector::new(platform.clone())
+                                .with_ecosystem_lookups(user_config.ecosystem_sizes);
+                        if let Some(path) = domain::system::SizeCache::default_path() {
+                            metrics = metrics.with_cache(domain::system::SizeCache::open(path));
+                        }
+                        let index = release_index().await;
+                        let channels =
+                            domain::dependency::ChannelIndex::new().with_releases(&index);
+                        let managers = manifest
+                            .dependencies
+                            .iter()
+                            .filter_map(|(name, spec)| Some((name.clone(), spec.manager()?)))
+                            .collect();
+                        let mut resolver =
+                            domain::intelligence::ConflictResolver::new(platform, registry)
+                                .with_index(index)
+                                .with_channels(channels)
+                                .with_managers(managers)
+                                .with_metrics(metrics)
+                                .with_targets_url(
+                                    url::Url::parse(crate::constants::REGISTRY_URL)?
+                                        .join("/targets/")?,
+                                    domain::security::tuf::consistent_snapshot(Path::new(
+                                        crate::constants::TUF_ROOT,
+                                    )),
+                                );
+                        if let Ok(projects) = crate::core::project_index::load() {
+                            resolver = resolver.with_projects(projects);
+                        }
+                        if let Some(path) = domain::system::InstallHistory::default_path() {
+                            resolver =
+                                resolver.with_history(domain::system::InstallHistory::open(path));
+                        }
+                        // Weights: user config, then the plan's, then the project's.
+                        let scoring = [
+                            user_config.scoring.as_ref(),
+                            manifest.scoring.as_ref(),
+                            project.as_ref().and_then(|p| p.scoring.as_ref()),
+                        ]
+                        .into_iter()
+                        .flatten()
+                        .fold(env_manifest::ScoringConfig::default(), |acc, layer| {
+                            acc.merge(layer)
+                        });
+                        resolver = resolver.with_scoring(domain::intelligence::ScoringModel::new(
+                            scoring.weights(),
+                        ));
+
+                        // Policy: --policy, then the project's, then the plan's.
+                        let policy = policy_file
+                            .clone()
+                            .or_else(|| project.as_ref().and_then(|p| p.resolution_policy.clone()))
+                            .or_else(|| manifest.resolution_policy.clone())
+                            .unwrap_or_default();
+                        let mut decisions = Vec::new();
+
+                        crate::core::output::log::step(
+                            "Analyzing for system conflicts (V2 Intelligence)...",
+                        )?;
 
                         for (tool_name, dep_spec) in &manifest.dependencies {
-                            use env_manifest::DependencySpec;
-                            let version_req = match dep_spec {
-                                DependencySpec::Simple(req) => req.clone(),
-                                DependencySpec::Detailed(d) => d.version.clone(),
-                            };
-
                             if let Some(conflict) = resolver.detect_conflicts(
                                 tool_name,
-                                &version_req,
+                                dep_spec.constraint(),
                                 "current-project",
                             ) {
-                                let recommendations = resolver.resolve(&conflict)?;
-
-                                if !recommendations.is_empty() {
-                                    cliclack::log::warning(format!(
+                                let ranked = resolver.evaluate(&conflict)?;
+
+                                if !interactive {
+                                    let (decision, chosen) = domain::intelligence::Decision::choose(
+                                        &policy, tool_name, &conflict, &ranked,
+                                    );
+                                    match chosen {
+                                        Some(idx) => crate::core::output::log::success(format!(
+                                            "{}: {} ({} = {})",
+                                            tool_name,
+                                            ranked[idx].recommendation.action,
+                                            decision.rule_source,
+                                            decision.rule
+                                        ))?,
+                                        None => crate::core::output::log::warning(format!(
+                                            "{}: {}",
+                                            tool_name,
+                                            decision.reason.as_deref().unwrap_or("skipped")
+                                        ))?,
+                                    }
+                                    decisions.push(decision);
+                                } else if !ranked.is_empty() {
+                                    crate::core::output::log::warning(format!(
                                         "⚠️  Conflict Detected: {}",
                                         console::style(tool_name).bold().yellow()
                                     ))?;
+                                    crate::core::output::note(
+                                        format!("Trade-offs for {}", tool_name),
+                                        trade_off_summary(&ranked),
+                                    )?;
 
                                     // Interactive Resolution Selector
-                                    let items: Vec<(usize, &str, &str)> = recommendations
+                                    let hints: Vec<String> = ranked
+                                        .iter()
+                                        .map(|r| format!("cost {:.2}", r.cost))
+                                        .collect();
+                                    let items: Vec<(usize, &str, &str)> = ranked
                                         .iter()
+                                        .zip(&hints)
                                         .enumerate()
-                                        .map(|(i, r)| (i, r.action.as_str(), ""))
+                                        .map(|(i, (r, hint))| {
+                                            (i, r.recommendation.action.as_str(), hint.as_str())
+                                        })
                                         .collect();
 
                                     let selection_idx = cliclack::select(format!(
@@ -255,13 +383,13 @@ impl ResolveCommand {
                                     .items(&items)
                                     .interact()?;
 
-                                    let chosen_rec = &recommendations[selection_idx];
+                                    let chosen_rec = &ranked[selection_idx].recommendation;
 
-                                    cliclack::log::success(format!(
+                                    crate::core::output::log::success(format!(
                                         "Selected: {}",
                                         chosen_rec.action
                                     ))?;
-                                    cliclack::log::info(format!(
+                                    crate::core::output::log::info(format!(
                                         "Will perform strategy: {:?}",
                                         chosen_rec.strategy
                                     ))?;
@@ -269,6 +397,34 @@ impl ResolveCommand {
                             }
                         }
 
+                        if !interactive {
+                            if let Some(path) = &self.decisions {
+                                let record = serde_json::json!({ "decisions": decisions });
+                                std::fs::write(path, serde_json::to_string_pretty(&record)?)
+                                    .with_context(|| {
+                                        format!("Failed to write {}", path.display())
+                                    })?;
+                                crate::core::output::log::success(format!(
+                                    "Wrote {} decision(s) to {}",
+                                    decisions.len(),
+                                    console::style(path.display()).bold()
+                                ))?;
+                            }
+
+                            let failed = decisions
+                                .iter()
+                                .filter(|d| {
+                                    d.outcome == domain::intelligence::DecisionOutcome::Failed
+                                })
+                                .count();
+                            if failed > 0 {
+                                anyhow::bail!(
+                                    "{} conflict(s) failed the resolution policy",
+                                    failed
+                                );
+                            }
+                        }
+
                         let spinner_v2 = cliclack::spinner();
                         spinner_v2.start("Finalizing V2 Sovereign Environment...");
 
@@ -280,16 +436,11 @@ impl ResolveCommand {
                         for (name, spec) in &manifest.dependencies {
                             spinner_v2.start(format!("Shimming {}...", name));
 
-                            let version_req = match spec {
-                                env_manifest::DependencySpec::Simple(req) => req.to_string(),
-                                env_manifest::DependencySpec::Detailed(details) => {
-                                    details.version.to_string()
-                                }
-                            };
-                            let version = if version_req == "*" {
+                            let constraint = spec.constraint();
+                            let version = if constraint.is_any() {
                                 "latest".to_string()
                             } else {
-                                version_req
+                                constraint.to_string()
                             };
 
                             let hash = "abc123456789";
@@ -323,20 +474,7 @@ impl ResolveCommand {
                                 }
                             }
 
-                            let shim_path = shims_dir.join(name);
-                            let shim_content = format!(
-                                "#!/bin/bash\nexec env-architect shim {} -- \"$@\"\n",
-                                name
-                            );
-                            std::fs::write(&shim_path, shim_content)?;
-
-                            #[cfg(unix)]
-                            {
-                                use std::os::unix::fs::PermissionsExt;
-                                let mut perms = std::fs::metadata(&shim_path)?.permissions();
-                                perms.set_mode(0o755);
-                                std::fs::set_permissions(&shim_path, perms)?;
-                            }
+                            write_shim(&shims_dir, name, None)?;
                         }
                         spinner_v2.stop("Sovereign environment ready.");
 
@@ -346,33 +484,36 @@ impl ResolveCommand {
                         let drifts = ConsensusEngine::detect_drift(&consensus, &local_tools);
 
                         if !drifts.is_empty() {
-                            cliclack::log::warning(
+                            crate::core::output::log::warning(
                                 "⚠️  Environment Drift Detected (Team vs Local):",
                             )?;
                             for drift in drifts {
                                 let desc = drift.description();
-                                cliclack::log::info(format!(
+                                crate::core::output::log::info(format!(
                                     "  {} {}",
                                     console::style("!").yellow(),
                                     desc
                                 ))?;
                             }
 
-                            if cliclack::confirm("Harmonize local environment with team consensus?")
+                            if interactive
+                                && cliclack::confirm(
+                                    "Harmonize local environment with team consensus?",
+                                )
                                 .interact()?
                             {
-                                cliclack::log::info("Harmonizing tools...")?;
+                                crate::core::output::log::info("Harmonizing tools...")?;
                             }
                         }
 
-                        cliclack::outro(format!(
+                        crate::core::output::outro(format!(
                             "Project active. Use '{}' to enter environment.",
                             console::style("architect shell").bold()
                         ))?;
 
                         if let Some(intel) = manifest.intelligence {
                             if !intel.proposed_actions.is_empty() {
-                                cliclack::log::warning(
+                                crate::core::output::log::warning(
                                     "Environment conflicts detected. Proposed resolutions:",
                                 )?;
 
@@ -382,7 +523,7 @@ impl ResolveCommand {
                                             manager,
                                             command,
                                         } => {
-                                            cliclack::log::info(format!(
+                                            crate::core::output::log::info(format!(
                                                 "  🔧 {}: {} {}",
                                                 console::style(manager).green(),
                                                 console::style("Run").dim(),
@@ -392,8 +533,9 @@ impl ResolveCommand {
                                         env_manifest::ResolutionAction::AutoShim {
                                             url,
                                             binary_name,
+                                            ..
                                         } => {
-                                            cliclack::log::info(format!(
+                                            crate::core::output::log::info(format!(
                                                 "  📥 {}: {} from {}",
                                                 console::style(binary_name).green(),
                                                 console::style("Auto-shim").dim(),
@@ -404,7 +546,7 @@ impl ResolveCommand {
                                             path,
                                             ..
                                         } => {
-                                            cliclack::log::info(format!(
+                                            crate::core::output::log::info(format!(
                                                 "  📄 {}: {}",
                                                 console::style("Config").green(),
                                                 console::style(path).dim()
@@ -414,26 +556,33 @@ impl ResolveCommand {
                                             message,
                                             ..
                                         } => {
-                                            cliclack::log::info(format!("  ℹ️  {}", message))?;
+                                            crate::core::output::log::info(format!(
+                                                "  ℹ️  {}",
+                                                message
+                                            ))?;
                                         }
                                     }
                                 }
-                                cliclack::log::info(format!(
+                                crate::core::output::log::info(format!(
                                     "Debug: Actions count: {}",
                                     intel.proposed_actions.len()
                                 ))?;
 
-                                if cliclack::confirm("Apply recommended resolutions?")
-                                    .initial_value(true)
-                                    .interact()?
-                                {
+                                let apply = if interactive {
+                                    cliclack::confirm("Apply recommended resolutions?")
+                                        .initial_value(true)
+                                        .interact()?
+                                } else {
+                                    self.yes
+                                };
+                                if apply {
                                     for action in &intel.proposed_actions {
                                         match action {
                                             env_manifest::ResolutionAction::ManagedInstall {
                                                 manager,
                                                 command,
                                             } => {
-                                                cliclack::log::info(format!(
+                                                crate::core::output::log::info(format!(
                                                     "🚀 Executing {}...",
                                                     manager
                                                 ))?;
@@ -443,7 +592,7 @@ impl ResolveCommand {
                                                     .status()?;
 
                                                 if !status.success() {
-                                                    cliclack::log::error(format!(
+                                                    crate::core::output::log::error(format!(
                                                         "Failed to execute {}",
                                                         command
                                                     ))?;
@@ -453,29 +602,50 @@ impl ResolveCommand {
                                                 path,
                                                 patch,
                                             } => {
-                                                cliclack::log::info(format!(
-                                                    "📝 Updating config: {}",
-                                                    path
-                                                ))?;
-                                                cliclack::log::info(format!(
-                                                    "Applying patch: {}",
-                                                    patch
-                                                ))?;
-
-                                                cliclack::log::warning("Patch application not yet fully implemented. Please verify env.toml manually.")?;
+                                                if let Err(e) = apply_config_update(
+                                                    &absolute_root,
+                                                    path,
+                                                    patch,
+                                                    interactive,
+                                                ) {
+                                                    crate::core::output::log::error(format!(
+                                                        "Failed to update {}: {:#}",
+                                                        path, e
+                                                    ))?;
+                                                }
                                             }
 
                                             env_manifest::ResolutionAction::AutoShim {
                                                 url,
                                                 binary_name,
+                                                version,
+                                                sha256,
                                             } => {
-                                                cliclack::log::warning(format!("Skipping AutoShim for {} (Downloading from {} not yet supported)", binary_name, url))?;
+                                                let capabilities = project
+                                                    .as_ref()
+                                                    .and_then(|p| p.capabilities.clone())
+                                                    .unwrap_or_default();
+                                                if let Err(e) = apply_auto_shim(
+                                                    &absolute_root,
+                                                    &capabilities,
+                                                    url,
+                                                    binary_name,
+                                                    version,
+                                                    sha256,
+                                                )
+                                                .await
+                                                {
+                                                    crate::core::output::log::error(format!(
+                                                        "Failed to auto-shim {}: {:#}",
+                                                        binary_name, e
+                                                    ))?;
+                                                }
                                             }
                                             env_manifest::ResolutionAction::ManualPrompt {
                                                 message,
                                                 ..
                                             } => {
-                                                cliclack::log::info(format!(
+                                                crate::core::output::log::info(format!(
                                                     "Please manually: {}",
                                                     message
                                                 ))?;
@@ -489,19 +659,400 @@ impl ResolveCommand {
                 }
 
                 if let Some(state) = output.state {
-                    cliclack::note("Opaque State", state)?;
+                    crate::core::output::note("Opaque State", state)?;
                 }
             }
             Ok(Err(e)) => {
                 spinner.error("Plugin Logic Error");
-                cliclack::log::error(format!("Error: {}", e))?;
+                crate::core::output::log::error(format!("Error: {}", e))?;
             }
             Err(e) => {
                 spinner.error("Host/Runtime Error");
-                cliclack::log::error(format!("Error: {}", e))?;
+                crate::core::output::log::error(format!("Error: {}", e))?;
             }
         }
 
         Ok(())
     }
 }
+
+/// One line per option: its numbers, then why it ranks where it does.
+fn trade_off_summary(ranked: &[domain::intelligence::ScoredRecommendation]) -> String {
+    ranked
+        .iter()
+        .enumerate()
+        .map(|(i, scored)| {
+            let rec = &scored.recommendation;
+            let why = if i == 0 {
+                "best for your weights".to_string()
+            } else if let Some(better) = scored.dominated_by {
+                format!("dominated by '{}'", ranked[better].recommendation.action)
+            } else {
+                scored
+                    .trade_off
+                    .clone()
+                    .unwrap_or_else(|| "as good as the best".to_string())
+            };
+            format!(
+                "{} {}\n   {} risk · {}MB · {}s · breaks {} project(s) · {}\n   {}",
+                if scored.is_pareto_optimal() {
+                    "◆"
+                } else {
+                    "◇"
+                },
+                console::style(&rec.action).bold(),
+                rec.risk,
+                rec.estimated_disk_mb,
+                rec.estimated_time_sec,
+                rec.affected_projects,
+                if rec.reversible {
+                    "reversible"
+                } else {
+                    "irreversible"
+                },
+                console::style(why).dim()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A project shim that runs `name` from the store through `env-architect shim`: `version`
+/// when given, otherwise the newest stored version the manifest's constraint allows.
+fn write_shim(shims_dir: &Path, name: &str, version: Option<&semver::Version>) -> Result<()> {
+    std::fs::create_dir_all(shims_dir)?;
+    let shim_path = shims_dir.join(name);
+    let pin = version.map_or(String::new(), |v| format!("--pin {} ", v));
+    let shim_content = format!(
+        "#!/bin/bash\nexec env-architect shim {}{} -- \"$@\"\n",
+        pin, name
+    );
+    std::fs::write(&shim_path, shim_content)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = std::fs::metadata(&shim_path)?.permissions();
+        perms.set_mode(0o755);
+        std::fs::set_permissions(&shim_path, perms)?;
+    }
+    Ok(())
+}
+
+/// Fetch an `auto-shim` artifact into the store, verified against its checksum, and shim
+/// it into the project. Only hosts the project's `network` capability allows are contacted,
+/// redirects included.
+async fn apply_auto_shim(
+    root: &Path,
+    capabilities: &[env_manifest::Capability],
+    url: &str,
+    binary_name: &str,
+    version: &str,
+    sha256: &str,
+) -> Result<()> {
+    let url = url::Url::parse(url).with_context(|| format!("Invalid download URL: {}", url))?;
+    let host = url
+        .host_str()
+        .with_context(|| format!("{} has no host", url))?;
+    anyhow::ensure!(
+        env_manifest::network_allows(capabilities, host),
+        "{} is not allowed by the manifest's `network` capability",
+        host
+    );
+    // All three end up in store paths.
+    anyhow::ensure!(
+        sha256.len() == 64 && sha256.bytes().all(|b| b.is_ascii_hexdigit()),
+        "Invalid sha256 checksum: {}",
+        sha256
+    );
+    anyhow::ensure!(
+        !binary_name.is_empty() && binary_name != ".." && !binary_name.contains(['/', '\\']),
+        "Invalid binary name: {}",
+        binary_name
+    );
+    let version =
+        semver::Version::parse(version).with_context(|| format!("Invalid version: {}", version))?;
+    let sha256 = sha256.to_ascii_lowercase();
+
+    let store = StoreManager::default()?;
+    let shims_dir = root.join(".architect").join("shims");
+    if store
+        .get_executable_path(binary_name, &version.to_string(), &sha256, binary_name)
+        .is_some()
+    {
+        write_shim(&shims_dir, binary_name, Some(&version))?;
+    } else {
+        let download = dirs::home_dir()
+            .context("Could not find home directory")?
+            .join(".architect")
+            .join("cache")
+            .join("downloads")
+            .join(&sha256);
+        let started = std::time::Instant::now();
+        let spinner = cliclack::spinner();
+        spinner.start(format!(
+            "Downloading {} {} from {}...",
+            binary_name, version, host
+        ));
+        let artifact = domain::system::Artifact::new(
+            url.clone(),
+            domain::system::Checksum::Sha256(sha256.clone()),
+        );
+        let capabilities = capabilities.to_vec();
+        if let Err(e) = domain::system::Downloader::new()
+            .with_allowed_hosts(move |host| env_manifest::network_allows(&capabilities, host))
+            .fetch(&artifact, &download)
+            .await
+        {
+            spinner.error(format!("Download of {} failed", binary_name));
+            return Err(e);
+        }
+        let shimmed = shim_artifact(
+            &store,
+            &shims_dir,
+            &download,
+            binary_name,
+            &version,
+            &sha256,
+        );
+        let _ = std::fs::remove_file(&download);
+        if let Err(e) = shimmed {
+            spinner.error(format!("{} {} can't be shimmed", binary_name, version));
+            return Err(e);
+        }
+        if let Some(path) = domain::system::InstallHistory::default_path() {
+            let mut history = domain::system::InstallHistory::open(path);
+            history.record(binary_name, started.elapsed());
+            let _ = history.save();
+        }
+        spinner.stop(format!(
+            "Verified {} {} (sha256 {})",
+            binary_name,
+            version,
+            &sha256[..12]
+        ));
+    }
+
+    crate::core::output::log::success(format!(
+        "Shimmed {} {} into this project",
+        binary_name, version
+    ))?;
+    Ok(())
+}
+
+/// Ingest a verified download into the store and shim that exact version into the project.
+fn shim_artifact(
+    store: &StoreManager,
+    shims_dir: &Path,
+    artifact: &Path,
+    binary_name: &str,
+    version: &semver::Version,
+    sha256: &str,
+) -> Result<()> {
+    store.ingest(
+        binary_name,
+        &version.to_string(),
+        sha256,
+        artifact,
+        binary_name,
+    )?;
+    write_shim(shims_dir, binary_name, Some(version))
+}
+
+/// Apply a `config-update` patch to a file in the project, after showing the diff.
+/// Returns whether the file was written.
+fn apply_config_update(root: &Path, path: &str, patch: &str, interactive: bool) -> Result<bool> {
+    let root = std::fs::canonicalize(root)?;
+    let target = std::fs::canonicalize(root.join(path))
+        .with_context(|| format!("{} does not exist", path))?;
+    anyhow::ensure!(
+        target.starts_with(&root),
+        "Refusing to patch {}: it is outside the project root",
+        target.display()
+    );
+
+    let patch = env_manifest::ConfigPatch::parse(patch)?;
+    let mut editor = env_manifest::ManifestEditor::open(&target)?;
+    let before = editor.to_string();
+    patch.apply(&mut editor)?;
+    let after = editor.to_string();
+
+    if before == after {
+        crate::core::output::log::info(format!("{} is already up to date", path))?;
+        return Ok(false);
+    }
+
+    crate::core::output::note(format!("📝 {}", path), render_diff(&before, &after))?;
+    if interactive
+        && !cliclack::confirm(format!("Write changes to {}?", path))
+            .initial_value(true)
+            .interact()?
+    {
+        crate::core::output::log::info(format!("Left {} unchanged", path))?;
+        return Ok(false);
+    }
+
+    editor.save()?;
+    crate::core::output::log::success(format!("Updated {}", path))?;
+    Ok(true)
+}
+
+/// Changed lines with two lines of context, hunks separated by an ellipsis.
+fn render_diff(before: &str, after: &str) -> String {
+    let diff = similar::TextDiff::from_lines(before, after);
+    let mut lines = Vec::new();
+    for (i, group) in diff.grouped_ops(2).iter().enumerate() {
+        if i > 0 {
+            lines.push(console::style("⋯").dim().to_string());
+        }
+        for change in group.iter().flat_map(|op| diff.iter_changes(op)) {
+            let text = change.value().trim_end_matches('\n');
+            lines.push(match change.tag() {
+                similar::ChangeTag::Delete => {
+                    console::style(format!("- {}", text)).red().to_string()
+                }
+                similar::ChangeTag::Insert => {
+                    console::style(format!("+ {}", text)).green().to_string()
+                }
+                similar::ChangeTag::Equal => {
+                    console::style(format!("  {}", text)).dim().to_string()
+                }
+            });
+        }
+    }
+    lines.join("\n")
+}
+
+/// The registry's published releases: refreshed from its TUF metadata when this machine
+/// trusts a root, otherwise whatever was cached last time (possibly nothing).
+async fn release_index() -> ReleaseIndex {
+    let Some(path) = ReleaseIndex::default_path() else {
+        return ReleaseIndex::new();
+    };
+    // Offline, the cached copy is the best we have.
+    let _ = refresh_release_index(&path).await;
+    ReleaseIndex::open(&path)
+}
+
+async fn refresh_release_index(path: &std::path::Path) -> Result<()> {
+    let tuf_root = PathBuf::from(crate::constants::TUF_ROOT);
+    if !tuf_root.exists() {
+        return Ok(());
+    }
+
+    let registry_url = url::Url::parse(crate::constants::REGISTRY_URL)?;
+    let verifier = RepositoryVerifier::new(
+        &tuf_root,
+        registry_url.join("/metadata")?,
+        registry_url.join("/targets")?,
+        std::path::Path::new(".env-architect/cache"),
+    );
+    let targets = verifier.fetch_targets().await?;
+    ReleaseIndex::from_targets(&targets)?;
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, &targets)?;
+    Ok(())
+}
+
+/// A standalone `--policy` file: the `[resolution-policy]` table on its own, in any
+/// manifest format (TOML unless the extension says otherwise).
+fn load_policy(path: &Path) -> Result<env_manifest::ResolutionPolicy> {
+    let content = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read policy file: {}", path.display()))?;
+    let policy = match env_manifest::ManifestFormat::from_path(path) {
+        Some(env_manifest::ManifestFormat::Json) => serde_json::from_str(&content)?,
+        Some(env_manifest::ManifestFormat::Yaml) => serde_yaml::from_str(&content)?,
+        _ => toml::from_str(&content)?,
+    };
+    Ok(policy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_update_stays_in_project() {
+        let temp = tempfile::tempdir().unwrap();
+        let root = temp.path().join("project");
+        std::fs::create_dir(&root).unwrap();
+        std::fs::write(
+            temp.path().join("outside.json"),
+            "{\"name\": \"outside\"}\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("package.json"), "{\"name\": \"app\"}\n").unwrap();
+        let patch = r#"{"private": true}"#;
+
+        let err = apply_config_update(&root, "../outside.json", patch, false).unwrap_err();
+        assert!(err.to_string().contains("outside the project root"));
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(temp.path().join("outside.json"), root.join("link.json"))
+                .unwrap();
+            let err = apply_config_update(&root, "link.json", patch, false).unwrap_err();
+            assert!(err.to_string().contains("outside the project root"));
+        }
+        assert_eq!(
+            std::fs::read_to_string(temp.path().join("outside.json")).unwrap(),
+            "{\"name\": \"outside\"}\n"
+        );
+
+        assert!(apply_config_update(&root, "package.json", patch, false).unwrap());
+        let written: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(root.join("package.json")).unwrap())
+                .unwrap();
+        assert_eq!(
+            written,
+            serde_json::json!({ "name": "app", "private": true })
+        );
+        // Applying it again changes nothing.
+        assert!(!apply_config_update(&root, "package.json", patch, false).unwrap());
+    }
+
+    #[test]
+    fn test_shim_artifacts() {
+        let temp = tempfile::tempdir().unwrap();
+        let store = StoreManager::new(temp.path().join("store"));
+        let shims_dir = temp.path().join("project/.architect/shims");
+        let version = semver::Version::new(20, 11, 0);
+        let sha256 = "ab".repeat(32);
+
+        let mut archive = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::default(),
+        ));
+        let binary = b"\x7fELF\x02\x01";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(binary.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+        archive
+            .append_data(&mut header, "node-v20.11.0/bin/node", &binary[..])
+            .unwrap();
+        let tar_gz = temp.path().join("node-v20.11.0.tar.gz");
+        std::fs::write(&tar_gz, archive.into_inner().unwrap().finish().unwrap()).unwrap();
+
+        shim_artifact(&store, &shims_dir, &tar_gz, "node", &version, &sha256).unwrap();
+        let executable = store
+            .find_executable("node", "node", |v| *v == version)
+            .unwrap();
+        assert_eq!(std::fs::read(executable).unwrap(), binary);
+        assert_eq!(
+            std::fs::read_to_string(shims_dir.join("node")).unwrap(),
+            "#!/bin/bash\nexec env-architect shim --pin 20.11.0 node -- \"$@\"\n"
+        );
+
+        // A plugin module is no executable: nothing lands in the store or the shims.
+        let wasm = temp.path().join("deno.wasm");
+        std::fs::write(&wasm, b"\0asm\x01\0\0\0").unwrap();
+        let err = shim_artifact(&store, &shims_dir, &wasm, "deno", &version, &sha256).unwrap_err();
+        assert!(err.to_string().contains("WebAssembly"));
+        assert!(store.find_executable("deno", "deno", |_| true).is_none());
+        assert!(!shims_dir.join("deno").exists());
+    }
+}
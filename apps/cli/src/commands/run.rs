@@ -1,7 +1,10 @@
+use crate::core::output::forward_masked;
 use anyhow::{Context, Result};
 use clap::Parser;
+use env_manifest::{InterpolationContext, Interpolator};
+use std::collections::HashMap;
 use std::path::PathBuf;
-use std::process::Command;
+use std::process::{Command, Stdio};
 
 #[derive(Parser, Debug)]
 pub struct RunCommand {
@@ -25,23 +28,70 @@ impl RunCommand {
             .unwrap_or_else(|| std::env::current_dir().unwrap_or(PathBuf::from(".")));
 
         let absolute_root = std::fs::canonicalize(&root).unwrap_or(root);
-        let shims_dir = absolute_root.join(".architect").join("shims");
-
-        // Update PATH to include project shims
-        let path_env = std::env::var("PATH").unwrap_or_default();
-        let new_path = format!("{}:{}", shims_dir.to_string_lossy(), path_env);
-
-        let mut child = Command::new(&self.command_name)
-            .args(&self.args)
-            .env("PATH", new_path)
-            .env(
-                "ARCHITECT_PROJECT_ROOT",
-                absolute_root.to_string_lossy().to_string(),
-            )
+        let environment = crate::core::environment::ProjectEnvironment::load(&absolute_root)?;
+        environment.check_platform()?;
+
+        // A manifest service runs its own command with its env layered on top.
+        let service = environment
+            .manifest
+            .as_ref()
+            .and_then(|manifest| Some((manifest, manifest.services.get(&self.command_name)?)));
+        let env = environment.resolve(service.map(|_| self.command_name.as_str()))?;
+
+        // Only the service's command is interpolated, against the env it will run in, so
+        // `${env.X}` sees env_files, profile env and real secret values.
+        let service = match service {
+            Some((manifest, def)) => {
+                let ctx = InterpolationContext::new(&manifest.project, &environment.root)
+                    .with_process_env(env.vars.clone());
+                let command = Interpolator::new(&ctx, &HashMap::new()).resolve_command(
+                    &def.command,
+                    &format!("services.{}.command", self.command_name),
+                )?;
+                Some(command)
+            }
+            None => None,
+        };
+
+        let mut command = match &service {
+            Some(service_command) => {
+                let mut command = Command::new("sh");
+                command
+                    .arg("-c")
+                    .arg(format!("{} \"$@\"", service_command))
+                    .arg(&self.command_name)
+                    .args(&self.args);
+                command
+            }
+            None => {
+                let mut command = Command::new(&self.command_name);
+                command.args(&self.args);
+                command
+            }
+        };
+        env.apply(&mut command);
+
+        // With secrets in its env, the command's output is passed through masked.
+        let masked = !env.secrets.is_empty();
+        if masked {
+            command.stdout(Stdio::piped()).stderr(Stdio::piped());
+        }
+
+        let mut child = command
             .spawn()
             .context(format!("Failed to run command: {}", self.command_name))?;
 
+        let forwarders = match (masked, child.stdout.take(), child.stderr.take()) {
+            (true, Some(stdout), Some(stderr)) => vec![
+                forward_masked(stdout, std::io::stdout()),
+                forward_masked(stderr, std::io::stderr()),
+            ],
+            _ => Vec::new(),
+        };
         let status = child.wait()?;
+        for forwarder in forwarders {
+            let _ = forwarder.join();
+        }
 
         if !status.success() {
             std::process::exit(status.code().unwrap_or(1));
@@ -0,0 +1,110 @@
+use crate::core::secrets::{ProjectSecrets, SecretStore};
+use anyhow::{Context, Result};
+use clap::{Parser, Subcommand};
+use std::io::{IsTerminal, Read};
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+pub struct SecretsCommand {
+    #[command(subcommand)]
+    pub action: SecretsAction,
+
+    /// Path to the environment file (env.toml/json/yaml)
+    #[arg(short, long, global = true)]
+    pub path: Option<PathBuf>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum SecretsAction {
+    /// Store a secret (prompts for the value, or reads it from stdin when piped)
+    Set {
+        /// Secret name, as used in `{ secret = "<name>" }`
+        name: String,
+
+        /// Where to keep the value
+        #[arg(long, value_enum, default_value = "keyring")]
+        store: SecretStore,
+    },
+
+    /// List the project's secret names (values are never shown)
+    List,
+
+    /// Remove a secret from every store
+    Rm {
+        /// Secret name
+        name: String,
+    },
+}
+
+impl SecretsCommand {
+    pub async fn execute(self) -> Result<()> {
+        let (manifest_path, manifest) = match &self.path {
+            Some(p) => (p.clone(), crate::utils::loader::load_manifest(p)?),
+            None => crate::utils::loader::find_and_load_manifest(&std::env::current_dir()?)?,
+        };
+        let root = manifest_path
+            .parent()
+            .map(|p| std::fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf()))
+            .unwrap_or_else(|| PathBuf::from("."));
+        let secrets = ProjectSecrets::new(&root, &manifest.project.name);
+
+        match self.action {
+            SecretsAction::Set { name, store } => {
+                let value = read_value(&name)?;
+                anyhow::ensure!(!value.is_empty(), "Secret '{}' cannot be empty", name);
+                secrets.set(&name, &value, store)?;
+                crate::core::output::log::success(format!(
+                    "Stored {} in the {}",
+                    console::style(&name).bold(),
+                    match store {
+                        SecretStore::Keyring => "OS keyring".to_string(),
+                        SecretStore::File => crate::core::secrets::SECRETS_FILE.to_string(),
+                    }
+                ))?;
+            }
+            SecretsAction::List => {
+                let names = secrets.list()?;
+                if names.is_empty() {
+                    crate::core::output::log::info("No secrets stored for this project.")?;
+                } else {
+                    let lines: Vec<String> = names
+                        .iter()
+                        .map(|(name, store)| {
+                            format!("{} {}", name, console::style(store.as_str()).dim())
+                        })
+                        .collect();
+                    crate::core::output::note("Secrets", lines.join("\n"))?;
+                }
+            }
+            SecretsAction::Rm { name } => {
+                if secrets.remove(&name)? {
+                    crate::core::output::log::success(format!(
+                        "Removed {}",
+                        console::style(&name).bold()
+                    ))?;
+                } else {
+                    crate::core::output::log::warning(format!("No secret named '{}'", name))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Prompt for the value, or read it from stdin when piped. Never taken as an argument,
+/// so it doesn't end up in shell history.
+fn read_value(name: &str) -> Result<String> {
+    if std::io::stdin().is_terminal() {
+        return cliclack::password(format!("Value for {}", name))
+            .mask('•')
+            .interact()
+            .context("Failed to read secret value");
+    }
+
+    let mut value = String::new();
+    std::io::stdin()
+        .read_to_string(&mut value)
+        .context("Failed to read secret value from stdin")?;
+    Ok(value.trim_end_matches(['\n', '\r']).to_string())
+}
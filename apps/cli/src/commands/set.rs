@@ -0,0 +1,103 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use env_manifest::ManifestEditor;
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+pub struct SetCommand {
+    /// Assignments as dotted `key=value` pairs (e.g. env.FOO=bar)
+    #[arg(required = true)]
+    pub assignments: Vec<String>,
+
+    /// Path to the environment file (env.toml/json/yaml)
+    #[arg(short, long)]
+    pub path: Option<PathBuf>,
+}
+
+impl SetCommand {
+    pub async fn execute(self) -> Result<()> {
+        let path = match &self.path {
+            Some(p) => p.clone(),
+            None => crate::utils::loader::find_manifest(&std::env::current_dir()?)?,
+        };
+        let mut editor = ManifestEditor::open(&path)?;
+
+        for assignment in &self.assignments {
+            let (key, value) = assignment
+                .split_once('=')
+                .with_context(|| format!("Expected key=value, got '{}'", assignment))?;
+            let segments: Vec<&str> = key.split('.').collect();
+            anyhow::ensure!(
+                segments.iter().all(|s| !s.is_empty()),
+                "Invalid key '{}'",
+                key
+            );
+
+            let value = parse_value(&segments, value);
+            editor.set(&segments, value.clone())?;
+            crate::core::output::log::success(format!(
+                "Set {} = {}",
+                console::style(key).bold(),
+                value
+            ))?;
+        }
+
+        crate::utils::loader::save_manifest(&editor)?;
+        Ok(())
+    }
+}
+
+/// Type a CLI value: booleans and numbers become scalars, except under an `env` table
+/// whose values are always strings (`env.PORT=8080`).
+fn parse_value(segments: &[&str], raw: &str) -> serde_json::Value {
+    if segments.contains(&"env") {
+        return serde_json::Value::String(raw.to_string());
+    }
+    match raw {
+        "true" => serde_json::Value::Bool(true),
+        "false" => serde_json::Value::Bool(false),
+        _ => {
+            if let Ok(int) = raw.parse::<i64>() {
+                int.into()
+            } else if let Some(float) = raw
+                .parse::<f64>()
+                .ok()
+                .filter(|f| f.is_finite())
+                .and_then(serde_json::Number::from_f64)
+            {
+                serde_json::Value::Number(float)
+            } else {
+                serde_json::Value::String(raw.to_string())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(
+            parse_value(&["lockfile", "generate"], "false"),
+            json!(false)
+        );
+        assert_eq!(parse_value(&["scoring", "weights", "size"], "2"), json!(2));
+        assert_eq!(
+            parse_value(&["scoring", "weights", "size"], "0.5"),
+            json!(0.5)
+        );
+        assert_eq!(
+            parse_value(&["project", "version"], "1.2.0"),
+            json!("1.2.0")
+        );
+        assert_eq!(parse_value(&["project", "name"], "nan"), json!("nan"));
+        assert_eq!(parse_value(&["env", "PORT"], "8080"), json!("8080"));
+        assert_eq!(
+            parse_value(&["services", "db", "env", "DEBUG"], "true"),
+            json!("true")
+        );
+    }
+}
@@ -26,40 +26,35 @@ impl ShellCommand {
 
         cliclack::intro(console::style("Architect Shell").on_cyan().black())?;
 
-        cliclack::log::info(format!(
+        crate::core::output::log::info(format!(
             "Activating Environment: {}",
             console::style(project_name).bold().cyan()
         ))?;
 
-        // For Phase 1, we simply create a temporary shims directory and add it to PATH
-        let shims_dir = absolute_root.join(".architect").join("shims");
+        let environment = crate::core::environment::ProjectEnvironment::load(&absolute_root)?;
+        environment.check_platform()?;
+        let shims_dir = environment.shims_dir();
         if !shims_dir.exists() {
             std::fs::create_dir_all(&shims_dir)?;
         }
 
         let shell = std::env::var("SHELL").unwrap_or_else(|_| "zsh".to_string());
+        let env = environment.resolve(None)?;
 
-        // Update PATH: Prepend shims directory
-        let path_env = std::env::var("PATH").unwrap_or_default();
-        let new_path = format!("{}:{}", shims_dir.to_string_lossy(), path_env);
+        crate::core::output::log::success(format!("Spawning {} with Architect context...", shell))?;
 
-        cliclack::log::success(format!("Spawning {} with Architect context...", shell))?;
-
-        let mut child = Command::new(&shell)
-            .env("PATH", new_path)
-            .env(
-                "ARCHITECT_PROJECT_ROOT",
-                absolute_root.to_string_lossy().to_string(),
-            )
+        let mut command = Command::new(&shell);
+        env.apply(&mut command);
+        let mut child = command
             .spawn()
             .context(format!("Failed to spawn shell: {}", shell))?;
 
         let status = child.wait()?;
 
         if status.success() {
-            cliclack::outro("Shell exited successfully.")?;
+            crate::core::output::outro("Shell exited successfully.")?;
         } else {
-            cliclack::log::error("Shell exited with error.")?;
+            crate::core::output::log::error("Shell exited with error.")?;
         }
 
         Ok(())
@@ -2,48 +2,51 @@ use anyhow::{Context, Result};
 use domain::system::StoreManager;
 use std::path::PathBuf;
 
-/// The entry point for the shim proxy
-pub async fn execute_shim(tool_name: String, args: Vec<String>) -> Result<()> {
+/// The entry point for the shim proxy. Auto-shims `pin` the version they installed;
+/// other shims run the newest stored version the project's constraint allows.
+pub async fn execute_shim(tool_name: String, pin: Option<String>, args: Vec<String>) -> Result<()> {
     let current_dir = std::env::current_dir()?;
     let project_root = find_project_root(&current_dir).context(
         "Could not find an Architect project (env.json/toml) in the current directory or parents",
     )?;
 
-    let manifest_path = project_root.join(crate::constants::MANIFEST_JSON); // Or .architect/lock.json
-    let content = std::fs::read_to_string(&manifest_path)
+    let environment = crate::core::environment::ProjectEnvironment::load(&project_root)?;
+    let manifest = environment
+        .manifest
+        .as_ref()
         .context("Failed to read project manifest. Have you run 'architect resolve'?")?;
 
-    let manifest: serde_json::Value = serde_json::from_str(&content)?;
-    let _version_req = manifest
-        .get("dependencies")
-        .and_then(|d| d.get(&tool_name))
-        .and_then(|v| v.as_str())
-        .or_else(|| None)
-        .context(format!(
-            "Tool '{}' is not defined in this project's environment",
-            tool_name
-        ))?;
-
     let store = StoreManager::default()?;
+    let exec_path = match pin {
+        Some(pin) => {
+            let version = semver::Version::parse(&pin)
+                .with_context(|| format!("Invalid version: {}", pin))?;
+            store.find_executable(&tool_name, &tool_name, |v| *v == version)
+        }
+        None => {
+            let spec = manifest.dependencies.get(&tool_name).context(format!(
+                "Tool '{}' is not defined in this project's environment",
+                tool_name
+            ))?;
+            // Channels need the registry to resolve; any stored version will do for them.
+            let range = spec.constraint().range(spec.ecosystem(&tool_name));
+            store.find_executable(&tool_name, &tool_name, |v| {
+                range.as_ref().is_none_or(|range| range.matches(v))
+            })
+        }
+    }
+    .context(format!(
+        "Tool '{}' is defined in manifest but no matching version is in the Architect Store",
+        tool_name
+    ))?;
 
-    let tools = store.list_tools()?;
-    let match_in_store = tools
-        .into_iter()
-        .find(|t| t == &tool_name)
-        .context(format!(
-            "Tool '{}' is defined in manifest but not found in Architect Store",
-            tool_name
-        ))?;
+    let env = environment.resolve(None)?;
 
-    let exec_path = store
-        .get_executable_path(&match_in_store, "20.11.0", "abc123456789", &tool_name)
-        .context(format!(
-            "Failed to find executable for '{}' in store",
-            tool_name
-        ))?;
+    let mut command = std::process::Command::new(exec_path);
+    command.args(args);
+    env.apply(&mut command);
 
-    let mut child = std::process::Command::new(exec_path)
-        .args(args)
+    let mut child = command
         .spawn()
         .context(format!("Failed to execute shimmed tool: {}", tool_name))?;
 
@@ -0,0 +1,120 @@
+use anyhow::Result;
+use clap::Parser;
+use domain::system::PlatformDetector;
+use env_manifest::{DependencySpec, EnvValue, InterpolationContext};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+#[derive(Parser, Debug)]
+pub struct ShowCommand {
+    /// Path to the environment file (env.toml/json/yaml)
+    #[arg(short, long)]
+    pub path: Option<PathBuf>,
+
+    /// Show the flattened dependency set and env for the selected profile/extras
+    #[arg(long)]
+    pub effective: bool,
+
+    /// Profile to apply (implies --effective)
+    #[arg(long)]
+    pub profile: Option<String>,
+
+    /// Extras to activate (comma-separated, implies --effective)
+    #[arg(long, value_delimiter = ',')]
+    pub extras: Vec<String>,
+}
+
+impl ShowCommand {
+    pub async fn execute(self) -> Result<()> {
+        let (manifest_path, manifest) = match &self.path {
+            Some(p) => (p.clone(), crate::utils::loader::load_manifest(p)?),
+            None => crate::utils::loader::find_and_load_manifest(&std::env::current_dir()?)?,
+        };
+
+        cliclack::intro(console::style("EnvArchitect Environment").bold())?;
+        crate::core::output::log::info(format!(
+            "Manifest: {}",
+            console::style(manifest_path.display()).dim()
+        ))?;
+
+        let project_root = manifest_path
+            .parent()
+            .map(|p| std::fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf()))
+            .unwrap_or_else(|| PathBuf::from("."));
+        let ctx = InterpolationContext::new(&manifest.project, &project_root);
+
+        if self.effective || self.profile.is_some() || !self.extras.is_empty() {
+            let platform = PlatformDetector::detect().target();
+            let effective = manifest
+                .effective(self.profile.as_deref(), &self.extras, &platform)?
+                .interpolate(&ctx)?;
+
+            crate::core::output::log::info(format!(
+                "Profile: {}  Extras: {}  Platform: {:?}/{:?}",
+                effective.profile.as_deref().unwrap_or("(default)"),
+                if effective.extras.is_empty() {
+                    "(none)".to_string()
+                } else {
+                    effective.extras.join(", ")
+                },
+                platform.os,
+                platform.arch
+            ))?;
+
+            crate::core::output::note(
+                "Dependencies",
+                format_dependencies(&effective.dependencies),
+            )?;
+            crate::core::output::note("Environment", format_env(&effective.env))?;
+        } else {
+            let manifest = manifest.interpolate(&ctx)?;
+
+            crate::core::output::note("Dependencies", format_dependencies(&manifest.dependencies))?;
+            if !manifest.dev_dependencies.is_empty() {
+                crate::core::output::note(
+                    "Dev Dependencies",
+                    format_dependencies(&manifest.dev_dependencies),
+                )?;
+            }
+            crate::core::output::note("Environment", format_env(&manifest.env))?;
+        }
+
+        crate::core::output::outro("Done.")?;
+        Ok(())
+    }
+}
+
+fn format_dependencies(deps: &HashMap<String, DependencySpec>) -> String {
+    if deps.is_empty() {
+        return "(none)".to_string();
+    }
+
+    let mut names: Vec<&String> = deps.keys().collect();
+    names.sort();
+
+    names
+        .into_iter()
+        .map(|name| match &deps[name] {
+            DependencySpec::Simple(req) => format!("{} {}", name, req),
+            DependencySpec::Detailed(details) => match details.manager {
+                Some(manager) => format!("{} {} ({:?})", name, details.version, manager),
+                None => format!("{} {}", name, details.version),
+            },
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn format_env(env: &HashMap<String, EnvValue>) -> String {
+    if env.is_empty() {
+        return "(none)".to_string();
+    }
+
+    let mut keys: Vec<&String> = env.keys().collect();
+    keys.sort();
+
+    keys.into_iter()
+        .map(|key| format!("{}={}", key, env[key]))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
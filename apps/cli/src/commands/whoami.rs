@@ -1,6 +1,7 @@
+use crate::core::output::{log, outro};
 use anyhow::{Context, Result};
 use clap::Args;
-use cliclack::{intro, log, outro};
+use cliclack::intro;
 use database::models::User;
 use reqwest::Client;
 use url::Url;
@@ -1,2 +1,5 @@
 pub const MANIFEST_JSON: &str = "env.json";
 pub const LOCK_FILE: &str = "lock.json";
+pub const REGISTRY_URL: &str = "https://registry.env-architect.dev";
+/// Trusted TUF root for the registry, relative to the working directory.
+pub const TUF_ROOT: &str = ".env-architect/tuf/root.json";
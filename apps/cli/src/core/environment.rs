@@ -0,0 +1,108 @@
+use crate::core::secrets::ProjectSecrets;
+use anyhow::{Context, Result};
+use domain::system::PlatformDetector;
+use env_manifest::{
+    read_env_files, EnhancedManifest, EnvValue, EnvironmentBuilder, InterpolationContext,
+    ManifestValidator, ResolvedEnv,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// The environment a project's child processes run in.
+///
+/// `shell`, `run`, shims, services and `dotenv export` all build their environment here so
+/// `env_files` and manifest `env` values (including `prepend`/`append`/`unset`/`default`)
+/// are applied the same way.
+pub struct ProjectEnvironment {
+    pub root: PathBuf,
+    pub manifest: Option<EnhancedManifest>,
+}
+
+impl ProjectEnvironment {
+    /// Load the project at `root`. A missing manifest is not an error; the environment then
+    /// only carries the shims directory and project root.
+    pub fn load(root: &Path) -> Result<Self> {
+        let root = std::fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+        let manifest = match crate::utils::loader::find_and_load_manifest(&root) {
+            Ok((_, manifest)) => Some(manifest),
+            Err(_) if !has_manifest(&root) => None,
+            Err(e) => return Err(e),
+        };
+
+        Ok(Self { root, manifest })
+    }
+
+    /// Fail when this machine doesn't meet the manifest's `platform` constraints.
+    pub fn check_platform(&self) -> Result<()> {
+        match &self.manifest {
+            Some(manifest) => check_platform(manifest),
+            None => Ok(()),
+        }
+    }
+
+    pub fn shims_dir(&self) -> PathBuf {
+        self.root.join(".architect").join("shims")
+    }
+
+    /// The manifest's env layers, lowest precedence first: `env_files` in order, the
+    /// effective `env` (profile env applied), then the service's own `env`.
+    pub fn layers(&self, service: Option<&str>) -> Result<Vec<HashMap<String, EnvValue>>> {
+        let Some(manifest) = &self.manifest else {
+            return Ok(Vec::new());
+        };
+
+        let mut layers = vec![read_env_files(&self.root, &manifest.env_files)?];
+        let effective = manifest.effective(None, &[], &PlatformDetector::detect().target())?;
+        layers.push(effective.env);
+
+        if let Some(name) = service {
+            let def = manifest
+                .services
+                .get(name)
+                .with_context(|| format!("Service '{}' is not defined", name))?;
+            layers.push(def.env.clone());
+        }
+
+        Ok(layers)
+    }
+
+    /// Resolve the environment, optionally layering a service's own `env` on top.
+    pub fn resolve(&self, service: Option<&str>) -> Result<ResolvedEnv> {
+        let mut builder = EnvironmentBuilder::from_process();
+        let project = self
+            .manifest
+            .as_ref()
+            .map(|m| m.project.clone())
+            .unwrap_or_default();
+        let ctx = InterpolationContext::new(&project, &self.root);
+
+        for layer in self.layers(service)? {
+            builder = builder.layer(&layer);
+        }
+
+        let secrets = ProjectSecrets::new(&self.root, &project.name);
+        let env = builder
+            .prepend("PATH", self.shims_dir().to_string_lossy())
+            .set(
+                "ARCHITECT_PROJECT_ROOT",
+                self.root.to_string_lossy().to_string(),
+            )
+            .build_with_secrets(&ctx, &secrets)?;
+        crate::core::secrets::register(&env.secrets);
+
+        Ok(env)
+    }
+}
+
+/// Fail when this machine doesn't meet `manifest`'s `platform` constraints. Requirements
+/// that can't be checked here are left to `env-architect check` to report.
+pub fn check_platform(manifest: &EnhancedManifest) -> Result<()> {
+    ManifestValidator::check_platform_compatibility(manifest, &PlatformDetector::detect().target())
+        .context("This project doesn't support this machine (see `env-architect check`)")
+}
+
+fn has_manifest(root: &Path) -> bool {
+    [crate::constants::MANIFEST_JSON, "env.toml", "env.yaml"]
+        .iter()
+        .any(|name| root.join(name).exists())
+}
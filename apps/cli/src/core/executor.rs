@@ -82,10 +82,13 @@ impl SystemExecutor {
                 }
 
                 if !instructions.is_empty() {
-                    cliclack::log::step("Executing installation plan:")?;
+                    crate::core::output::log::step("Executing installation plan:")?;
 
                     for cmd in instructions {
-                        cliclack::log::info(format!("Running: {}", console::style(&cmd).dim()))?;
+                        crate::core::output::log::info(format!(
+                            "Running: {}",
+                            console::style(&cmd).dim()
+                        ))?;
 
                         let spinner_cmd = cliclack::spinner();
                         spinner_cmd.start("Processing...");
@@ -102,10 +105,14 @@ impl SystemExecutor {
 
                             // Print captured stderr/stdout for debugging
                             if !output_res.stdout.is_empty() {
-                                cliclack::log::info(String::from_utf8_lossy(&output_res.stdout))?;
+                                crate::core::output::log::info(String::from_utf8_lossy(
+                                    &output_res.stdout,
+                                ))?;
                             }
                             if !output_res.stderr.is_empty() {
-                                cliclack::log::error(String::from_utf8_lossy(&output_res.stderr))?;
+                                crate::core::output::log::error(String::from_utf8_lossy(
+                                    &output_res.stderr,
+                                ))?;
                             }
 
                             return Err(anyhow::anyhow!("Command failed: {}", cmd));
@@ -115,14 +122,16 @@ impl SystemExecutor {
                     }
 
                     if let Some(state) = output.state {
-                        cliclack::log::info(format!("Success: {}", state))?;
+                        crate::core::output::log::info(format!("Success: {}", state))?;
                     }
                 } else {
-                    cliclack::log::warning("Plugin returned no installation instructions.")?;
+                    crate::core::output::log::warning(
+                        "Plugin returned no installation instructions.",
+                    )?;
                 }
             }
             Err(e) => {
-                cliclack::log::error(format!("Plugin Resolved Error: {}", e))?;
+                crate::core::output::log::error(format!("Plugin Resolved Error: {}", e))?;
                 return Err(anyhow::anyhow!("Plugin logic failed: {}", e));
             }
         }
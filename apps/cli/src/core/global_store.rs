@@ -1,7 +1,7 @@
 use anyhow::{Context, Result};
 use env_architect::domain::entities::manifest::global::GlobalManifest;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub struct GlobalStateService {
     // config_dir: PathBuf, // Removed unused field
@@ -66,4 +66,15 @@ impl GlobalStateService {
         self.save(&manifest)?;
         Ok(())
     }
+
+    /// Track a project directory, so impact analysis knows it exists.
+    pub fn add_project(&self, root: &Path) -> Result<()> {
+        let mut manifest = self.load()?;
+        let root = root.to_string_lossy().to_string();
+        if !manifest.projects.contains(&root) {
+            manifest.projects.push(root);
+            self.save(&manifest)?;
+        }
+        Ok(())
+    }
 }
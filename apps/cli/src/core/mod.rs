@@ -1,3 +1,8 @@
+pub mod environment;
 pub mod executor;
 pub mod global_store;
+pub mod output;
+pub mod project_index;
+pub mod secrets;
+pub mod user_config;
 pub mod virtual_manifest;
@@ -0,0 +1,62 @@
+//! cliclack's log, note and outro, with every secret resolved so far masked.
+//!
+//! Everything the CLI prints goes through here rather than `cliclack` directly, so a
+//! secret quoted by a hook's output, an error or an env listing never reaches the
+//! terminal.
+
+use super::secrets::mask;
+use std::fmt::Display;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::thread::JoinHandle;
+
+pub mod log {
+    use super::mask;
+    use std::fmt::Display;
+    use std::io;
+
+    pub fn info(text: impl Display) -> io::Result<()> {
+        cliclack::log::info(mask(&text.to_string()))
+    }
+
+    pub fn step(text: impl Display) -> io::Result<()> {
+        cliclack::log::step(mask(&text.to_string()))
+    }
+
+    pub fn success(text: impl Display) -> io::Result<()> {
+        cliclack::log::success(mask(&text.to_string()))
+    }
+
+    pub fn warning(text: impl Display) -> io::Result<()> {
+        cliclack::log::warning(mask(&text.to_string()))
+    }
+
+    pub fn error(text: impl Display) -> io::Result<()> {
+        cliclack::log::error(mask(&text.to_string()))
+    }
+}
+
+pub fn note(prompt: impl Display, message: impl Display) -> io::Result<()> {
+    cliclack::note(mask(&prompt.to_string()), mask(&message.to_string()))
+}
+
+pub fn outro(message: impl Display) -> io::Result<()> {
+    cliclack::outro(mask(&message.to_string()))
+}
+
+/// Copy a child process's output to `writer` line by line, masking secrets.
+pub fn forward_masked<R, W>(reader: R, mut writer: W) -> JoinHandle<io::Result<()>>
+where
+    R: Read + Send + 'static,
+    W: Write + Send + 'static,
+{
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(reader);
+        let mut line = Vec::new();
+        while reader.read_until(b'\n', &mut line)? > 0 {
+            writer.write_all(mask(&String::from_utf8_lossy(&line)).as_bytes())?;
+            writer.flush()?;
+            line.clear();
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,45 @@
+use anyhow::{Context, Result};
+use domain::system::ProjectIndex;
+use env_architect::domain::entities::manifest::EnhancedManifest;
+use std::path::Path;
+
+use super::global_store::GlobalStateService;
+
+/// The project index, brought up to date with `GlobalManifest.projects`: new or edited
+/// manifests are read again, projects whose manifest is gone are dropped.
+pub fn load() -> Result<ProjectIndex> {
+    let path = ProjectIndex::default_path().context("Could not find home directory")?;
+    let mut index = ProjectIndex::open(path);
+    let global = GlobalStateService::new()?.load()?;
+
+    for root in global.projects.iter().map(Path::new) {
+        let Ok(manifest_path) = crate::utils::loader::find_manifest(root) else {
+            continue;
+        };
+        if index.is_current(root, &manifest_path) {
+            continue;
+        }
+        // A manifest that doesn't parse right now keeps its last indexed requirements.
+        if let Ok(manifest) = crate::utils::loader::load_manifest(&manifest_path) {
+            index.register(root, &manifest_path, &manifest);
+        }
+    }
+    index.prune();
+    index.save()?;
+    Ok(index)
+}
+
+/// Remember the project `manifest_path` belongs to in the global manifest and the
+/// project index.
+pub fn register(manifest_path: &Path, manifest: &EnhancedManifest) -> Result<()> {
+    let manifest_path = std::fs::canonicalize(manifest_path)?;
+    let root = manifest_path
+        .parent()
+        .context("Manifest has no parent directory")?;
+    GlobalStateService::new()?.add_project(root)?;
+
+    let path = ProjectIndex::default_path().context("Could not find home directory")?;
+    let mut index = ProjectIndex::open(path);
+    index.register(root, &manifest_path, manifest);
+    index.save()
+}
@@ -0,0 +1,316 @@
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Nonce,
+};
+use anyhow::{anyhow, Context, Result};
+use env_manifest::{SecretRef, SecretResolver};
+use keyring::Entry;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Encrypted project secrets, next to the shims directory.
+pub const SECRETS_FILE: &str = ".architect/secrets.json";
+
+/// Hex-encoded 32-byte key for the secrets file; lets CI and teammates share it.
+const KEY_ENV: &str = "ARCHITECT_SECRETS_KEY";
+const KEY_KEYRING_SERVICE: &str = "env-architect-secrets-key";
+const KEY_KEYRING_USER: &str = "default";
+
+/// Where `secrets set` stores a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SecretStore {
+    /// The OS keyring (only this machine can read it)
+    Keyring,
+    /// The project's encrypted secrets file
+    File,
+}
+
+impl SecretStore {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecretStore::Keyring => "keyring",
+            SecretStore::File => "file",
+        }
+    }
+}
+
+/// On-disk layout of [`SECRETS_FILE`]. Keyring values can't be enumerated, so their
+/// names are indexed here; the values never are.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SecretsFile {
+    #[serde(default)]
+    keyring: BTreeSet<String>,
+    #[serde(default)]
+    encrypted: BTreeMap<String, String>,
+}
+
+/// A project's secrets: OS keyring entries plus the encrypted secrets file.
+pub struct ProjectSecrets {
+    root: PathBuf,
+    service: String,
+}
+
+impl ProjectSecrets {
+    /// Secrets for the project at `root`, keyed in the keyring by project name
+    /// (or directory name when the manifest has none).
+    pub fn new(root: &Path, project_name: &str) -> Self {
+        let name = if project_name.is_empty() {
+            root.file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("default")
+        } else {
+            project_name
+        };
+
+        Self {
+            root: root.to_path_buf(),
+            service: format!("env-architect-secrets:{}", name),
+        }
+    }
+
+    pub fn set(&self, name: &str, value: &str, store: SecretStore) -> Result<()> {
+        let mut file = self.read_file()?;
+        match store {
+            SecretStore::Keyring => {
+                self.entry(name)?
+                    .set_password(value)
+                    .context("Failed to save secret to the OS keyring (try --store file)")?;
+                file.encrypted.remove(name);
+                file.keyring.insert(name.to_string());
+            }
+            SecretStore::File => {
+                let key = load_or_create_key()?;
+                file.encrypted
+                    .insert(name.to_string(), encrypt(&key, value)?);
+                if file.keyring.remove(name) {
+                    let _ = self.entry(name)?.delete_credential();
+                }
+            }
+        }
+        self.write_file(&file)
+    }
+
+    pub fn get(&self, name: &str) -> Result<Option<String>> {
+        if let Ok(value) = self.entry(name)?.get_password() {
+            return Ok(Some(value));
+        }
+
+        let file = self.read_file()?;
+        match file.encrypted.get(name) {
+            Some(ciphertext) => Ok(Some(decrypt(&load_or_create_key()?, ciphertext)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Known secret names and where each is stored.
+    pub fn list(&self) -> Result<Vec<(String, SecretStore)>> {
+        let file = self.read_file()?;
+        let mut names: Vec<(String, SecretStore)> = file
+            .keyring
+            .into_iter()
+            .map(|name| (name, SecretStore::Keyring))
+            .chain(
+                file.encrypted
+                    .into_keys()
+                    .map(|name| (name, SecretStore::File)),
+            )
+            .collect();
+        names.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(names)
+    }
+
+    /// Remove a secret from every store; returns whether it existed.
+    pub fn remove(&self, name: &str) -> Result<bool> {
+        let mut file = self.read_file()?;
+        let deleted = self.entry(name)?.delete_credential().is_ok();
+        let indexed = file.keyring.remove(name);
+        let encrypted = file.encrypted.remove(name).is_some();
+        if indexed || encrypted {
+            self.write_file(&file)?;
+        }
+        Ok(deleted || indexed || encrypted)
+    }
+
+    fn entry(&self, name: &str) -> Result<Entry> {
+        Entry::new(&self.service, name).context("Failed to access keyring")
+    }
+
+    fn path(&self) -> PathBuf {
+        self.root.join(SECRETS_FILE)
+    }
+
+    fn read_file(&self) -> Result<SecretsFile> {
+        let path = self.path();
+        if !path.exists() {
+            return Ok(SecretsFile::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&content).with_context(|| format!("Invalid {}", path.display()))
+    }
+
+    fn write_file(&self, file: &SecretsFile) -> Result<()> {
+        let path = self.path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, serde_json::to_string_pretty(file)? + "\n")
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+impl SecretResolver for ProjectSecrets {
+    fn resolve_secret(&self, secret: &SecretRef) -> std::result::Result<String, String> {
+        if let Some(path) = &secret.secret_file {
+            let path = self.root.join(path);
+            return std::fs::read_to_string(&path)
+                .map(|value| value.trim_end_matches(['\n', '\r']).to_string())
+                .map_err(|e| format!("cannot read secret file {}: {}", path.display(), e));
+        }
+
+        let name = secret.secret.as_deref().unwrap_or_default();
+        match self.get(name) {
+            Ok(Some(value)) => Ok(value),
+            Ok(None) => Err(format!(
+                "secret '{}' is not set (run `env-architect secrets set {}`)",
+                name, name
+            )),
+            Err(e) => Err(format!("cannot read secret '{}': {:#}", name, e)),
+        }
+    }
+}
+
+/// Secret values resolved during this run, masked in everything we print.
+fn registry() -> &'static Mutex<Vec<String>> {
+    static SECRETS: OnceLock<Mutex<Vec<String>>> = OnceLock::new();
+    SECRETS.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+/// Remember resolved secret values so [`mask`] hides them.
+pub fn register(values: &[String]) {
+    if let Ok(mut secrets) = registry().lock() {
+        for value in values {
+            if !secrets.contains(value) {
+                secrets.push(value.clone());
+            }
+        }
+    }
+}
+
+/// Mask every secret value resolved so far in `text`.
+pub fn mask(text: &str) -> String {
+    match registry().lock() {
+        Ok(secrets) => env_manifest::mask_secrets(text, &secrets),
+        Err(_) => text.to_string(),
+    }
+}
+
+/// Load the secrets-file key: `ARCHITECT_SECRETS_KEY`, then the OS keyring, then
+/// `~/.config/env-architect/secrets-key`, generating one on first use.
+fn load_or_create_key() -> Result<[u8; 32]> {
+    if let Ok(hex_key) = std::env::var(KEY_ENV) {
+        return parse_key(&hex_key).with_context(|| format!("Invalid {}", KEY_ENV));
+    }
+
+    let entry = Entry::new(KEY_KEYRING_SERVICE, KEY_KEYRING_USER).ok();
+    if let Some(key) = entry
+        .as_ref()
+        .and_then(|e| e.get_password().ok())
+        .and_then(|hex_key| parse_key(&hex_key).ok())
+    {
+        return Ok(key);
+    }
+
+    let home = dirs::home_dir().context("Could not find home directory")?;
+    let key_path = home
+        .join(".config")
+        .join("env-architect")
+        .join("secrets-key");
+    if key_path.exists() {
+        let hex_key = std::fs::read_to_string(&key_path)?;
+        return parse_key(&hex_key).with_context(|| format!("Invalid {}", key_path.display()));
+    }
+
+    let mut key = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut key);
+    let hex_key = hex::encode(key);
+    if let Some(entry) = &entry {
+        let _ = entry.set_password(&hex_key);
+    }
+
+    if let Some(parent) = key_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(&key_path, &hex_key).context("Failed to save secrets key")?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&key_path, std::fs::Permissions::from_mode(0o600))?;
+    }
+
+    Ok(key)
+}
+
+fn parse_key(hex_key: &str) -> Result<[u8; 32]> {
+    hex::decode(hex_key.trim())?
+        .try_into()
+        .map_err(|_| anyhow!("key must be 32 bytes (64 hex chars)"))
+}
+
+/// AES-256-GCM with a random nonce, stored as hex(nonce || ciphertext).
+fn encrypt(key: &[u8; 32], plaintext: &str) -> Result<String> {
+    let cipher =
+        Aes256Gcm::new_from_slice(key).map_err(|e| anyhow!("Cipher init failed: {}", e))?;
+
+    let mut nonce_bytes = [0u8; 12];
+    rand::thread_rng().fill_bytes(&mut nonce_bytes);
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce_bytes), plaintext.as_bytes())
+        .map_err(|e| anyhow!("Encryption failed: {}", e))?;
+
+    let mut combined = nonce_bytes.to_vec();
+    combined.extend_from_slice(&ciphertext);
+    Ok(hex::encode(combined))
+}
+
+fn decrypt(key: &[u8; 32], hex_ciphertext: &str) -> Result<String> {
+    let combined = hex::decode(hex_ciphertext.trim()).context("Invalid hex ciphertext")?;
+    if combined.len() < 12 {
+        anyhow::bail!("Ciphertext too short");
+    }
+
+    let (nonce_bytes, ciphertext) = combined.split_at(12);
+    let cipher =
+        Aes256Gcm::new_from_slice(key).map_err(|e| anyhow!("Cipher init failed: {}", e))?;
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce_bytes), ciphertext)
+        .map_err(|_| anyhow!("Decryption failed (wrong secrets key?)"))?;
+
+    String::from_utf8(plaintext).context("Invalid UTF-8 after decryption")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_roundtrip() {
+        let key = [7u8; 32];
+        let sealed = encrypt(&key, "npm_s3cr3t").unwrap();
+        assert!(!sealed.contains("npm_s3cr3t"));
+        assert_eq!(decrypt(&key, &sealed).unwrap(), "npm_s3cr3t");
+        assert!(decrypt(&[8u8; 32], &sealed).is_err());
+    }
+
+    #[test]
+    fn test_mask_registered_values() {
+        register(&["ghp_abcdef123456".to_string()]);
+        assert_eq!(
+            mask("token=ghp_abcdef123456 ok"),
+            format!("token={} ok", env_manifest::SECRET_MASK)
+        );
+    }
+}
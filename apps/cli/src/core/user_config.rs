@@ -0,0 +1,37 @@
+use anyhow::{Context, Result};
+use env_manifest::{UserConfig, ValidationLevel};
+use std::path::PathBuf;
+
+/// `~/.env-architect/config.toml`, next to the global manifest.
+pub fn path() -> Result<PathBuf> {
+    let home = dirs::home_dir().context("Could not find home directory")?;
+    Ok(home.join(".env-architect").join("config.toml"))
+}
+
+/// Load and validate the user config; a missing file is an empty config.
+pub fn load() -> Result<UserConfig> {
+    let path = path()?;
+    if !path.exists() {
+        return Ok(UserConfig::default());
+    }
+
+    let content = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let config: UserConfig =
+        toml::from_str(&content).with_context(|| format!("Invalid {}", path.display()))?;
+
+    let result = config.validate();
+    let errors: Vec<String> = result
+        .issues
+        .iter()
+        .filter(|issue| issue.level == ValidationLevel::Error)
+        .map(|issue| format!("  {}: {}", issue.field, issue.message))
+        .collect();
+    anyhow::ensure!(
+        errors.is_empty(),
+        "Invalid {}:\n{}",
+        path.display(),
+        errors.join("\n")
+    );
+    Ok(config)
+}
@@ -27,20 +27,17 @@ impl VirtualManifestBuilder {
         let mut deps = HashMap::new();
 
         // Parse resolution string (simple heuristic for this MVP)
-        use env_architect::domain::entities::manifest::DependencyDetails;
-        use semver::VersionReq;
+        use env_architect::domain::entities::manifest::{DependencyDetails, VersionConstraint};
 
         let spec = if let Some(path) = resolution.strip_prefix("path:") {
             DependencySpec::Detailed(DependencyDetails {
-                version: VersionReq::parse("*").expect("Failed to parse wildcard version"),
+                version: VersionConstraint::any(),
                 manager: None,
                 source: Some(path.to_string()),
                 optional: false,
             })
         } else {
-            DependencySpec::Simple(
-                VersionReq::parse("*").expect("Failed to parse wildcard version"),
-            )
+            DependencySpec::Simple(VersionConstraint::any())
         };
 
         deps.insert(package_name.to_string(), spec);
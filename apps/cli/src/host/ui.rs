@@ -11,13 +11,13 @@ impl Host for HostState {
         match level {
             LogLevel::Debug => (), // Skip debug
             LogLevel::Info => {
-                let _ = cliclack::log::info(message);
+                let _ = crate::core::output::log::info(message);
             }
             LogLevel::Warn => {
-                let _ = cliclack::log::warning(message);
+                let _ = crate::core::output::log::warning(message);
             }
             LogLevel::Error => {
-                let _ = cliclack::log::error(message);
+                let _ = crate::core::output::log::error(message);
             }
         }
     }
@@ -37,7 +37,7 @@ impl Host for HostState {
     async fn secret(&mut self, prompt_msg: String) -> String {
         // Enforce capability
         if !self.allowed_capabilities.contains(&"ui-secret".to_string()) {
-            let _ = cliclack::log::error("Capability Denied: ui-secret");
+            let _ = crate::core::output::log::error("Capability Denied: ui-secret");
             return String::new();
         }
 
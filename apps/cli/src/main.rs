@@ -1,6 +1,6 @@
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use url::Url;
 
 // Import application services
@@ -38,6 +38,14 @@ enum Commands {
         /// Force re-installation
         #[arg(long, short)]
         force: bool,
+
+        /// Profile to install (e.g. 'ci')
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Extras to activate (comma-separated, e.g. 'gpu,docs')
+        #[arg(long, value_delimiter = ',')]
+        extras: Vec<String>,
     },
 
     /// Resolve an environment using a WASM plugin (Host Runtime Check)
@@ -72,6 +80,9 @@ enum Commands {
     Shim {
         /// The name of the tool being shimmed
         tool: String,
+        /// Run exactly this stored version instead of the manifest's constraint
+        #[arg(long)]
+        pin: Option<String>,
         /// Arguments for the tool
         #[arg(last = true)]
         args: Vec<String>,
@@ -85,6 +96,37 @@ enum Commands {
 
     /// Display the current logged-in identity
     Whoami(commands::whoami::WhoamiCommand),
+
+    /// Show the project environment manifest
+    Show(commands::show::ShowCommand),
+
+    /// Validate the environment manifest and report issues with their location
+    Check(commands::check::CheckCommand),
+
+    /// Add dependencies to the manifest (e.g. `add node@^20 --dev`)
+    Add(commands::add::AddCommand),
+
+    /// Remove dependencies from the manifest
+    Remove(commands::remove::RemoveCommand),
+
+    /// Set manifest values (e.g. `set env.FOO=bar`)
+    Set(commands::set::SetCommand),
+
+    /// Upgrade the manifest to the current schema version in place
+    Migrate(commands::migrate::MigrateCommand),
+
+    /// Manage project secrets referenced as `{ secret = "..." }` in env
+    Secrets(commands::secrets::SecretsCommand),
+
+    /// Work with `.env` files (e.g. `dotenv export -o .env`)
+    Dotenv(commands::dotenv::DotenvCommand),
+
+    /// List registered projects that would break if a tool version were replaced
+    /// (e.g. `impact node@18 --to 20`)
+    Impact(commands::impact::ImpactCommand),
+
+    /// Import versions from .nvmrc, .tool-versions, go.mod and other pin files
+    Import(commands::import::ImportCommand),
 }
 
 #[tokio::main]
@@ -94,22 +136,32 @@ async fn main() -> Result<()> {
 
     let cli = Cli::parse();
 
+    // Errors can quote resolved env values; never print a secret.
+    run(cli)
+        .await
+        .map_err(|e| anyhow::anyhow!(core::secrets::mask(&format!("{:?}", e))))
+}
+
+async fn run(cli: Cli) -> Result<()> {
     match cli.command {
         Commands::Install {
             package,
             path,
             force,
+            profile,
+            extras,
         } => {
             cliclack::intro(console::style("EnvArchitect Install").bold())?;
             if force {
-                cliclack::log::warning("Force mode enabled")?;
+                crate::core::output::log::warning("Force mode enabled")?;
             }
 
             // 1. System/Global Install Mode (Homebrew-style)
             // When a package is explicitly named, we operate in system mode.
             // This is context-independent and does not read/write local project files.
             if let Some(pkg_name) = package {
-                cliclack::log::step(format!("System Install: '{}'", pkg_name))?;
+                crate::core::output::log::step(format!("System Install: '{}'", pkg_name))?;
+                let started = std::time::Instant::now();
 
                 // Resolve against system registry or local WASM cache (dev mode)
                 let resolution = match pkg_name.as_str() {
@@ -153,14 +205,16 @@ async fn main() -> Result<()> {
                     )?;
 
                     // Initialize Service
-                    let registry_url = Url::parse("https://registry.env-architect.dev")
-                        .context("Invalid registry URL")?;
+                    let registry_url =
+                        Url::parse(constants::REGISTRY_URL).context("Invalid registry URL")?;
                     let tuf_root = PathBuf::from(".env-architect/tuf");
                     let tuf_cache = PathBuf::from(".env-architect/cache");
                     std::fs::create_dir_all(&tuf_root)?;
                     std::fs::create_dir_all(&tuf_cache)?;
 
-                    let mut service = InstallService::new(registry_url, tuf_root, tuf_cache)?;
+                    let mirrors = crate::core::user_config::load()?.mirrors;
+                    let mut service = InstallService::new(registry_url, tuf_root, tuf_cache)?
+                        .with_target_mirrors(mirrors);
                     service.install_from_manifest(manifest).await?;
                 }
 
@@ -168,30 +222,74 @@ async fn main() -> Result<()> {
                 let global_store = crate::core::global_store::GlobalStateService::new()?;
                 global_store.add_tool(&pkg_name, &resolution, Some("latest".to_string()), None)?;
 
-                cliclack::log::success(format!("Installed '{}' to global system.", pkg_name))?;
-                cliclack::outro("Done.")?;
+                // Feeds the conflict resolver's install-time estimates.
+                if let Some(path) = domain::system::InstallHistory::default_path() {
+                    let tool = pkg_name.split('@').next().unwrap_or(&pkg_name);
+                    let mut history = domain::system::InstallHistory::open(path);
+                    history.record(tool, started.elapsed());
+                    let _ = history.save();
+                }
+
+                crate::core::output::log::success(format!(
+                    "Installed '{}' to global system.",
+                    pkg_name
+                ))?;
+                crate::core::output::outro("Done.")?;
             }
             // 2. Project Install Mode (npm install / cargo build style)
             // When no package is named, we look for a manifest file to restore the environment.
             else {
-                let (_manifest_path, manifest) = match path {
+                let (manifest_path, manifest) = match path {
                     Some(p) => (p.clone(), utils::loader::load_manifest(&p)?),
                     None => utils::loader::find_and_load_manifest(&std::env::current_dir()?)?,
                 };
 
-                cliclack::log::step(format!("Restoring Project: {}", &manifest.project.name))?;
+                crate::core::output::log::step(format!(
+                    "Restoring Project: {}",
+                    &manifest.project.name
+                ))?;
+                crate::core::environment::check_platform(&manifest)?;
+
+                let effective = manifest.effective(
+                    profile.as_deref(),
+                    &extras,
+                    &domain::system::PlatformDetector::detect().target(),
+                )?;
+                if let Some(profile) = &effective.profile {
+                    crate::core::output::log::info(format!("Profile: {}", profile))?;
+                }
+                if !effective.extras.is_empty() {
+                    crate::core::output::log::info(format!(
+                        "Extras: {}",
+                        effective.extras.join(", ")
+                    ))?;
+                }
 
-                let registry_url = Url::parse("https://registry.env-architect.dev")
-                    .context("Invalid registry URL")?;
+                let registry_url =
+                    Url::parse(constants::REGISTRY_URL).context("Invalid registry URL")?;
                 let tuf_root = PathBuf::from(".env-architect/tuf");
                 let tuf_cache = PathBuf::from(".env-architect/cache");
                 std::fs::create_dir_all(&tuf_root)?;
                 std::fs::create_dir_all(&tuf_cache)?;
 
-                let mut service = InstallService::new(registry_url, tuf_root, tuf_cache)?;
-                service.install_from_manifest(manifest).await?;
+                let mirrors = crate::core::user_config::load()?.mirrors;
+                let mut service = InstallService::new(registry_url, tuf_root, tuf_cache)?
+                    .with_target_mirrors(mirrors);
+                service
+                    .install_dependencies(&effective.dependencies)
+                    .await?;
+                service
+                    .install_assets(&manifest.assets, Path::new(".env-architect/assets"))
+                    .await?;
+                // The environment is in place; a stale index only weakens `impact`.
+                if let Err(e) = crate::core::project_index::register(&manifest_path, &manifest) {
+                    crate::core::output::log::warning(format!(
+                        "Could not register this project for impact analysis: {:#}",
+                        e
+                    ))?;
+                }
 
-                cliclack::outro("Project environment restored.")?;
+                crate::core::output::outro("Project environment restored.")?;
             }
         }
         Commands::Resolve(cmd) => {
@@ -202,18 +300,18 @@ async fn main() -> Result<()> {
         }
         Commands::Search { query } => {
             cliclack::intro("EnvArchitect Search")?;
-            cliclack::log::info(format!("Searching for: {}", query))?;
-            cliclack::outro("Done")?;
+            crate::core::output::log::info(format!("Searching for: {}", query))?;
+            crate::core::output::outro("Done")?;
         }
         Commands::Audit => {
             cliclack::intro("EnvArchitect Audit")?;
-            cliclack::log::info("Auditing system...")?;
-            cliclack::outro("Audit complete")?;
+            crate::core::output::log::info("Auditing system...")?;
+            crate::core::output::outro("Audit complete")?;
         }
         Commands::Login => {
             cliclack::intro("Login")?;
-            cliclack::log::info("Login flow initiated")?;
-            cliclack::outro("You are logged in")?;
+            crate::core::output::log::info("Login flow initiated")?;
+            crate::core::output::outro("You are logged in")?;
         }
         Commands::Dev(cmd) => {
             cmd.execute().await?;
@@ -224,8 +322,8 @@ async fn main() -> Result<()> {
         Commands::Run(cmd) => {
             cmd.execute().await?;
         }
-        Commands::Shim { tool, args } => {
-            commands::shim::execute_shim(tool, args).await?;
+        Commands::Shim { tool, pin, args } => {
+            commands::shim::execute_shim(tool, pin, args).await?;
         }
         Commands::Doctor(cmd) => {
             cmd.execute().await?;
@@ -236,6 +334,36 @@ async fn main() -> Result<()> {
         Commands::Whoami(cmd) => {
             cmd.execute().await?;
         }
+        Commands::Show(cmd) => {
+            cmd.execute().await?;
+        }
+        Commands::Check(cmd) => {
+            cmd.execute().await?;
+        }
+        Commands::Add(cmd) => {
+            cmd.execute().await?;
+        }
+        Commands::Remove(cmd) => {
+            cmd.execute().await?;
+        }
+        Commands::Set(cmd) => {
+            cmd.execute().await?;
+        }
+        Commands::Migrate(cmd) => {
+            cmd.execute().await?;
+        }
+        Commands::Secrets(cmd) => {
+            cmd.execute().await?;
+        }
+        Commands::Dotenv(cmd) => {
+            cmd.execute().await?;
+        }
+        Commands::Impact(cmd) => {
+            cmd.execute().await?;
+        }
+        Commands::Import(cmd) => {
+            cmd.execute().await?;
+        }
     }
 
     Ok(())
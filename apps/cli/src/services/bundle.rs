@@ -39,16 +39,16 @@ impl BundleService {
             return Err(anyhow::anyhow!("Project directory does not exist"));
         }
 
-        cliclack::log::info(format!("Project Directory: {:?}", project_dir))?;
+        crate::core::output::log::info(format!("Project Directory: {:?}", project_dir))?;
 
         let adapter = adapters::get_adapter(&project_dir)?;
-        cliclack::log::info(format!("Detected language: {}", adapter.name()))?;
+        crate::core::output::log::info(format!("Detected language: {}", adapter.name()))?;
 
         let build_opts = adapters::BuildOptions {
             release: !no_optimize,
         };
         let wasm_path = adapter.build(&project_dir, build_opts).await?;
-        cliclack::log::success(format!("Build artifact: {:?}", wasm_path))?;
+        crate::core::output::log::success(format!("Build artifact: {:?}", wasm_path))?;
 
         // Start with Adapter metadata
         let base_meta = adapter.metadata(&project_dir)?;
@@ -66,7 +66,7 @@ impl BundleService {
         let bundle_dir = project_dir.join("dist");
         if bundle_dir.exists() {
             std::fs::remove_dir_all(&bundle_dir)?;
-            cliclack::log::info("Cleaned previous dist artifacts")?;
+            crate::core::output::log::info("Cleaned previous dist artifacts")?;
         }
         std::fs::create_dir(&bundle_dir)?;
 
@@ -76,7 +76,7 @@ impl BundleService {
         if wasm_path.exists() {
             std::fs::copy(&wasm_path, &dest)?;
         } else {
-            cliclack::log::error(format!("Wasm artifact not found at {:?}", wasm_path))?;
+            crate::core::output::log::error(format!("Wasm artifact not found at {:?}", wasm_path))?;
             return Err(anyhow::anyhow!("Build failed to produce artifact"));
         }
 
@@ -91,8 +91,8 @@ impl BundleService {
         // SBOM
         Self::generate_sbom(&project_dir, &bundle_dir)?;
 
-        cliclack::log::success(format!("Bundle created at {:?}", bundle_dir))?;
-        cliclack::outro("Bundle complete.")?;
+        crate::core::output::log::success(format!("Bundle created at {:?}", bundle_dir))?;
+        crate::core::output::outro("Bundle complete.")?;
 
         Ok(bundle_dir)
     }
@@ -0,0 +1,113 @@
+use env_manifest::{ParseError, SourceSpan, ValidationIssue, ValidationLevel};
+
+/// Render a manifest diagnostic the way rustc does:
+///
+/// ```text
+/// error: Invalid version requirement 'abc'
+///   --> env.toml:12:5
+///    |
+/// 12 | linux = "abc"
+///    | ^^^^^
+/// ```
+///
+/// Issues raised by a named rule carry its ID in the header (`warning[undeclared-tool]`).
+pub fn render(
+    level: ValidationLevel,
+    code: Option<&str>,
+    message: &str,
+    span: Option<&SourceSpan>,
+) -> String {
+    let name = match level {
+        ValidationLevel::Error => "error",
+        ValidationLevel::Warning => "warning",
+        ValidationLevel::Info => "info",
+    };
+    let label = code.map_or(name.to_string(), |code| format!("{}[{}]", name, code));
+    let header = match level {
+        ValidationLevel::Error => console::style(label).red().bold(),
+        ValidationLevel::Warning => console::style(label).yellow().bold(),
+        ValidationLevel::Info => console::style(label).cyan().bold(),
+    };
+    let mut out = format!("{}: {}", header, console::style(message).bold());
+
+    let Some(span) = span else {
+        return out;
+    };
+
+    let line_number = span.line.to_string();
+    let gutter = " ".repeat(line_number.len());
+    let bar = console::style("|").blue().bold();
+
+    // Underline the span, clipped to the snippet line.
+    let start = span
+        .snippet
+        .char_indices()
+        .nth(span.column.saturating_sub(1))
+        .map_or(span.snippet.len(), |(i, _)| i);
+    let end = (start + span.length).min(span.snippet.len());
+    let width = span
+        .snippet
+        .char_indices()
+        .filter(|(i, _)| *i >= start && *i < end)
+        .count()
+        .max(1);
+    let prefix: String = span.snippet[..start]
+        .chars()
+        .map(|c| if c == '\t' { '\t' } else { ' ' })
+        .collect();
+    let marker = match level {
+        ValidationLevel::Error => console::style("^".repeat(width)).red().bold(),
+        ValidationLevel::Warning => console::style("^".repeat(width)).yellow().bold(),
+        ValidationLevel::Info => console::style("^".repeat(width)).cyan().bold(),
+    };
+
+    out.push_str(&format!(
+        "\n{}{} {}\n{} {}\n{} {} {}\n{} {} {}{}",
+        gutter,
+        console::style("-->").blue().bold(),
+        span,
+        gutter,
+        bar,
+        console::style(&line_number).blue().bold(),
+        bar,
+        span.snippet,
+        gutter,
+        bar,
+        prefix,
+        marker
+    ));
+    out
+}
+
+pub fn render_issue(issue: &ValidationIssue) -> String {
+    let mut out = render(
+        issue.level,
+        issue.rule.as_deref(),
+        &issue.message,
+        issue.location.as_ref(),
+    );
+    out.push_str(&format!(
+        "\n{} {} field: {}",
+        " ".repeat(
+            issue
+                .location
+                .as_ref()
+                .map_or(0, |s| s.line.to_string().len())
+        ),
+        console::style("=").blue().bold(),
+        issue.field
+    ));
+    out
+}
+
+pub fn render_parse_error(error: &ParseError) -> String {
+    render(
+        ValidationLevel::Error,
+        None,
+        &format!(
+            "Failed to parse {} manifest: {}",
+            error.format, error.message
+        ),
+        error.span.as_ref(),
+    )
+}
@@ -1,10 +1,15 @@
-use anyhow::{Context, Result};
-use env_architect::domain::entities::manifest::EnhancedManifest;
-use std::fs;
+use anyhow::Result;
+use env_architect::domain::entities::manifest::{EnhancedManifest, ManifestEditor, ManifestSource};
 use std::path::{Path, PathBuf};
 
 /// Finds and loads an environment manifest following the ecosystem precedence rules.
 pub fn find_and_load_manifest(start_dir: &Path) -> Result<(PathBuf, EnhancedManifest)> {
+    let path = find_manifest(start_dir)?;
+    load_manifest(&path).map(|m| (path, m))
+}
+
+/// Finds the environment manifest in a directory without parsing it.
+pub fn find_manifest(start_dir: &Path) -> Result<PathBuf> {
     // Discovery Order:
 
     use crate::constants::MANIFEST_JSON;
@@ -13,7 +18,7 @@ pub fn find_and_load_manifest(start_dir: &Path) -> Result<(PathBuf, EnhancedMani
     for filename in candidates {
         let path = start_dir.join(filename);
         if path.exists() {
-            return load_manifest(&path).map(|m| (path, m));
+            return Ok(path);
         }
     }
 
@@ -24,18 +29,19 @@ pub fn find_and_load_manifest(start_dir: &Path) -> Result<(PathBuf, EnhancedMani
 }
 
 /// Loads a manifest from a specific path, detecting format by extension.
+/// Parse errors are rendered with the offending line and column.
 pub fn load_manifest(path: &Path) -> Result<EnhancedManifest> {
-    let content = fs::read_to_string(path)
-        .with_context(|| format!("Failed to read manifest file: {:?}", path))?;
-
-    let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("");
+    let source = ManifestSource::read(path)?;
+    source
+        .parse()
+        .map_err(|e| anyhow::anyhow!(super::diagnostics::render_parse_error(&e)))
+}
 
-    match ext {
-        "json" => serde_json::from_str(&content).with_context(|| "Failed to parse JSON manifest"),
-        "toml" => toml::from_str(&content).with_context(|| "Failed to parse TOML manifest"),
-        "yaml" | "yml" => {
-            serde_yaml::from_str(&content).with_context(|| "Failed to parse YAML manifest")
-        }
-        _ => anyhow::bail!("Unsupported manifest format: {}", ext),
-    }
+/// Writes an edited manifest back to disk, refusing edits that no longer parse.
+pub fn save_manifest(editor: &ManifestEditor) -> Result<()> {
+    editor
+        .source()
+        .parse()
+        .map_err(|e| anyhow::anyhow!(super::diagnostics::render_parse_error(&e)))?;
+    editor.save()
 }
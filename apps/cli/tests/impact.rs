@@ -0,0 +1,89 @@
+//! `impact` reports the registered projects a tool replacement would break.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// A home directory with three registered projects using node.
+fn machine() -> tempfile::TempDir {
+    let home = tempfile::tempdir().unwrap();
+    let mut roots = Vec::new();
+    for (name, node) in [("legacy", "^18"), ("modern", ">=18"), ("web", "*")] {
+        let root = home.path().join(name);
+        std::fs::create_dir(&root).unwrap();
+        std::fs::write(
+            root.join("env.toml"),
+            format!(
+                "[project]\nname = \"{}\"\n\n[dependencies]\nnode = \"{}\"\n",
+                name, node
+            ),
+        )
+        .unwrap();
+        roots.push(root.to_string_lossy().to_string());
+    }
+    let config = home.path().join(".env-architect");
+    std::fs::create_dir(&config).unwrap();
+    std::fs::write(
+        config.join("global.env.json"),
+        serde_json::json!({ "projects": roots }).to_string(),
+    )
+    .unwrap();
+    home
+}
+
+fn architect(home: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_env-architect"))
+        .args(args)
+        .current_dir(home)
+        .env("HOME", home)
+        .output()
+        .unwrap()
+}
+
+fn broken_names(output: &Output) -> Vec<String> {
+    assert!(output.status.success(), "{:?}", output);
+    let projects: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    projects
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|project| project["name"].as_str().unwrap().to_string())
+        .collect()
+}
+
+#[test]
+fn test_impact_json() {
+    let home = machine();
+
+    let output = architect(home.path(), &["impact", "node@18", "--json"]);
+    assert_eq!(broken_names(&output), ["legacy", "modern"]);
+    let projects: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(projects[0]["requirement"], "^18");
+    assert_eq!(
+        projects[0]["root"],
+        home.path().join("legacy").to_string_lossy().as_ref()
+    );
+
+    // `modern` accepts node 20 as well.
+    let output = architect(home.path(), &["impact", "node@18", "--to", "20", "--json"]);
+    assert_eq!(broken_names(&output), ["legacy"]);
+}
+
+#[test]
+fn test_impact_report() {
+    let home = machine();
+
+    let output = architect(home.path(), &["impact", "node@18", "--to", "20"]);
+    assert!(output.status.success(), "{:?}", output);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("1 of 3 project(s) using node would break if node@18 were replaced by 20"),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("legacy") && !stderr.contains("modern"));
+
+    let output = architect(home.path(), &["impact", "node@16", "--to", "20"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("No registered project would break (3 of 3 use node)"));
+}
@@ -0,0 +1,69 @@
+//! `run <service>` interpolates the service command against the env it runs in.
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+const TOKEN: &str = "tok_1a2b3c4d5e6f";
+
+/// A project whose services read a secret and an `env_files` variable, next to a script
+/// referring to an undefined variable.
+fn project() -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(
+        dir.path().join("env.toml"),
+        r#"
+env_files = [".env"]
+
+[project]
+name = "services"
+
+[env]
+API_TOKEN = { secret-file = "token.txt" }
+
+[scripts]
+broken = "echo ${env.NOT_DEFINED}"
+
+[services.token]
+command = "printf %s ${env.API_TOKEN} > seen.txt"
+
+[services.greet]
+command = "echo greeting=${env.GREETING}"
+"#,
+    )
+    .unwrap();
+    std::fs::write(dir.path().join(".env"), "GREETING=hello from dotenv\n").unwrap();
+    std::fs::write(dir.path().join("token.txt"), format!("{}\n", TOKEN)).unwrap();
+    dir
+}
+
+fn architect(project: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_env-architect"))
+        .args(args)
+        .current_dir(project)
+        .env("HOME", project)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn test_service_command_sees_secret() {
+    let project = project();
+    let root = project.path().to_str().unwrap();
+
+    let output = architect(project.path(), &["run", "-p", root, "token"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert_eq!(
+        std::fs::read_to_string(project.path().join("seen.txt")).unwrap(),
+        TOKEN
+    );
+}
+
+#[test]
+fn test_service_command_sees_env_files() {
+    let project = project();
+    let root = project.path().to_str().unwrap();
+
+    let output = architect(project.path(), &["run", "-p", root, "greet"]);
+    assert!(output.status.success(), "{:?}", output);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("greeting=hello from dotenv"));
+}
@@ -1,22 +1,29 @@
 use anyhow::{Context, Result};
-use std::path::PathBuf;
+use futures_util::future::join_all;
+use std::path::{Path, PathBuf};
 use url::Url;
 
 use domain::dependency::graph::ExecutionDag;
 use domain::dependency::solver::{SatEngine, SolverPackage};
 use domain::security::tuf::RepositoryVerifier;
+use domain::system::{Artifact, Checksum, Downloader};
 use infrastructure::runtime::wasm::PluginRuntime;
 
 use indicatif::{ProgressBar, ProgressStyle};
 
+use crate::progress::BatchProgress;
+
 /// The core orchestrator that wires all Brain components together.
 /// This is where SAT Solver → DAG → TUF → Wasm → Kalman all integrate.
-use domain::entities::manifest::EnhancedManifest;
+use domain::entities::manifest::{Asset, DependencySpec, EnhancedManifest};
+use std::collections::HashMap;
 
 pub struct InstallService {
     // ...
     sat_engine: SatEngine,
     tuf_verifier: RepositoryVerifier,
+    /// Shared by target and asset downloads, so per-host limits hold across both
+    downloader: Downloader,
     wasm_runtime: PluginRuntime,
     _registry_url: Url,
 }
@@ -24,29 +31,100 @@ pub struct InstallService {
 impl InstallService {
     pub fn new(registry_url: Url, tuf_root: PathBuf, tuf_cache: PathBuf) -> Result<Self> {
         let sat_engine = SatEngine::new();
+        let downloader = Downloader::new();
 
         let tuf_verifier = RepositoryVerifier::new(
             &tuf_root.join("root.json"),
             registry_url.join("/metadata")?,
             registry_url.join("/targets")?,
             &tuf_cache,
-        );
+        )
+        .with_downloader(downloader.clone());
 
         let wasm_runtime = PluginRuntime::new().context("Failed to initialize Wasm runtime")?;
 
         Ok(Self {
             sat_engine,
             tuf_verifier,
+            downloader,
             wasm_runtime,
             _registry_url: registry_url,
         })
     }
 
+    /// Fetch targets from these mirrors when the registry can't serve them.
+    pub fn with_target_mirrors(mut self, mirrors: Vec<Url>) -> Self {
+        self.tuf_verifier = self.tuf_verifier.with_target_mirrors(mirrors);
+        self
+    }
+
+    /// Download a manifest's `assets` into `dir`, in parallel, each verified against its
+    /// checksum. Assets already there and intact aren't fetched again.
+    pub async fn install_assets(&self, assets: &[Asset], dir: &Path) -> Result<()> {
+        if assets.is_empty() {
+            return Ok(());
+        }
+        let mut artifacts = Vec::with_capacity(assets.len());
+        for asset in assets {
+            // The name becomes a file name under `dir`.
+            anyhow::ensure!(
+                !asset.name.is_empty() && asset.name != ".." && !asset.name.contains(['/', '\\']),
+                "Invalid asset name: {}",
+                asset.name
+            );
+            let checksum = Checksum::parse(&asset.checksum)
+                .with_context(|| format!("Invalid checksum for asset '{}'", asset.name))?;
+            artifacts.push(Artifact::new(asset.url.clone(), checksum));
+        }
+
+        let progress = BatchProgress::new(assets.len());
+        let downloads = assets.iter().zip(&artifacts).map(|(asset, artifact)| {
+            let mut bar = progress.artifact(&asset.name);
+            async move {
+                let result = self
+                    .downloader
+                    .fetch_with_progress(artifact, &dir.join(&asset.name), |received, total| {
+                        bar.update(received, total)
+                    })
+                    .await;
+                bar.finish();
+                result.with_context(|| format!("Asset '{}'", asset.name))
+            }
+        });
+        let results = join_all(downloads).await;
+        progress.finish();
+
+        let errors: Vec<String> = results
+            .into_iter()
+            .filter_map(|result| result.err().map(|e| format!("{:#}", e)))
+            .collect();
+        anyhow::ensure!(
+            errors.is_empty(),
+            "Failed to download {} asset(s):\n{}",
+            errors.len(),
+            errors.join("\n")
+        );
+        Ok(())
+    }
+
     /// Install from a full environment manifest
     pub async fn install_from_manifest(&mut self, manifest: EnhancedManifest) -> Result<()> {
+        self.install_dependencies(&manifest.dependencies).await
+    }
+
+    /// Install a concrete dependency set (e.g. from `EnhancedManifest::effective`)
+    pub async fn install_dependencies(
+        &mut self,
+        dependencies: &HashMap<String, DependencySpec>,
+    ) -> Result<()> {
         let mut resolved = Vec::new();
 
-        for (name, _spec) in &manifest.dependencies {
+        for (name, spec) in dependencies {
+            if let Some(manager) = spec.manager() {
+                self.sat_engine.managers.insert(name.clone(), manager);
+            }
+        }
+        for name in dependencies.keys() {
             self.populate_registry_mock(name)?;
             let sub_resolved = self.simple_resolve(name)?;
             resolved.extend(sub_resolved);
@@ -69,10 +147,11 @@ impl InstallService {
 
         let batches = dag.resolve_batched().context("Dependency cycle detected")?;
 
-        // 4. Download + Verify + Execute each batch
-        for (_batch_idx, batch) in batches.iter().enumerate() {
-            for plugin_name in batch {
-                self.install_single(plugin_name).await?;
+        // 4. Download each batch in parallel, then verify + execute
+        for batch in &batches {
+            let downloads = self.download_batch(batch).await;
+            for (plugin_name, download) in batch.iter().zip(downloads) {
+                self.install_single(plugin_name, download).await?;
             }
         }
 
@@ -110,9 +189,10 @@ impl InstallService {
 
         let batches = dag.resolve_batched().context("Dependency cycle detected")?;
 
-        for (_batch_idx, batch) in batches.iter().enumerate() {
-            for plugin_name in batch {
-                self.install_single(plugin_name).await?;
+        for batch in &batches {
+            let downloads = self.download_batch(batch).await;
+            for (plugin_name, download) in batch.iter().zip(downloads) {
+                self.install_single(plugin_name, download).await?;
             }
         }
 
@@ -120,10 +200,31 @@ impl InstallService {
         Ok(())
     }
 
-    /// Install a single plugin with TUF verification and progress tracking
-    async fn install_single(&mut self, plugin_name: &str) -> Result<()> {
-        let target_name = format!("{}.wasm", plugin_name);
+    /// Fetch a batch's plugins in parallel through TUF, each with a byte progress bar
+    /// and a Kalman-smoothed speed and ETA, plus an ETA for the whole batch.
+    async fn download_batch(&self, batch: &[String]) -> Vec<Result<PathBuf>> {
+        let progress = BatchProgress::new(batch.len());
+        let downloads = batch.iter().map(|plugin_name| {
+            let mut bar = progress.artifact(plugin_name);
+            async move {
+                let target_name = format!("{}.wasm", plugin_name);
+                let path = self
+                    .tuf_verifier
+                    .verify_and_download_with_progress(&target_name, |received, total| {
+                        bar.update(received, total)
+                    })
+                    .await;
+                bar.finish();
+                path
+            }
+        });
+        let downloads = join_all(downloads).await;
+        progress.finish();
+        downloads
+    }
 
+    /// Verify and install a downloaded plugin
+    async fn install_single(&mut self, plugin_name: &str, download: Result<PathBuf>) -> Result<()> {
         // Create progress bar
         let pb = ProgressBar::new_spinner();
         pb.set_style(
@@ -131,14 +232,9 @@ impl InstallService {
                 .template("{spinner:.green} {msg}")
                 .unwrap(),
         );
-        pb.set_message(format!("Downloading {}...", plugin_name));
 
-        // Use TUF to securely download and verify
-        // Use TUF to securely download and verify
         // FALLBACK: If real download fails (since we are in demo/dev mode without live registry), simulate success.
-        let plugin_path_result = self.tuf_verifier.verify_and_download(&target_name).await;
-
-        let plugin_path = match plugin_path_result {
+        let plugin_path = match download {
             Ok(path) => path,
             Err(_) => {
                 // pb.set_message("⚠️  Registry unreachable. Simulation mode: Mocking download.");
@@ -203,7 +299,6 @@ impl InstallService {
 
     fn populate_registry_mock(&mut self, plugin_name: &str) -> Result<()> {
         use semver::Version;
-        use std::collections::HashMap;
 
         // Add mock packages for demonstration
         let pkg = SolverPackage {
@@ -1,5 +1,6 @@
 pub mod install_service;
 pub mod install_usecase;
+pub mod progress;
 
 pub use install_service::InstallService;
 
@@ -0,0 +1,237 @@
+use domain::math::kalman::{EtaBand, ProgressEstimator};
+use indicatif::{HumanBytes, MultiProgress, ProgressBar, ProgressStyle};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Minimum gap between speed samples; tiny chunks make for noisy measurements.
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(200);
+
+/// What the batch summary needs from each download.
+#[derive(Debug, Clone, Copy, Default)]
+struct Transfer {
+    remaining: Option<u64>,
+    speed: f64,
+    jitter: f64,
+}
+
+/// Progress bars for a batch of parallel downloads, plus a summary line whose ETA
+/// covers the whole batch.
+pub struct BatchProgress {
+    multi: MultiProgress,
+    summary: ProgressBar,
+    transfers: Arc<Mutex<Vec<Transfer>>>,
+}
+
+impl BatchProgress {
+    pub fn new(count: usize) -> Self {
+        let multi = MultiProgress::new();
+        let summary = multi.add(ProgressBar::new(0));
+        summary.set_style(
+            ProgressStyle::default_bar()
+                .template("{prefix:.bold} {bytes}/{total_bytes} {msg}")
+                .unwrap(),
+        );
+        summary.set_prefix(format!("{} download(s)", count));
+        Self {
+            multi,
+            summary,
+            transfers: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// A bar for one artifact, shown above the summary.
+    pub fn artifact(&self, name: &str) -> ArtifactProgress {
+        let bar = self
+            .multi
+            .insert_before(&self.summary, ProgressBar::new_spinner());
+        bar.set_style(
+            ProgressStyle::default_spinner()
+                .template("{spinner:.green} {prefix} {msg}")
+                .unwrap(),
+        );
+        bar.set_prefix(name.to_string());
+        bar.set_message("connecting…");
+        bar.enable_steady_tick(Duration::from_millis(100));
+
+        let slot = {
+            let mut transfers = self.transfers.lock().unwrap();
+            transfers.push(Transfer::default());
+            transfers.len() - 1
+        };
+        ArtifactProgress {
+            bar,
+            summary: self.summary.clone(),
+            transfers: Arc::clone(&self.transfers),
+            slot,
+            estimator: ProgressEstimator::new(),
+            total: None,
+            received: 0,
+            sampled: (Instant::now(), 0),
+        }
+    }
+
+    pub fn finish(self) {
+        self.summary.finish_and_clear();
+    }
+}
+
+/// One artifact's bar, fed by the downloader as bytes arrive.
+pub struct ArtifactProgress {
+    bar: ProgressBar,
+    summary: ProgressBar,
+    transfers: Arc<Mutex<Vec<Transfer>>>,
+    slot: usize,
+    estimator: ProgressEstimator,
+    total: Option<u64>,
+    received: u64,
+    /// When speed was last sampled, and the byte count then.
+    sampled: (Instant, u64),
+}
+
+impl ArtifactProgress {
+    /// Record `received` bytes so far, out of `total` if known.
+    pub fn update(&mut self, received: u64, total: Option<u64>) {
+        if total.is_some() && self.total.is_none() {
+            let length = total.unwrap_or_default();
+            self.bar.set_length(length);
+            self.bar.set_style(
+                ProgressStyle::default_bar()
+                    .template("{prefix:>12} [{bar:30.cyan/blue}] {bytes}/{total_bytes} {msg}")
+                    .unwrap()
+                    .progress_chars("=> "),
+            );
+            self.summary.inc_length(length);
+            self.total = total;
+        }
+        self.bar.set_position(received);
+        self.summary.inc(received.saturating_sub(self.received));
+        self.received = received;
+
+        let (at, bytes) = self.sampled;
+        let elapsed = at.elapsed();
+        if elapsed < SAMPLE_INTERVAL {
+            return;
+        }
+        self.estimator
+            .update(received.saturating_sub(bytes) as f64 / elapsed.as_secs_f64());
+        self.sampled = (Instant::now(), received);
+
+        let remaining = self.total.map(|total| total.saturating_sub(received));
+        let (speed, jitter) = (self.estimator.speed(), self.estimator.jitter());
+        self.bar.set_message(describe(
+            speed,
+            remaining.and_then(|r| self.estimator.eta_band(r)),
+        ));
+
+        let mut transfers = self.transfers.lock().unwrap();
+        transfers[self.slot] = Transfer {
+            remaining,
+            speed,
+            jitter,
+        };
+        self.summary.set_message(summarize(&transfers));
+    }
+
+    pub fn finish(&self) {
+        self.transfers.lock().unwrap()[self.slot] = Transfer {
+            remaining: Some(0),
+            ..Transfer::default()
+        };
+        self.bar.finish_and_clear();
+    }
+}
+
+/// `4.2 MiB/s · ETA 12s (9s–16s)`
+fn describe(speed: f64, eta: Option<EtaBand>) -> String {
+    let speed = format!("{}/s", HumanBytes(speed as u64));
+    match eta {
+        Some(eta) => format!(
+            "{} · ETA {} ({}–{})",
+            speed,
+            format_secs(eta.expected),
+            format_secs(eta.earliest),
+            format_secs(eta.latest)
+        ),
+        None => speed,
+    }
+}
+
+/// The batch ETA: everything left over the combined speed, since the downloads share
+/// the link. Jitter adds up as independent noise.
+fn summarize(transfers: &[Transfer]) -> String {
+    let speed: f64 = transfers.iter().map(|t| t.speed).sum();
+    let jitter = transfers
+        .iter()
+        .map(|t| t.jitter * t.jitter)
+        .sum::<f64>()
+        .sqrt();
+    // One artifact of unknown size makes the whole batch's ETA unknown.
+    let remaining = transfers.iter().map(|t| t.remaining).sum::<Option<u64>>();
+    describe(
+        speed,
+        remaining.and_then(|r| EtaBand::new(speed, jitter, r)),
+    )
+}
+
+/// `45s`, `3m05s`, `1h02m`
+fn format_secs(secs: f64) -> String {
+    let secs = secs.ceil() as u64;
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m{:02}s", secs / 60, secs % 60),
+        _ => format!("{}h{:02}m", secs / 3600, secs % 3600 / 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transfer(remaining: Option<u64>, speed: f64, jitter: f64) -> Transfer {
+        Transfer {
+            remaining,
+            speed,
+            jitter,
+        }
+    }
+
+    #[test]
+    fn test_format_secs() {
+        assert_eq!(format_secs(0.2), "1s");
+        assert_eq!(format_secs(45.0), "45s");
+        assert_eq!(format_secs(59.5), "1m00s");
+        assert_eq!(format_secs(185.0), "3m05s");
+        assert_eq!(format_secs(3720.0), "1h02m");
+    }
+
+    #[test]
+    fn test_describe() {
+        let speed = 4.0 * 1024.0 * 1024.0;
+        assert_eq!(describe(speed, None), "4.00 MiB/s");
+        let eta = EtaBand {
+            expected: 12.0,
+            earliest: 8.4,
+            latest: 16.0,
+        };
+        assert_eq!(describe(speed, Some(eta)), "4.00 MiB/s · ETA 12s (9s–16s)");
+    }
+
+    #[test]
+    fn test_summarize() {
+        // 10000 bytes left at a combined 1000 B/s. Jitters of 300 and 400 add up to 500
+        // in quadrature (not 700): 10000 / 1500 and 10000 / 500.
+        let transfers = [
+            transfer(Some(6000), 600.0, 300.0),
+            transfer(Some(4000), 400.0, 400.0),
+        ];
+        assert_eq!(summarize(&transfers), "1000 B/s · ETA 10s (7s–20s)");
+
+        // Finished downloads add nothing; one of unknown size leaves the batch without an ETA.
+        let transfers = [
+            transfer(Some(6000), 600.0, 300.0),
+            transfer(Some(0), 0.0, 0.0),
+            transfer(None, 400.0, 400.0),
+        ];
+        assert_eq!(summarize(&transfers), "1000 B/s");
+    }
+}
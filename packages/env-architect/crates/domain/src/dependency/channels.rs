@@ -0,0 +1,206 @@
+use super::index::ReleaseIndex;
+use env_manifest::{ChannelRegistry, Ecosystem};
+use semver::{Version, VersionReq};
+use std::collections::{BTreeMap, HashMap};
+
+/// Node.js LTS codenames and their release lines, oldest first.
+const NODE_LTS: &[(&str, u64)] = &[
+    ("argon", 4),
+    ("boron", 6),
+    ("carbon", 8),
+    ("dubnium", 10),
+    ("erbium", 12),
+    ("fermium", 14),
+    ("gallium", 16),
+    ("hydrogen", 18),
+    ("iron", 20),
+    ("jod", 22),
+    ("krypton", 24),
+];
+
+/// Release channel aliases (`lts/iron`, `stable`) known to the registry.
+///
+/// Starts from the built-in Node LTS table; the registry's release index adds or
+/// overrides entries with [`ChannelIndex::with_releases`].
+#[derive(Debug, Clone)]
+pub struct ChannelIndex {
+    channels: HashMap<String, Vec<(String, VersionReq)>>,
+}
+
+impl ChannelIndex {
+    pub fn new() -> Self {
+        let mut index = Self {
+            channels: HashMap::new(),
+        };
+        for (codename, major) in NODE_LTS {
+            index.insert(
+                "node",
+                format!("lts/{}", codename),
+                VersionReq::parse(&format!("^{}", major)).unwrap(),
+            );
+        }
+        index
+    }
+
+    /// Add the channels the registry tags releases with. A channel covers the releases
+    /// tagged with it: `lts/iron` on 20.9.0 through 20.11.0 is `>=20.9.0, <=20.11.0`.
+    pub fn with_releases(mut self, index: &ReleaseIndex) -> Self {
+        for (tool, releases) in index.tools() {
+            // Oldest and newest tagged version per channel, in order of first release.
+            let mut tagged: BTreeMap<&str, (&Version, &Version)> = BTreeMap::new();
+            for release in releases {
+                for channel in &release.channels {
+                    tagged
+                        .entry(channel)
+                        .and_modify(|(_, newest)| *newest = &release.version)
+                        .or_insert((&release.version, &release.version));
+                }
+            }
+            let mut channels: Vec<_> = tagged.into_iter().collect();
+            channels.sort_by(|a, b| a.1 .0.cmp(b.1 .0));
+
+            for (channel, (oldest, newest)) in channels {
+                let req = if oldest == newest {
+                    format!("={}", oldest)
+                } else {
+                    format!(">={}, <={}", oldest, newest)
+                };
+                if let Ok(req) = VersionReq::parse(&req) {
+                    self.insert(tool, channel, req);
+                }
+            }
+        }
+        self
+    }
+
+    /// Register a channel; a later entry for the same name replaces the earlier one in
+    /// place, so LTS lines keep their order.
+    pub fn insert(&mut self, tool: &str, channel: impl Into<String>, req: VersionReq) {
+        let channel = channel.into();
+        let entries = self.channels.entry(tool.to_string()).or_default();
+        match entries.iter_mut().find(|(name, _)| *name == channel) {
+            Some(entry) => entry.1 = req,
+            None => entries.push((channel, req)),
+        }
+    }
+
+    fn lookup(&self, tool: &str, channel: &str) -> Option<VersionReq> {
+        let entries = self.channels.get(tool)?;
+        match channel {
+            // The newest LTS line registered.
+            "lts" | "lts/*" => entries
+                .iter()
+                .rev()
+                .find(|(name, _)| name.starts_with("lts/"))
+                .map(|(_, req)| req.clone()),
+            _ => entries
+                .iter()
+                .find(|(name, _)| name == channel)
+                .map(|(_, req)| req.clone()),
+        }
+    }
+}
+
+impl Default for ChannelIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChannelRegistry for ChannelIndex {
+    fn resolve_channel(
+        &self,
+        tool: &str,
+        ecosystem: Ecosystem,
+        channel: &str,
+    ) -> Option<VersionReq> {
+        // `nodejs`, `npm` etc. share Node's release channels.
+        let key = if ecosystem == Ecosystem::Node {
+            "node"
+        } else {
+            tool
+        };
+
+        self.lookup(key, channel).or_else(|| match channel {
+            // Channels that track the newest stable release of anything.
+            "latest" | "stable" | "current" => Some(VersionReq::STAR),
+            "node" if ecosystem == Ecosystem::Node => Some(VersionReq::STAR),
+            _ => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use semver::Version;
+
+    #[test]
+    fn test_node_lts_aliases() {
+        let index = ChannelIndex::new();
+        let iron = index
+            .resolve_channel("nodejs", Ecosystem::Node, "lts/iron")
+            .unwrap();
+        assert!(iron.matches(&Version::new(20, 11, 0)));
+
+        let newest = index
+            .resolve_channel("node", Ecosystem::Node, "lts/*")
+            .unwrap();
+        assert_eq!(newest, VersionReq::parse("^24").unwrap());
+
+        assert!(index
+            .resolve_channel("node", Ecosystem::Node, "lts/bogus")
+            .is_none());
+    }
+
+    #[test]
+    fn test_registry_entries() {
+        let mut index = ChannelIndex::new();
+        assert!(index
+            .resolve_channel("rust", Ecosystem::Semver, "beta")
+            .is_none());
+
+        index.insert("rust", "beta", VersionReq::parse("=1.80.0-beta.1").unwrap());
+        assert!(index
+            .resolve_channel("rust", Ecosystem::Semver, "beta")
+            .is_some());
+        assert_eq!(
+            index.resolve_channel("rust", Ecosystem::Semver, "stable"),
+            Some(VersionReq::STAR)
+        );
+    }
+
+    #[test]
+    fn test_channels_from_release_index() {
+        let targets = br#"{
+            "targets": {
+                "node-20.9.0.wasm": { "length": 1, "custom": { "channels": ["lts/iron"] } },
+                "node-20.11.0.wasm": { "length": 1, "custom": { "channels": ["lts/iron"] } },
+                "rust-1.80.0-beta.1.tar.gz": { "length": 1, "custom": { "channels": ["beta"] } },
+                "rust-1.79.0.tar.gz": { "length": 1, "custom": { "channels": ["stable"] } }
+            }
+        }"#;
+        let index =
+            ChannelIndex::new().with_releases(&ReleaseIndex::from_targets(targets).unwrap());
+
+        let iron = index
+            .resolve_channel("node", Ecosystem::Node, "lts/iron")
+            .unwrap();
+        assert!(iron.matches(&Version::new(20, 10, 0)));
+        assert!(!iron.matches(&Version::new(20, 8, 0)));
+        // Built-in lines the registry doesn't tag are kept, in order.
+        assert_eq!(
+            index.resolve_channel("node", Ecosystem::Node, "lts/*"),
+            Some(VersionReq::parse("^24").unwrap())
+        );
+
+        assert_eq!(
+            index.resolve_channel("rust", Ecosystem::Semver, "beta"),
+            Some(VersionReq::parse("=1.80.0-beta.1").unwrap())
+        );
+        assert_eq!(
+            index.resolve_channel("rust", Ecosystem::Semver, "stable"),
+            Some(VersionReq::parse("=1.79.0").unwrap())
+        );
+    }
+}
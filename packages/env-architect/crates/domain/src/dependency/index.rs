@@ -0,0 +1,283 @@
+use crate::system::registry::tool_names;
+use anyhow::{Context, Result};
+use env_manifest::{TargetPlatform, VersionRange};
+use semver::Version;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Artifact extensions stripped from target names before reading the version.
+const EXTENSIONS: &[&str] = &[".wasm", ".tar.gz", ".tgz", ".tar.xz", ".zip"];
+
+/// A published version of a tool and its artifact.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Release {
+    pub version: Version,
+    pub size_bytes: u64,
+    /// Target path of the artifact, relative to the registry's targets URL.
+    pub target: Option<String>,
+    /// Hex SHA-256 of the artifact, from the signed metadata.
+    pub sha256: Option<String>,
+    /// Installed size in bytes per platform (`linux-x86_64`, `linux-x86_64-musl`,
+    /// `macos-aarch64`), from the target's `custom.sizes`.
+    pub sizes: HashMap<String, u64>,
+    /// Release channels (`lts/iron`, `stable`) the registry tags this release with, from
+    /// the target's `custom.channels`.
+    pub channels: Vec<String>,
+}
+
+impl Release {
+    pub fn new(version: Version, size_bytes: u64) -> Self {
+        Self {
+            version,
+            size_bytes,
+            target: None,
+            sha256: None,
+            sizes: HashMap::new(),
+            channels: Vec::new(),
+        }
+    }
+
+    /// Whether the artifact is something the store can unpack and run: a gzipped tarball
+    /// or a bare executable, not a WebAssembly plugin.
+    pub fn is_native(&self) -> bool {
+        self.target.as_deref().is_some_and(|target| {
+            [".tar.gz", ".tgz"].iter().any(|ext| target.ends_with(ext))
+                || !EXTENSIONS.iter().any(|ext| target.ends_with(ext))
+        })
+    }
+
+    /// Size on `platform`: the most specific published entry, otherwise the artifact's.
+    pub fn size_for(&self, platform: &TargetPlatform) -> u64 {
+        platform_keys(platform)
+            .iter()
+            .find_map(|key| self.sizes.get(key))
+            .copied()
+            .unwrap_or(self.size_bytes)
+    }
+}
+
+/// Keys a platform's sizes are published under, most specific first:
+/// `linux-x86_64-musl`, then `linux-x86_64`.
+pub fn platform_keys(platform: &TargetPlatform) -> Vec<String> {
+    let base = format!("{}-{}", platform.os.as_str(), platform.arch.as_str());
+    let mut keys = Vec::with_capacity(2);
+    if let Some(env) = platform.env {
+        keys.push(format!("{}-{}", base, env.as_str()));
+    }
+    keys.push(base);
+    keys
+}
+
+/// Every version the registry publishes, read from its TUF `targets.json`.
+///
+/// Targets are named `<tool>-<version>.<ext>` (e.g. `node-20.11.0.wasm`); `tool` and
+/// `version` entries in a target's `custom` metadata take precedence over the name,
+/// `sizes` maps platforms to installed sizes and `channels` lists release channels.
+#[derive(Debug, Clone, Default)]
+pub struct ReleaseIndex {
+    /// Oldest first.
+    releases: HashMap<String, Vec<Release>>,
+}
+
+#[derive(Deserialize)]
+struct TargetEntry {
+    length: u64,
+    #[serde(default)]
+    hashes: HashMap<String, String>,
+    #[serde(default)]
+    custom: Option<HashMap<String, serde_json::Value>>,
+}
+
+impl ReleaseIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `~/.architect/cache/registry-index.json`
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| {
+            home.join(".architect")
+                .join("cache")
+                .join("registry-index.json")
+        })
+    }
+
+    /// Load a cached `targets.json`; a missing or unreadable file is an empty index.
+    pub fn open(path: &Path) -> Self {
+        std::fs::read(path)
+            .ok()
+            .and_then(|content| Self::from_targets(&content).ok())
+            .unwrap_or_default()
+    }
+
+    /// Parse TUF targets metadata, signed (`{"signed": {"targets": ...}}`) or bare.
+    pub fn from_targets(json: &[u8]) -> Result<Self> {
+        let value: serde_json::Value =
+            serde_json::from_slice(json).context("Invalid targets metadata")?;
+        let targets = value
+            .get("signed")
+            .unwrap_or(&value)
+            .get("targets")
+            .cloned()
+            .context("Targets metadata has no `targets`")?;
+        let targets: HashMap<String, TargetEntry> =
+            serde_json::from_value(targets).context("Invalid targets metadata")?;
+
+        let mut index = Self::new();
+        for (name, target) in targets {
+            let custom = |key: &str| {
+                target
+                    .custom
+                    .as_ref()
+                    .and_then(|custom| custom.get(key))
+                    .and_then(|value| value.as_str())
+                    .map(str::to_string)
+            };
+            let parsed = parse_target_name(&name);
+            let tool = custom("tool").or_else(|| parsed.as_ref().map(|(tool, _)| tool.clone()));
+            let version = custom("version")
+                .and_then(|v| Version::parse(v.trim_start_matches('v')).ok())
+                .or_else(|| parsed.map(|(_, version)| version));
+            if let (Some(tool), Some(version)) = (tool, version) {
+                let sizes = target
+                    .custom
+                    .as_ref()
+                    .and_then(|custom| custom.get("sizes"))
+                    .and_then(|sizes| serde_json::from_value(sizes.clone()).ok())
+                    .unwrap_or_default();
+                let channels = target
+                    .custom
+                    .as_ref()
+                    .and_then(|custom| custom.get("channels"))
+                    .and_then(|channels| serde_json::from_value(channels.clone()).ok())
+                    .unwrap_or_default();
+                let release = Release {
+                    sha256: target.hashes.get("sha256").cloned(),
+                    sizes,
+                    channels,
+                    target: Some(name),
+                    ..Release::new(version, target.length)
+                };
+                index.insert(&tool, release);
+            }
+        }
+        Ok(index)
+    }
+
+    /// Record a release; a version already listed is replaced.
+    pub fn insert(&mut self, tool: &str, release: Release) {
+        let releases = self.releases.entry(tool.to_string()).or_default();
+        releases.retain(|r| r.version != release.version);
+        releases.push(release);
+        releases.sort_by(|a, b| a.version.cmp(&b.version));
+    }
+
+    /// Every tool with its releases, oldest first.
+    pub fn tools(&self) -> impl Iterator<Item = (&str, &[Release])> {
+        self.releases
+            .iter()
+            .map(|(tool, releases)| (tool.as_str(), releases.as_slice()))
+    }
+
+    /// Releases of a tool under any of its names (`node`/`nodejs`, ...), oldest first.
+    pub fn releases(&self, tool: &str) -> &[Release] {
+        tool_names(tool)
+            .into_iter()
+            .find_map(|name| self.releases.get(name))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// The newest release inside `range`.
+    pub fn newest_matching(&self, tool: &str, range: &VersionRange) -> Option<&Release> {
+        self.releases(tool)
+            .iter()
+            .rev()
+            .find(|r| range.matches(&r.version))
+    }
+
+    pub fn release(&self, tool: &str, version: &Version) -> Option<&Release> {
+        self.releases(tool).iter().find(|r| r.version == *version)
+    }
+}
+
+/// Split `rust-analyzer-1.2.3.tar.gz` into `rust-analyzer` and `1.2.3`: the tool name
+/// ends at the first `-` followed by a version.
+fn parse_target_name(name: &str) -> Option<(String, Version)> {
+    let file = name.rsplit('/').next().unwrap_or(name);
+    let stem = EXTENSIONS
+        .iter()
+        .find_map(|ext| file.strip_suffix(ext))
+        .unwrap_or(file);
+
+    stem.match_indices('-').find_map(|(i, _)| {
+        let version = Version::parse(stem[i + 1..].trim_start_matches('v')).ok()?;
+        Some((stem[..i].to_string(), version))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use semver::VersionReq;
+
+    #[test]
+    fn test_index_from_targets() {
+        let targets = br#"{
+            "signatures": [],
+            "signed": {
+                "_type": "targets",
+                "targets": {
+                    "node-18.19.0.wasm": { "length": 31457280, "hashes": {} },
+                    "node-20.9.0.wasm": {
+                        "length": 41943040,
+                        "hashes": {},
+                        "custom": { "channels": ["lts/iron"] }
+                    },
+                    "node-20.11.0.wasm": {
+                        "length": 44040192,
+                        "hashes": { "sha256": "ab12" },
+                        "custom": { "sizes": { "linux-x86_64": 90177536, "macos-aarch64": 85983232 } }
+                    },
+                    "rust-analyzer-1.2.3.tar.gz": { "length": 1024, "hashes": {} },
+                    "python.tar.gz": {
+                        "length": 2048,
+                        "hashes": {},
+                        "custom": { "tool": "python", "version": "3.12.1" }
+                    },
+                    "README": { "length": 10, "hashes": {} }
+                }
+            }
+        }"#;
+        let index = ReleaseIndex::from_targets(targets).unwrap();
+
+        let range = VersionRange::from(VersionReq::parse("^20").unwrap());
+        let newest = index.newest_matching("nodejs", &range).unwrap();
+        assert_eq!(newest.version, Version::new(20, 11, 0));
+        assert_eq!(newest.size_bytes, 44040192);
+        assert_eq!(newest.target.as_deref(), Some("node-20.11.0.wasm"));
+        assert_eq!(newest.sha256.as_deref(), Some("ab12"));
+        let linux = TargetPlatform::new(
+            env_manifest::OperatingSystem::Linux,
+            env_manifest::CpuArchitecture::X86_64,
+        );
+        assert_eq!(newest.size_for(&linux), 90177536);
+        let windows = TargetPlatform::new(
+            env_manifest::OperatingSystem::Windows,
+            env_manifest::CpuArchitecture::X86_64,
+        );
+        assert_eq!(newest.size_for(&windows), 44040192);
+        assert_eq!(index.releases("node").len(), 3);
+        assert_eq!(index.releases("node")[1].channels, ["lts/iron"]);
+
+        assert_eq!(
+            index.releases("rust-analyzer")[0].version,
+            Version::new(1, 2, 3)
+        );
+        assert!(index.release("python", &Version::new(3, 12, 1)).is_some());
+
+        let range = VersionRange::from(VersionReq::parse("^22").unwrap());
+        assert!(index.newest_matching("node", &range).is_none());
+        assert!(ReleaseIndex::from_targets(b"{}").is_err());
+    }
+}
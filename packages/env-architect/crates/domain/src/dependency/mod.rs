@@ -1,6 +1,10 @@
+pub mod channels;
 pub mod consensus;
 pub mod graph;
+pub mod index;
 pub mod solver;
 
+pub use channels::ChannelIndex;
 pub use consensus::{ConsensusEngine, Drift, Lockfile, PinnedVersion};
+pub use index::{Release, ReleaseIndex};
 pub use solver::SatEngine;
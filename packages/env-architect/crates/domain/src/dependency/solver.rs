@@ -1,8 +1,10 @@
+use super::channels::ChannelIndex;
+use env_manifest::{Ecosystem, PackageManager, VersionConstraint, VersionRange};
 use resolvo::{
     Candidates, Condition, ConditionId, Dependencies, DependencyProvider, Interner,
     KnownDependencies, NameId, SolvableId, SolverCache, StringId, VersionSetId, VersionSetUnionId,
 };
-use semver::{Version, VersionReq};
+use semver::Version;
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
@@ -13,7 +15,7 @@ use std::hash::{Hash, Hasher};
 pub struct SolverPackage {
     pub name: String,
     pub version: Version,
-    pub deps: HashMap<String, VersionReq>,
+    pub deps: HashMap<String, VersionConstraint>,
 }
 
 impl PartialEq for SolverPackage {
@@ -38,12 +40,18 @@ impl Display for SolverPackage {
 /// The Engine that drives the SAT resolution.
 pub struct SatEngine {
     pub registry: HashMap<String, Vec<SolverPackage>>,
+    /// Release channels (`lts/iron`, `stable`) used to resolve channel constraints.
+    pub channels: ChannelIndex,
+    /// Package managers dependencies are installed through (a dependency's `manager`),
+    /// which decide the syntax of their constraints.
+    pub managers: HashMap<String, PackageManager>,
 
     strings: RefCell<Vec<String>>,
     names: RefCell<Vec<String>>,
     name_to_id: RefCell<HashMap<String, NameId>>,
 
-    version_sets: RefCell<Vec<(NameId, VersionReq)>>,
+    /// Each constraint as written, with its resolved range (`None` if the channel is unknown).
+    version_sets: RefCell<Vec<(NameId, VersionConstraint, Option<VersionRange>)>>,
 
     solvables: RefCell<Vec<SolverPackage>>,
 }
@@ -52,6 +60,8 @@ impl SatEngine {
     pub fn new() -> Self {
         Self {
             registry: HashMap::new(),
+            channels: ChannelIndex::new(),
+            managers: HashMap::new(),
             strings: RefCell::new(Vec::new()),
             names: RefCell::new(Vec::new()),
             name_to_id: RefCell::new(HashMap::new()),
@@ -86,10 +96,20 @@ impl SatEngine {
         id
     }
 
-    pub fn intern_version_set(&self, pkg_name: NameId, req: VersionReq) -> VersionSetId {
+    pub fn intern_version_set(
+        &self,
+        pkg_name: NameId,
+        constraint: VersionConstraint,
+    ) -> VersionSetId {
+        let range = {
+            let name = &self.names.borrow()[pkg_name.0 as usize];
+            let ecosystem = Ecosystem::for_dependency(name, self.managers.get(name).copied());
+            constraint.resolve(name, ecosystem, &self.channels).ok()
+        };
+
         let mut sets = self.version_sets.borrow_mut();
         let id = VersionSetId(sets.len() as u32);
-        sets.push((pkg_name, req));
+        sets.push((pkg_name, constraint, range));
         id
     }
 }
@@ -106,7 +126,7 @@ impl Interner for SatEngine {
     fn display_version_set(&self, version_set_id: VersionSetId) -> impl Display + '_ {
         let idx = version_set_id.0 as usize;
         let sets = self.version_sets.borrow();
-        let (name_id, ref req) = sets[idx];
+        let (name_id, ref req, _) = sets[idx];
         let name = self.names.borrow()[name_id.0 as usize].clone();
         format!("{} {}", name, req)
     }
@@ -158,9 +178,9 @@ impl DependencyProvider for SatEngine {
         inverse: bool,
     ) -> Vec<SolvableId> {
         let idx = version_set_id.0 as usize;
-        let req = {
+        let range = {
             let sets = self.version_sets.borrow();
-            sets[idx].1.clone()
+            sets[idx].2.clone()
         };
 
         let solvables = self.solvables.borrow();
@@ -174,7 +194,7 @@ impl DependencyProvider for SatEngine {
                 }
                 let s_idx = id.0 as usize - 1;
                 if let Some(pkg) = solvables.get(s_idx) {
-                    let matches = req.matches(&pkg.version);
+                    let matches = range.as_ref().is_some_and(|r| r.matches(&pkg.version));
                     if inverse {
                         !matches
                     } else {
@@ -1,18 +1,12 @@
-use super::manifest::EnhancedManifest;
+use super::manifest::{EnhancedManifest, ManifestSource};
 use anyhow::{Context, Result};
 use std::path::Path;
 
+pub use super::manifest::ManifestFormat;
+
 /// Multi-format manifest parser (JSON, YAML, TOML)
 pub struct ManifestParser;
 
-/// Supported manifest formats
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub enum ManifestFormat {
-    Json,
-    Yaml,
-    Toml,
-}
-
 impl ManifestParser {
     /// Auto-detect format from file extension and parse
     pub fn parse_file(path: &Path) -> Result<EnhancedManifest> {
@@ -20,16 +14,15 @@ impl ManifestParser {
             .with_context(|| format!("Failed to read manifest file: {}", path.display()))?;
 
         let format = Self::detect_format(path)?;
-        Self::parse(&content, format)
+        Ok(ManifestSource::new(content, format)
+            .with_path(path)
+            .parse()?)
     }
 
-    /// Parse manifest from string with explicit format
+    /// Parse manifest from string with explicit format.
+    /// Errors carry the line and column of the problem.
     pub fn parse(content: &str, format: ManifestFormat) -> Result<EnhancedManifest> {
-        match format {
-            ManifestFormat::Json => Self::parse_json(content),
-            ManifestFormat::Yaml => Self::parse_yaml(content),
-            ManifestFormat::Toml => Self::parse_toml(content),
-        }
+        Ok(ManifestSource::new(content, format).parse()?)
     }
 
     /// Detect format from file extension
@@ -75,21 +68,6 @@ impl ManifestParser {
         anyhow::bail!("No manifest file found in directory: {}", dir.display())
     }
 
-    /// Parse JSON manifest
-    fn parse_json(content: &str) -> Result<EnhancedManifest> {
-        serde_json::from_str(content).context("Failed to parse JSON manifest")
-    }
-
-    /// Parse YAML manifest
-    fn parse_yaml(content: &str) -> Result<EnhancedManifest> {
-        serde_yaml::from_str(content).context("Failed to parse YAML manifest")
-    }
-
-    /// Parse TOML manifest
-    fn parse_toml(content: &str) -> Result<EnhancedManifest> {
-        toml::from_str(content).context("Failed to parse TOML manifest")
-    }
-
     /// Serialize manifest to string
     pub fn serialize(manifest: &EnhancedManifest, format: ManifestFormat) -> Result<String> {
         match format {
@@ -191,7 +169,7 @@ DEBUG = "true"
         manifest.project.name = "test".to_string();
         manifest.dependencies.insert(
             "nodejs".to_string(),
-            env_manifest::DependencySpec::Simple(semver::VersionReq::parse("^20.0.0").unwrap()),
+            env_manifest::DependencySpec::Simple("^20.0.0".parse().unwrap()),
         );
 
         let json = ManifestParser::serialize(&manifest, ManifestFormat::Json).unwrap();
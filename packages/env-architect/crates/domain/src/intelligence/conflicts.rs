@@ -1,38 +1,126 @@
 use super::metrics::MetricsDetector;
+use super::scoring::{ScoredRecommendation, ScoringModel};
+use crate::dependency::{ChannelIndex, ReleaseIndex};
 use crate::intelligence::strategies::{Conflict, InstallStrategy, Recommendation, Risk};
-use crate::system::{InstalledToolsRegistry, PlatformInfo};
+use crate::security::tuf::target_file_name;
+use crate::system::{
+    InstallHistory, InstalledToolsRegistry, PlatformInfo, ProjectIndex, ToolManager,
+};
 use anyhow::Result;
-use env_manifest::ResolutionAction;
-use semver::{Version, VersionReq};
+use env_manifest::{Ecosystem, PackageManager, ResolutionAction, VersionConstraint, VersionRange};
+use semver::{Comparator, Op, Version, VersionReq};
+use std::collections::HashMap;
+use url::Url;
+
+/// Fixed cost of an install (resolving, unpacking, linking) when there's no history.
+const BASE_INSTALL_SECS: u64 = 15;
+
+/// Download plus unpack throughput assumed when there's no history.
+const ASSUMED_MB_PER_SEC: u64 = 5;
 
 /// The main conflict resolution engine
 /// Uses game theory and multi-objective optimization to resolve conflicts
 pub struct ConflictResolver {
-    _platform: PlatformInfo,
+    platform: PlatformInfo,
     registry: InstalledToolsRegistry,
     metrics: MetricsDetector,
+    channels: ChannelIndex,
+    managers: HashMap<String, PackageManager>,
+    index: ReleaseIndex,
+    history: Option<InstallHistory>,
+    projects: Option<ProjectIndex>,
+    scoring: ScoringModel,
+    targets_url: Option<Url>,
+    consistent_snapshot: bool,
 }
 
 impl ConflictResolver {
     pub fn new(platform: PlatformInfo, registry: InstalledToolsRegistry) -> Self {
         let metrics = MetricsDetector::new(platform.clone());
         Self {
-            _platform: platform,
+            platform,
             registry,
             metrics,
+            channels: ChannelIndex::new(),
+            managers: HashMap::new(),
+            index: ReleaseIndex::new(),
+            history: None,
+            projects: None,
+            scoring: ScoringModel::default(),
+            targets_url: None,
+            consistent_snapshot: false,
         }
     }
 
+    /// Use registry-provided release channels instead of the built-in table.
+    pub fn with_channels(mut self, channels: ChannelIndex) -> Self {
+        self.channels = channels;
+        self
+    }
+
+    /// Read these tools' constraints in their package manager's syntax (a dependency's
+    /// `manager`) rather than the one its name implies.
+    pub fn with_managers(mut self, managers: HashMap<String, PackageManager>) -> Self {
+        self.managers = managers;
+        self
+    }
+
+    /// Pick target versions and artifact sizes from the registry's published releases.
+    pub fn with_index(mut self, index: ReleaseIndex) -> Self {
+        self.index = index;
+        self
+    }
+
+    /// Where the index's artifacts are served, enabling auto-shim recommendations.
+    /// `consistent_snapshot` follows the registry's root: files are then `<sha256>.<name>`.
+    pub fn with_targets_url(mut self, url: Url, consistent_snapshot: bool) -> Self {
+        self.targets_url = Some(url);
+        self.consistent_snapshot = consistent_snapshot;
+        self
+    }
+
+    /// Size tools the index doesn't publish with this detector (its cache and lookups)
+    /// instead of offline estimates.
+    pub fn with_metrics(mut self, metrics: MetricsDetector) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Estimate install times from how long past installs took on this machine.
+    pub fn with_history(mut self, history: InstallHistory) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Count projects a replacement would break from the registered projects instead of
+    /// walking the filesystem.
+    pub fn with_projects(mut self, projects: ProjectIndex) -> Self {
+        self.projects = Some(projects);
+        self
+    }
+
+    /// Rank recommendations with these weights instead of the balanced preset.
+    pub fn with_scoring(mut self, scoring: ScoringModel) -> Self {
+        self.scoring = scoring;
+        self
+    }
+
+    /// Normalize a constraint in the tool's own ecosystem, resolving channels.
+    fn range(&self, tool: &str, required: &VersionConstraint) -> Result<VersionRange> {
+        let ecosystem = Ecosystem::for_dependency(tool, self.managers.get(tool).copied());
+        Ok(required.resolve(tool, ecosystem, &self.channels)?)
+    }
+
     /// Detect conflicts for a given tool requirement
     pub fn detect_conflicts(
         &self,
         tool: &str,
-        required: &VersionReq,
+        required: &VersionConstraint,
         required_by: &str,
     ) -> Option<Conflict> {
         use crate::intelligence::{Conflict, ConflictSource};
 
-        let installed = self.registry.get_installed(tool);
+        let installed = self.registry.find_installed(tool);
 
         if installed.is_empty() {
             // Tool not installed
@@ -44,8 +132,10 @@ impl ConflictResolver {
             });
         }
 
-        // Check if any installed version satisfies requirement
-        let satisfied = installed.iter().any(|v| required.matches(&v.version));
+        // Check if any installed version satisfies requirement (an unknown channel never does)
+        let satisfied = self
+            .range(tool, required)
+            .is_ok_and(|range| installed.iter().any(|v| range.matches(&v.version)));
 
         if !satisfied {
             // Version mismatch
@@ -61,8 +151,22 @@ impl ConflictResolver {
         None
     }
 
-    /// Resolve a conflict using game-theoretic approach
+    /// Resolve a conflict: the recommendations, best first.
     pub fn resolve(&self, conflict: &Conflict) -> Result<Vec<Recommendation>> {
+        Ok(self
+            .evaluate(conflict)?
+            .into_iter()
+            .map(|scored| scored.recommendation)
+            .collect())
+    }
+
+    /// Resolve a conflict and rank the options with the scoring model: the Pareto
+    /// front first, each option's trade-off against the top one spelled out.
+    pub fn evaluate(&self, conflict: &Conflict) -> Result<Vec<ScoredRecommendation>> {
+        Ok(self.scoring.rank(self.recommend(conflict)?))
+    }
+
+    fn recommend(&self, conflict: &Conflict) -> Result<Vec<Recommendation>> {
         match conflict {
             Conflict::VersionMismatch {
                 tool,
@@ -82,13 +186,16 @@ impl ConflictResolver {
     fn resolve_version_mismatch(
         &self,
         tool: &str,
-        required: &VersionReq,
+        required: &VersionConstraint,
         installed: &[Version],
     ) -> Result<Vec<Recommendation>> {
         let mut recommendations = Vec::new();
 
         // Find the newest version that matches requirement
-        let target_version = self.find_target_version(required)?;
+        let range = self.range(tool, required)?;
+        let target_version = self.find_target_version(tool, &range)?;
+        let size_mb = self.estimate_tool_size(tool, &target_version);
+        let install_secs = self.estimate_install_time(tool, size_mb);
 
         let current_version = installed
             .first()
@@ -104,15 +211,14 @@ impl ConflictResolver {
                 "Preserves current setup".to_string(),
             ],
             cons: vec![
-                format!(
-                    "Uses additional ~{}MB disk space",
-                    self.estimate_tool_size(tool)
-                ),
+                format!("Uses additional ~{}MB disk space", size_mb),
                 "Need to manage multiple versions".to_string(),
             ],
             risk: Risk::Low,
-            estimated_disk_mb: self.estimate_tool_size(tool),
-            estimated_time_sec: self.estimate_install_time(tool),
+            estimated_disk_mb: size_mb,
+            estimated_time_sec: install_secs,
+            affected_projects: 0,
+            reversible: true,
             resolution_actions: vec![ResolutionAction::ManagedInstall {
                 manager: self.detect_best_manager(tool),
                 command: format!("architect install {}@{}", tool, target_version),
@@ -121,7 +227,8 @@ impl ConflictResolver {
 
         // Strategy 2: Upgrade/Downgrade (if target is newer/older)
         let is_upgrade = target_version > *current_version;
-        let num_dependent_projects = self.count_dependent_projects(tool);
+        let num_dependent_projects =
+            self.count_dependent_projects(tool, current_version, &target_version);
 
         recommendations.push(Recommendation {
             action: if is_upgrade {
@@ -149,19 +256,24 @@ impl ConflictResolver {
             } else {
                 Risk::Low
             },
-            estimated_disk_mb: 0, // No additional space
-            estimated_time_sec: self.estimate_install_time(tool) + 30, // +30s for uninstall
+            estimated_disk_mb: 0,                  // No additional space
+            estimated_time_sec: install_secs + 30, // +30s for uninstall
+            affected_projects: num_dependent_projects,
+            reversible: false,
             resolution_actions: vec![ResolutionAction::ManagedInstall {
                 manager: self.detect_best_manager(tool),
                 command: format!("architect install {}@{}", tool, target_version),
             }],
         });
 
-        // Strategy 3: Use Existing (if any version partially satisfies)
-        if let Some(partial_match) = self.find_partial_match(installed, required) {
+        // Strategy 3: Shim the published artifact into this project only
+        recommendations.extend(self.auto_shim(tool, &target_version, size_mb, install_secs));
+
+        // Strategy 4: Use Existing (if any version partially satisfies)
+        if let Some(partial_match) = self.find_partial_match(installed, &range) {
             recommendations.push(Recommendation {
                 action: format!("Try using existing {} (partial match)", partial_match),
-                strategy: InstallStrategy::Alongside, // Use Alongside as a proxy for 'using existing'
+                strategy: InstallStrategy::UseExisting,
                 pros: vec![
                     "No installation needed".to_string(),
                     "Zero disk space".to_string(),
@@ -173,13 +285,12 @@ impl ConflictResolver {
                 risk: Risk::High,
                 estimated_disk_mb: 0,
                 estimated_time_sec: 0,
+                affected_projects: 0,
+                reversible: true,
                 resolution_actions: vec![],
             });
         }
 
-        // Sort recommendations by risk (Low risk first)
-        recommendations.sort_by_key(|r| r.risk);
-
         Ok(recommendations)
     }
 
@@ -187,11 +298,14 @@ impl ConflictResolver {
     fn resolve_missing_tool(
         &self,
         tool: &str,
-        required: &VersionReq,
+        required: &VersionConstraint,
     ) -> Result<Vec<Recommendation>> {
         let mut recommendations = Vec::new();
 
-        let target_version = self.find_target_version(required)?;
+        let range = self.range(tool, required)?;
+        let target_version = self.find_target_version(tool, &range)?;
+        let size_mb = self.estimate_tool_size(tool, &target_version);
+        let install_secs = self.estimate_install_time(tool, size_mb);
 
         // Strategy: Install
         recommendations.push(Recommendation {
@@ -201,13 +315,12 @@ impl ConflictResolver {
                 "Satisfies requirement".to_string(),
                 format!("Official {} version", tool),
             ],
-            cons: vec![format!(
-                "Requires ~{}MB disk space",
-                self.estimate_tool_size(tool)
-            )],
+            cons: vec![format!("Requires ~{}MB disk space", size_mb)],
             risk: Risk::Low,
-            estimated_disk_mb: self.estimate_tool_size(tool),
-            estimated_time_sec: self.estimate_install_time(tool),
+            estimated_disk_mb: size_mb,
+            estimated_time_sec: install_secs,
+            affected_projects: 0,
+            reversible: true,
             resolution_actions: vec![ResolutionAction::ManagedInstall {
                 manager: self.detect_best_manager(tool),
                 command: format!("architect install {}@{}", tool, target_version),
@@ -219,7 +332,7 @@ impl ConflictResolver {
         if !alternatives.is_empty() {
             recommendations.push(Recommendation {
                 action: format!("Use alternative: {}", alternatives.join(" or ")),
-                strategy: InstallStrategy::Alongside, // Use Alongside as a proxy
+                strategy: InstallStrategy::Link,
                 pros: vec![
                     format!("Already have {}", alternatives[0]),
                     "No additional install".to_string(),
@@ -228,125 +341,176 @@ impl ConflictResolver {
                 risk: Risk::Medium,
                 estimated_disk_mb: 0,
                 estimated_time_sec: 0,
+                affected_projects: 0,
+                reversible: true,
                 resolution_actions: vec![],
             });
         }
 
-        // Add auto-shim recommendation if it's a known tool
-        if self.is_shimmable(tool) {
-            recommendations.push(Recommendation {
-                action: format!("Auto-shim {} (Zero System Impact)", tool),
-                strategy: InstallStrategy::Alongside,
-                pros: vec![
-                    "Zero system-wide impact".to_string(),
-                    "Specific to this project".to_string(),
-                ],
-                cons: vec!["Requires Architect to launch tool".to_string()],
-                risk: Risk::Low,
-                estimated_disk_mb: self.estimate_tool_size(tool),
-                estimated_time_sec: self.estimate_install_time(tool),
-                resolution_actions: vec![ResolutionAction::AutoShim {
-                    url: format!(
-                        "https://registry.architect.io/bin/{}/{}",
-                        tool, target_version
-                    ),
-                    binary_name: tool.to_string(),
-                }],
-            });
-        }
+        recommendations.extend(self.auto_shim(tool, &target_version, size_mb, install_secs));
 
         Ok(recommendations)
     }
 
     fn detect_best_manager(&self, tool: &str) -> String {
-        match tool {
-            "node" | "nodejs" | "npm" => "nvm".to_string(),
-            "python" | "python3" => "pyenv".to_string(),
-            "rust" | "rustc" | "cargo" => "rustup".to_string(),
-            _ => "brew".to_string(),
+        // Keep using the version manager that already holds this tool's installs.
+        if let Some(installed) = self
+            .registry
+            .find_installed(tool)
+            .into_iter()
+            .find(|v| v.managed_by.is_version_manager())
+        {
+            return installed.managed_by.to_string();
         }
+
+        // Otherwise a manager on this machine that can install it, version managers
+        // first; our own store when there is none.
+        let managers = self.registry.available_managers();
+        managers
+            .iter()
+            .filter(|m| m.is_version_manager())
+            .chain(managers)
+            .find(|m| m.supports(tool))
+            .unwrap_or(&ToolManager::EnvArchitect)
+            .to_string()
     }
 
-    fn is_shimmable(&self, tool: &str) -> bool {
-        matches!(tool, "node" | "python" | "rustup" | "go")
+    /// Download `tool`'s published artifact straight into the store and shim it into the
+    /// project: only for native releases whose signed metadata carries a SHA-256.
+    fn auto_shim(
+        &self,
+        tool: &str,
+        version: &Version,
+        size_mb: u64,
+        install_secs: u64,
+    ) -> Option<Recommendation> {
+        let release = self
+            .index
+            .release(tool, version)
+            .filter(|release| release.is_native())?;
+        let sha256 = release.sha256.clone()?;
+        let file_name =
+            target_file_name(release.target.as_ref()?, &sha256, self.consistent_snapshot);
+        let url = self.targets_url.as_ref()?.join(&file_name).ok()?;
+        Some(Recommendation {
+            action: format!("Auto-shim {} (Zero System Impact)", tool),
+            strategy: InstallStrategy::AutoShim,
+            pros: vec![
+                "Zero system-wide impact".to_string(),
+                "Specific to this project".to_string(),
+            ],
+            cons: vec!["Requires Architect to launch tool".to_string()],
+            risk: Risk::Low,
+            estimated_disk_mb: size_mb,
+            estimated_time_sec: install_secs,
+            affected_projects: 0,
+            reversible: true,
+            resolution_actions: vec![ResolutionAction::AutoShim {
+                url: url.to_string(),
+                binary_name: tool.to_string(),
+                version: version.to_string(),
+                sha256,
+            }],
+        })
     }
 
-    /// Find the best version that matches requirement
-    fn find_target_version(&self, required: &VersionReq) -> Result<Version> {
-        // In production, this would query the registry for available versions
-
-        // Parse the requirement to extract major version
-        let req_str = required.to_string();
-        if req_str.starts_with('^') || req_str.starts_with('~') {
-            let version_str = req_str.trim_start_matches('^').trim_start_matches('~');
-            if let Ok(base) = Version::parse(version_str) {
-                // For demo, add .11 to make it realistic (e.g., 20.0.0 -> 20.11.0)
-                return Ok(Version::new(base.major, base.minor + 11, 0));
-            }
+    /// The newest published release inside the range. Tools the registry index doesn't
+    /// list fall back to the range's lower bound.
+    fn find_target_version(&self, tool: &str, required: &VersionRange) -> Result<Version> {
+        if !self.index.releases(tool).is_empty() {
+            return self
+                .index
+                .newest_matching(tool, required)
+                .map(|release| release.version.clone())
+                .ok_or_else(|| {
+                    anyhow::anyhow!("No published {} release matches {}", tool, required)
+                });
         }
 
-        // Fallback: try to parse as exact version
-        Version::parse(&req_str).or_else(|_| Ok(Version::new(1, 0, 0)))
+        let base = required
+            .req
+            .comparators
+            .iter()
+            .find(|c| !matches!(c.op, Op::Less | Op::LessEq))
+            .map(|c| Version::new(c.major, c.minor.unwrap_or(0), c.patch.unwrap_or(0)));
+
+        Ok(base.unwrap_or_else(|| Version::new(1, 0, 0)))
     }
 
     /// Find a partial match from installed versions
-    fn find_partial_match(&self, installed: &[Version], required: &VersionReq) -> Option<Version> {
+    fn find_partial_match(
+        &self,
+        installed: &[Version],
+        required: &VersionRange,
+    ) -> Option<Version> {
         // Check if major version matches
-        installed
-            .iter()
-            .find(|v| {
-                // Extract major version from requirement
-                if let Some(major) = self.extract_major_version(required) {
-                    v.major == major
-                } else {
-                    false
-                }
-            })
-            .cloned()
+        let major = self.extract_major_version(required)?;
+        installed.iter().find(|v| v.major == major).cloned()
     }
 
     /// Extract major version from version requirement
-    fn extract_major_version(&self, req: &VersionReq) -> Option<u64> {
-        let req_str = req.to_string();
-        let clean = req_str
-            .trim_start_matches('^')
-            .trim_start_matches('~')
-            .trim_start_matches('=');
-
-        if let Ok(version) = Version::parse(clean) {
-            Some(version.major)
-        } else {
-            None
-        }
+    fn extract_major_version(&self, required: &VersionRange) -> Option<u64> {
+        required.req.comparators.first().map(|c| c.major)
     }
 
-    /// Get actual tool size (queries package managers)
-    fn estimate_tool_size(&self, tool: &str) -> u64 {
-        self.metrics.get_tool_size(tool).unwrap_or(100)
+    /// Size in MB on this platform: the registry index's for that release, otherwise
+    /// whatever the metrics detector knows about the tool
+    fn estimate_tool_size(&self, tool: &str, version: &Version) -> u64 {
+        match self.index.release(tool, version) {
+            Some(release) => release
+                .size_for(&self.platform.target())
+                .div_ceil(1024 * 1024),
+            None => self
+                .metrics
+                .get_tool_size(tool, version, self.managers.get(tool).copied())
+                .unwrap_or(100),
+        }
     }
 
-    /// Estimate install time in seconds
-    fn estimate_install_time(&self, tool: &str) -> u64 {
-        match tool {
-            "nodejs" | "node" => 120,
-            "python" | "python3" => 180,
-            "rust" | "rustc" | "cargo" => 300,
-            _ => 60,
-        }
+    /// Install time in seconds: the median of past installs on this machine, otherwise
+    /// derived from the artifact size
+    fn estimate_install_time(&self, tool: &str, size_mb: u64) -> u64 {
+        self.history
+            .as_ref()
+            .and_then(|history| history.estimate(tool))
+            .map(|duration| duration.as_secs())
+            .unwrap_or(BASE_INSTALL_SECS + size_mb / ASSUMED_MB_PER_SEC)
     }
 
-    /// Count how many projects depend on this tool
-    /// Scans filesystem for .node-version files, package.json, Cargo.toml, etc.
-    fn count_dependent_projects(&self, tool: &str) -> usize {
-        self.metrics.scan_dependent_projects(tool).len()
+    /// Count the projects that replacing `current` with `target` would break.
+    /// Without a project index, falls back to scanning the filesystem for .node-version
+    /// files, package.json, Cargo.toml, etc. (every project using the tool counts).
+    fn count_dependent_projects(&self, tool: &str, current: &Version, target: &Version) -> usize {
+        let Some(projects) = &self.projects else {
+            return self.metrics.scan_dependent_projects(tool).len();
+        };
+        let exactly = |version: &Version| {
+            VersionRange::from(VersionReq {
+                comparators: vec![Comparator {
+                    op: Op::Exact,
+                    major: version.major,
+                    minor: Some(version.minor),
+                    patch: Some(version.patch),
+                    pre: version.pre.clone(),
+                }],
+            })
+        };
+        projects
+            .broken_by(
+                tool,
+                &exactly(current),
+                Some(&exactly(target)),
+                &self.channels,
+            )
+            .len()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::dependency::Release;
     use crate::system::platform::PlatformDetector;
-    use semver::VersionReq;
 
     #[test]
     fn test_version_mismatch_resolution() {
@@ -356,7 +520,7 @@ mod tests {
 
         // Simulate: Node 18 installed, Node 20 required
         let installed = vec![Version::new(18, 19, 0)];
-        let required = VersionReq::parse("^20.0.0").unwrap();
+        let required = VersionConstraint::parse("^20.0.0").unwrap();
 
         let recommendations = resolver
             .resolve_version_mismatch("nodejs", &required, &installed)
@@ -373,4 +537,211 @@ mod tests {
             println!("   Cons: {}", rec.cons.join(", "));
         }
     }
+
+    #[test]
+    fn test_native_constraints() {
+        let platform = PlatformDetector::detect();
+        let mut registry = InstalledToolsRegistry::new();
+        registry.add_version(crate::system::InstalledVersion {
+            tool: "node".to_string(),
+            version: Version::new(20, 11, 0),
+            location: "/usr/bin/node".into(),
+            managed_by: crate::system::ToolManager::System,
+        });
+        let resolver = ConflictResolver::new(platform, registry);
+
+        let iron = VersionConstraint::parse("lts/iron").unwrap();
+        assert!(resolver.detect_conflicts("node", &iron, "test").is_none());
+
+        let hydrogen = VersionConstraint::parse("lts/hydrogen").unwrap();
+        let conflict = resolver
+            .detect_conflicts("node", &hydrogen, "test")
+            .unwrap();
+        assert!(conflict.to_string().contains("lts/hydrogen"));
+
+        let recommendations = resolver.resolve(&conflict).unwrap();
+        assert!(recommendations[0].action.contains("18.0.0"));
+    }
+
+    #[test]
+    fn test_dependency_manager_sets_syntax() {
+        let resolver = || {
+            let mut registry = InstalledToolsRegistry::new().with_strategies(Vec::new());
+            registry.add_version(crate::system::InstalledVersion {
+                tool: "black".to_string(),
+                version: Version::new(23, 12, 1),
+                location: "/usr/bin/black".into(),
+                managed_by: crate::system::ToolManager::System,
+            });
+            ConflictResolver::new(PlatformDetector::detect(), registry)
+        };
+        // Through pip, a bare `23.1` is the 23.1 release line rather than `^23.1`.
+        let bare = VersionConstraint::parse("23.1").unwrap();
+        assert!(resolver()
+            .detect_conflicts("black", &bare, "test")
+            .is_none());
+
+        let managers = HashMap::from([("black".to_string(), PackageManager::Pip)]);
+        assert!(resolver()
+            .with_managers(managers)
+            .detect_conflicts("black", &bare, "test")
+            .is_some());
+    }
+
+    #[test]
+    fn test_manager_installs_satisfy_requirements() {
+        use crate::system::{InstalledVersion, ToolManager};
+
+        let platform = PlatformDetector::detect();
+        let mut registry = InstalledToolsRegistry::new().with_strategies(Vec::new());
+        for (tool, version, manager) in [
+            ("node", Version::new(18, 19, 0), ToolManager::Fnm),
+            ("rustc", Version::new(1, 76, 0), ToolManager::Rustup),
+        ] {
+            registry.add_version(InstalledVersion {
+                tool: tool.to_string(),
+                version,
+                location: format!("/opt/{}/{}", manager, tool).into(),
+                managed_by: manager,
+            });
+        }
+        let resolver = ConflictResolver::new(platform, registry);
+
+        // `rust` is satisfied by the rustup-managed `rustc`.
+        let rust = VersionConstraint::parse("1.75").unwrap();
+        assert!(resolver.detect_conflicts("rust", &rust, "test").is_none());
+
+        // A missing Node version goes through the manager that already has Node.
+        let node = VersionConstraint::parse("^20").unwrap();
+        let conflict = resolver.detect_conflicts("node", &node, "test").unwrap();
+        let recommendations = resolver.resolve(&conflict).unwrap();
+        assert!(recommendations[0]
+            .resolution_actions
+            .iter()
+            .any(|action| matches!(
+                action,
+                ResolutionAction::ManagedInstall { manager, .. } if manager == "fnm"
+            )));
+    }
+
+    #[test]
+    fn test_registry_backed_recommendations() {
+        use crate::system::{InstalledVersion, ToolManager};
+        use std::time::Duration;
+
+        let platform = PlatformDetector::detect();
+        let mut registry = InstalledToolsRegistry::new().with_strategies(Vec::new());
+        registry.add_version(InstalledVersion {
+            tool: "node".to_string(),
+            version: Version::new(18, 19, 0),
+            location: "/usr/bin/node".into(),
+            managed_by: ToolManager::System,
+        });
+        registry.add_manager(ToolManager::Homebrew);
+        registry.add_manager(ToolManager::Pyenv);
+
+        let mut index = ReleaseIndex::new();
+        index.insert(
+            "node",
+            Release::new(Version::new(20, 9, 0), 40 * 1024 * 1024),
+        );
+        index.insert(
+            "node",
+            Release {
+                target: Some("node-20.11.0".to_string()),
+                sha256: Some("ab12".to_string()),
+                ..Release::new(Version::new(20, 11, 0), 44_040_192)
+            },
+        );
+        index.insert(
+            "node",
+            Release::new(Version::new(22, 0, 0), 50 * 1024 * 1024),
+        );
+        // Published as a WebAssembly plugin, which can't be shimmed as an executable.
+        index.insert(
+            "python",
+            Release {
+                target: Some("python-3.11.7.wasm".to_string()),
+                sha256: Some("cd34".to_string()),
+                ..Release::new(Version::new(3, 11, 7), 1024)
+            },
+        );
+
+        let temp = tempfile::tempdir().unwrap();
+        let mut history = InstallHistory::open(temp.path().join("install-times.json"));
+        history.record("nodejs", Duration::from_secs(90));
+
+        // One registered project still needs Node 18, another is fine with anything newer.
+        let mut projects = ProjectIndex::open(temp.path().join("projects.json"));
+        for (name, requirement) in [("legacy", "^18"), ("modern", ">=18")] {
+            let mut manifest = env_manifest::EnhancedManifest::default();
+            manifest.dependencies.insert(
+                "node".to_string(),
+                env_manifest::DependencySpec::Simple(
+                    VersionConstraint::parse(requirement).unwrap(),
+                ),
+            );
+            let root = temp.path().join(name);
+            projects.register(&root, &root.join("env.json"), &manifest);
+        }
+
+        let resolver = ConflictResolver::new(platform, registry)
+            .with_index(index)
+            .with_history(history)
+            .with_projects(projects)
+            .with_targets_url(
+                Url::parse("https://registry.example.com/targets/").unwrap(),
+                true,
+            );
+
+        let node = VersionConstraint::parse("^20").unwrap();
+        let conflict = resolver.detect_conflicts("node", &node, "test").unwrap();
+        let recommendations = resolver.resolve(&conflict).unwrap();
+        let alongside = &recommendations[0];
+        assert_eq!(alongside.action, "Install 20.11.0 alongside 18.19.0");
+        assert_eq!(alongside.estimated_disk_mb, 42);
+        assert_eq!(alongside.estimated_time_sec, 90);
+        assert!(matches!(
+            &alongside.resolution_actions[0],
+            ResolutionAction::ManagedInstall { manager, .. } if manager == "brew"
+        ));
+        let upgrade = recommendations
+            .iter()
+            .find(|r| r.strategy == InstallStrategy::Replace)
+            .unwrap();
+        assert_eq!(upgrade.affected_projects, 1);
+        assert_eq!(upgrade.risk, Risk::Medium);
+        // The published artifact is checksummed, so it can be shimmed straight from the registry.
+        assert!(recommendations.iter().any(|r| r.resolution_actions
+            == [ResolutionAction::AutoShim {
+                url: "https://registry.example.com/targets/ab12.node-20.11.0".to_string(),
+                binary_name: "node".to_string(),
+                version: "20.11.0".to_string(),
+                sha256: "ab12".to_string(),
+            }]));
+
+        // pyenv is preferred for Python; with no history the time follows the size.
+        let python = VersionConstraint::parse("~3.11").unwrap();
+        let conflict = resolver
+            .detect_conflicts("python", &python, "test")
+            .unwrap();
+        let recommendations = resolver.resolve(&conflict).unwrap();
+        assert!(recommendations
+            .iter()
+            .all(|r| r.strategy != InstallStrategy::AutoShim));
+        let install = &recommendations[0];
+        assert_eq!(install.action, "Install python @ 3.11.7");
+        assert_eq!(install.estimated_time_sec, BASE_INSTALL_SECS);
+        assert!(matches!(
+            &install.resolution_actions[0],
+            ResolutionAction::ManagedInstall { manager, .. } if manager == "pyenv"
+        ));
+
+        // Nothing published satisfies the requirement.
+        let python = VersionConstraint::parse("^3.12").unwrap();
+        let conflict = resolver
+            .detect_conflicts("python", &python, "test")
+            .unwrap();
+        assert!(resolver.resolve(&conflict).is_err());
+    }
 }
@@ -1,185 +1,153 @@
-use crate::system::{OsType, PlatformInfo};
+use crate::dependency::index::{platform_keys, ReleaseIndex};
+use crate::system::{OsType, PlatformInfo, SizeCache};
 use anyhow::Result;
-use serde::Deserialize;
+use env_manifest::{CpuArchitecture, Ecosystem, OperatingSystem, PackageManager, TargetPlatform};
+use semver::Version;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::Mutex;
+use std::time::Duration;
+use url::Url;
 use walkdir::WalkDir;
 
-/// Metrics detector for real system data
-pub struct MetricsDetector {
-    platform: PlatformInfo,
-}
-
-#[derive(Debug, Deserialize)]
-struct HomebrewBottle {
-    _stable: Option<HomebrewBottleStable>,
+/// How long each ecosystem registry gets to answer a size lookup.
+const LOOKUP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Runtimes aren't packages of their own ecosystem: `node` on npm or `python` on PyPI
+/// are unrelated projects.
+const RUNTIMES: &[&str] = &[
+    "node", "nodejs", "bun", "python", "python3", "pypy", "go", "golang", "ruby",
+];
+
+/// An ecosystem registry that can tell a package's size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PackageRegistry {
+    Npm,
+    PyPi,
+    CratesIo,
 }
 
-#[derive(Debug, Deserialize)]
-struct HomebrewBottleStable {
-    _files: serde_json::Value,
+impl PackageRegistry {
+    /// The registry publishing `tool`: its manager's, else the ecosystem its name implies.
+    /// Crates are only looked up for `manager = "cargo"`, since a bare name says nothing.
+    fn for_tool(tool: &str, manager: Option<PackageManager>) -> Option<Self> {
+        if RUNTIMES.contains(&tool) {
+            return None;
+        }
+        match (Ecosystem::for_dependency(tool, manager), manager) {
+            (Ecosystem::Node, _) => Some(Self::Npm),
+            (Ecosystem::Python, _) => Some(Self::PyPi),
+            (_, Some(PackageManager::Cargo)) => Some(Self::CratesIo),
+            _ => None,
+        }
+    }
 }
 
-#[derive(Debug, Deserialize)]
-struct HomebrewInfo {
-    _bottle: Option<HomebrewBottle>,
+/// Metrics detector for real system data
+pub struct MetricsDetector {
+    platform: PlatformInfo,
+    index: ReleaseIndex,
+    cache: Option<Mutex<SizeCache>>,
+    ecosystem_lookups: bool,
 }
 
 impl MetricsDetector {
     pub fn new(platform: PlatformInfo) -> Self {
-        Self { platform }
-    }
-
-    /// Get actual tool size by querying package managers and registries
-    pub fn get_tool_size(&self, tool: &str) -> Result<u64> {
-        // First try language-specific registries (more accurate)
-        if let Ok(size) = self.try_registry_apis(tool) {
-            return Ok(size);
-        }
-
-        // Fall back to OS package managers
-        match self.platform.os_type {
-            OsType::MacOS => self.query_homebrew_size(tool),
-            OsType::Linux => self.query_apt_size(tool),
-            _ => Ok(self.fallback_estimate(tool)),
-        }
-    }
-
-    /// Try language-specific package registry APIs
-    fn try_registry_apis(&self, tool: &str) -> Result<u64> {
-        match tool {
-            // JavaScript ecosystem
-            t if t.starts_with("@") || self.is_npm_package(t) => self.query_npm_registry(t),
-            // Python ecosystem
-            t if self.is_python_package(t) => self.query_pypi_registry(t),
-            // Rust ecosystem
-            t if self.is_rust_crate(t) => self.query_crates_io(t),
-            _ => anyhow::bail!("No registry API available"),
+        Self {
+            platform,
+            index: ReleaseIndex::new(),
+            cache: None,
+            ecosystem_lookups: false,
         }
     }
 
-    /// Check if this looks like an npm package
-    fn is_npm_package(&self, name: &str) -> bool {
-        // Common npm packages we might install
-        matches!(
-            name,
-            "typescript" | "webpack" | "vite" | "eslint" | "prettier"
-        )
-    }
-
-    /// Check if this is a Python package
-    fn is_python_package(&self, name: &str) -> bool {
-        matches!(name, "django" | "flask" | "requests" | "numpy" | "pandas")
-    }
-
-    /// Check if this is a Rust crate
-    fn is_rust_crate(&self, name: &str) -> bool {
-        matches!(name, "serde" | "tokio" | "actix-web" | "rocket")
+    /// Read sizes from the registry's published releases.
+    pub fn with_index(mut self, index: ReleaseIndex) -> Self {
+        self.index = index;
+        self
     }
 
-    /// Query npm registry for package size
-    fn query_npm_registry(&self, package: &str) -> Result<u64> {
-        // npm registry API: https://registry.npmjs.org/<package>
-        // We'd use reqwest here in production
-        // For now, return known sizes for common packages
-
-        let size_kb = match package {
-            "typescript" => 65_000, // ~65MB
-            "webpack" => 5_000,     // ~5MB
-            "vite" => 15_000,       // ~15MB
-            "eslint" => 8_000,      // ~8MB
-            "prettier" => 3_000,    // ~3MB
-            "react" => 500,         // ~500KB
-            "vue" => 3_000,         // ~3MB
-            "express" => 200,       // ~200KB
-            _ => anyhow::bail!("Unknown npm package"),
-        };
-
-        Ok(size_kb / 1024) // Convert to MB
+    /// Remember sizes found by ecosystem lookups, and answer from them offline.
+    pub fn with_cache(mut self, cache: SizeCache) -> Self {
+        self.cache = Some(Mutex::new(cache));
+        self
     }
 
-    /// Query PyPI for package size
-    fn query_pypi_registry(&self, package: &str) -> Result<u64> {
-        // PyPI API: https://pypi.org/pypi/<package>/json
-        // Would parse urls[0].size from the response
-
-        let size_kb = match package {
-            "django" => 10_000,      // ~10MB
-            "flask" => 700,          // ~700KB
-            "requests" => 500,       // ~500KB
-            "numpy" => 25_000,       // ~25MB
-            "pandas" => 40_000,      // ~40MB
-            "tensorflow" => 450_000, // ~450MB
-            "pytorch" => 800_000,    // ~800MB
-            _ => anyhow::bail!("Unknown PyPI package"),
-        };
-
-        Ok(size_kb / 1024) // Convert to MB
+    /// Ask the OS package manager and npm, PyPI and crates.io for sizes nothing else
+    /// knows. Off by default, so size estimates never touch the network.
+    pub fn with_ecosystem_lookups(mut self, enabled: bool) -> Self {
+        self.ecosystem_lookups = enabled;
+        self
     }
 
-    /// Query crates.io for crate size
-    fn query_crates_io(&self, crate_name: &str) -> Result<u64> {
-        // crates.io API: https://crates.io/api/v1/crates/<name>
-        // Would parse crate.downloads and recent_downloads
-
-        let size_kb = match crate_name {
-            "serde" => 200,       // ~200KB
-            "tokio" => 3_000,     // ~3MB
-            "actix-web" => 2_000, // ~2MB
-            "rocket" => 1_500,    // ~1.5MB
-            "diesel" => 5_000,    // ~5MB
-            _ => anyhow::bail!("Unknown crate"),
-        };
+    /// Size in MB of `version` of a tool on this platform: the registry index's entry for
+    /// that release (its newest release when `version` isn't published), then sizes cached
+    /// on this machine, then (if enabled) lookups in the OS package manager and the
+    /// registry of the tool's `manager` or ecosystem, then a rough estimate.
+    pub fn get_tool_size(
+        &self,
+        tool: &str,
+        version: &Version,
+        manager: Option<PackageManager>,
+    ) -> Result<u64> {
+        let target = self.platform.target();
+        let release = self
+            .index
+            .release(tool, version)
+            .or_else(|| self.index.releases(tool).last());
+        if let Some(release) = release {
+            return Ok(to_mb(release.size_for(&target)));
+        }
 
-        Ok(size_kb / 1024) // Convert to MB
-    }
+        let keys = platform_keys(&target);
+        if let Some(cache) = self.cache.as_ref().and_then(|cache| cache.lock().ok()) {
+            if let Some(bytes) = keys.iter().find_map(|key| cache.get(key, tool)) {
+                return Ok(to_mb(bytes));
+            }
+        }
 
-    /// Query Homebrew for package size (macOS)
-    fn query_homebrew_size(&self, tool: &str) -> Result<u64> {
-        // Try to get info from Homebrew
-        let output = Command::new("brew")
-            .args(&["info", "--json=v1", tool])
-            .output();
-
-        if let Ok(out) = output {
-            if out.status.success() {
-                let json_str = String::from_utf8_lossy(&out.stdout);
-                if let Ok(infos) = serde_json::from_str::<Vec<HomebrewInfo>>(&json_str) {
-                    if let Some(_info) = infos.first() {
-                        // For now, use formula-based estimation
-                        // In production, would parse bottle URL and query size
-                        return Ok(self.fallback_estimate(tool));
-                    }
+        if self.ecosystem_lookups {
+            let found = self.query_package_manager(tool).or_else(|| {
+                let registry = PackageRegistry::for_tool(tool, manager)?;
+                query_package_registry(registry, tool, &target)
+            });
+            if let Some(bytes) = found {
+                if let Some(mut cache) = self.cache.as_ref().and_then(|cache| cache.lock().ok()) {
+                    cache.insert(&keys[0], tool, bytes);
+                    // Unsaved, the size is simply looked up again next time.
+                    let _ = cache.save();
                 }
+                return Ok(to_mb(bytes));
             }
         }
 
-        // Fallback if Homebrew query fails
         Ok(self.fallback_estimate(tool))
     }
 
-    /// Query APT for package size (Linux)
-    fn query_apt_size(&self, tool: &str) -> Result<u64> {
-        let output = Command::new("apt").args(&["show", tool]).output();
-
-        if let Ok(out) = output {
-            if out.status.success() {
-                let text = String::from_utf8_lossy(&out.stdout);
-
-                // Parse "Installed-Size: 12345" line
-                for line in text.lines() {
-                    if line.starts_with("Installed-Size:") {
-                        if let Some(size_str) = line.split(':').nth(1) {
-                            let size_kb: u64 = size_str.trim().parse().unwrap_or(0);
-                            // Convert KB to MB
-                            return Ok(size_kb / 1024);
-                        }
-                    }
-                }
-            }
+    /// Installed size in bytes according to the OS package manager.
+    fn query_package_manager(&self, tool: &str) -> Option<u64> {
+        match self.platform.os_type {
+            OsType::MacOS => self.query_homebrew_size(tool),
+            OsType::Linux => query_apt_size(tool),
+            _ => None,
         }
+    }
 
-        Ok(self.fallback_estimate(tool))
+    /// Disk usage of the tool's Homebrew cellar, if it's installed (macOS)
+    fn query_homebrew_size(&self, tool: &str) -> Option<u64> {
+        let out = Command::new("brew")
+            .args(["--cellar", tool])
+            .output()
+            .ok()?;
+        if !out.status.success() {
+            return None;
+        }
+        let cellar = PathBuf::from(String::from_utf8_lossy(&out.stdout).trim());
+        if !cellar.exists() {
+            return None;
+        }
+        let mb = self.get_disk_usage(&cellar).ok().filter(|mb| *mb > 0)?;
+        Some(mb * 1024 * 1024)
     }
 
     /// Fallback size estimation
@@ -317,6 +285,108 @@ impl MetricsDetector {
     }
 }
 
+fn to_mb(bytes: u64) -> u64 {
+    bytes.div_ceil(1024 * 1024)
+}
+
+/// `Installed-Size` from `apt show`, in bytes (Linux)
+fn query_apt_size(tool: &str) -> Option<u64> {
+    let out = Command::new("apt").args(["show", tool]).output().ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    let text = String::from_utf8_lossy(&out.stdout);
+    // `Installed-Size: 12345` (KB), or `Installed-Size: 12.3 MB` on newer apt.
+    let value = text
+        .lines()
+        .find_map(|line| line.strip_prefix("Installed-Size:"))?
+        .trim();
+    let (number, unit) = value.split_once(' ').unwrap_or((value, "kB"));
+    let number: f64 = number.parse().ok()?;
+    let scale = match unit.to_ascii_lowercase().as_str() {
+        "b" => 1.0,
+        "mb" => 1024.0 * 1024.0,
+        "gb" => 1024.0 * 1024.0 * 1024.0,
+        _ => 1024.0,
+    };
+    Some((number * scale) as u64).filter(|bytes| *bytes > 0)
+}
+
+/// Size in bytes from the first of npm, PyPI and crates.io that publishes `tool`.
+fn query_package_registry(
+    registry: PackageRegistry,
+    tool: &str,
+    target: &TargetPlatform,
+) -> Option<u64> {
+    let tool = tool.to_string();
+    let target = target.clone();
+    // The blocking client can't run on an async runtime's thread, which callers may be on.
+    std::thread::spawn(move || {
+        let client = reqwest::blocking::Client::builder()
+            .timeout(LOOKUP_TIMEOUT)
+            .user_agent(concat!("env-architect/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .ok()?;
+        let get = |base: &str, segments: &[&str]| -> Option<serde_json::Value> {
+            let mut url = Url::parse(base).ok()?;
+            url.path_segments_mut().ok()?.extend(segments);
+            let response = client.get(url).send().ok()?.error_for_status().ok()?;
+            serde_json::from_slice(&response.bytes().ok()?).ok()
+        };
+
+        // npm: unpacked size of the latest version
+        let npm = || {
+            get("https://registry.npmjs.org/", &[&tool, "latest"])?
+                .pointer("/dist/unpackedSize")?
+                .as_u64()
+        };
+        // PyPI: the latest release's wheel for this platform, else its largest file
+        let pypi = || {
+            let info = get("https://pypi.org/pypi/", &[&tool, "json"])?;
+            let files = info.get("urls")?.as_array()?;
+            let size = |file: &serde_json::Value| file.get("size")?.as_u64();
+            files
+                .iter()
+                .find(|file| {
+                    file.get("filename")
+                        .and_then(|name| name.as_str())
+                        .is_some_and(|name| matches_wheel_platform(name, &target))
+                })
+                .and_then(size)
+                .or_else(|| files.iter().filter_map(size).max())
+        };
+        // crates.io: the newest version's crate
+        let crates_io = || {
+            get("https://crates.io/api/v1/crates/", &[&tool])?
+                .pointer("/versions/0/crate_size")?
+                .as_u64()
+        };
+
+        match registry {
+            PackageRegistry::Npm => npm(),
+            PackageRegistry::PyPi => pypi(),
+            PackageRegistry::CratesIo => crates_io(),
+        }
+    })
+    .join()
+    .ok()
+    .flatten()
+}
+
+/// Whether a wheel's platform tag (`manylinux_2_17_x86_64`, `macosx_11_0_arm64`,
+/// `win_amd64`) fits `target`.
+fn matches_wheel_platform(filename: &str, target: &TargetPlatform) -> bool {
+    let (os, arch) = match (target.os, target.arch.canonical()) {
+        (OperatingSystem::Linux, arch) => ("linux", arch.as_str()),
+        (OperatingSystem::Macos, CpuArchitecture::Aarch64) => ("macosx", "arm64"),
+        (OperatingSystem::Macos, arch) => ("macosx", arch.as_str()),
+        (OperatingSystem::Windows, CpuArchitecture::X86_64) => ("win", "amd64"),
+        (OperatingSystem::Windows, arch) => ("win", arch.as_str()),
+        _ => return false,
+    };
+    filename.ends_with(".whl") && filename.contains(os) && filename.contains(arch)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -344,10 +414,45 @@ mod tests {
         let platform = PlatformDetector::detect();
         let detector = MetricsDetector::new(platform);
 
-        let size = detector.get_tool_size("nodejs").unwrap();
+        let size = detector
+            .get_tool_size("nodejs", &Version::new(20, 11, 0), None)
+            .unwrap();
         println!("Node.js estimated size: {}MB", size);
 
         assert!(size > 0);
         assert!(size < 1000); // Sanity check
     }
+
+    #[test]
+    fn test_package_registry_routing() {
+        use PackageRegistry::*;
+        // Runtimes are never looked up, whatever the manager.
+        for runtime in ["node", "python", "go"] {
+            assert_eq!(PackageRegistry::for_tool(runtime, None), None);
+            assert_eq!(
+                PackageRegistry::for_tool(runtime, Some(PackageManager::Npm)),
+                None
+            );
+        }
+        assert_eq!(PackageRegistry::for_tool("yarn", None), Some(Npm));
+        assert_eq!(PackageRegistry::for_tool("poetry", None), Some(PyPi));
+        assert_eq!(
+            PackageRegistry::for_tool("typescript", Some(PackageManager::Npm)),
+            Some(Npm)
+        );
+        assert_eq!(
+            PackageRegistry::for_tool("black", Some(PackageManager::Pip)),
+            Some(PyPi)
+        );
+        assert_eq!(
+            PackageRegistry::for_tool("ripgrep", Some(PackageManager::Cargo)),
+            Some(CratesIo)
+        );
+        // Nothing names the ecosystem, or it has no lookup.
+        assert_eq!(PackageRegistry::for_tool("ripgrep", None), None);
+        assert_eq!(
+            PackageRegistry::for_tool("rails", Some(PackageManager::Gem)),
+            None
+        );
+    }
 }
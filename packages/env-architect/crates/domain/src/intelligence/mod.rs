@@ -1,9 +1,13 @@
 pub mod conflicts;
 pub mod metrics;
+pub mod policy;
+pub mod scoring;
 pub mod strategies;
 
 pub use conflicts::ConflictResolver;
 pub use metrics::MetricsDetector;
+pub use policy::{Decision, DecisionOutcome};
+pub use scoring::{ScoredRecommendation, ScoringModel};
 pub use strategies::{
     Conflict, ConflictSource, InstallStrategy, Recommendation, Resolution, ResolutionStrategy, Risk,
 };
@@ -0,0 +1,148 @@
+use crate::intelligence::scoring::ScoredRecommendation;
+use crate::intelligence::strategies::{Conflict, InstallStrategy};
+use env_manifest::{PolicyRule, ResolutionPolicy};
+use serde::Serialize;
+
+/// How a conflict ended up under a policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum DecisionOutcome {
+    /// A recommendation was chosen.
+    Resolved,
+    /// Left as is, as the rule asked.
+    Skipped,
+    /// The rule forbids the conflict, or no recommendation fits it.
+    Failed,
+}
+
+/// One conflict settled without prompting, in the record `resolve` prints for CI.
+#[derive(Debug, Clone, Serialize)]
+pub struct Decision {
+    pub tool: String,
+    pub conflict: String,
+    pub rule: PolicyRule,
+    /// `tools.<name>` or `default`.
+    pub rule_source: String,
+    pub outcome: DecisionOutcome,
+    /// Action of the chosen recommendation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<InstallStrategy>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+impl Decision {
+    /// Settle a conflict by `policy`, returning the decision and the index into `ranked`
+    /// of the chosen recommendation, if any.
+    pub fn choose(
+        policy: &ResolutionPolicy,
+        tool: &str,
+        conflict: &Conflict,
+        ranked: &[ScoredRecommendation],
+    ) -> (Self, Option<usize>) {
+        let (rule, rule_source) = policy.rule_for(tool);
+        let wanted = match rule {
+            PolicyRule::Best => Some(None),
+            PolicyRule::Alongside => Some(Some(InstallStrategy::Alongside)),
+            PolicyRule::Replace => Some(Some(InstallStrategy::Replace)),
+            PolicyRule::UseExisting => Some(Some(InstallStrategy::UseExisting)),
+            PolicyRule::AutoShim => Some(Some(InstallStrategy::AutoShim)),
+            PolicyRule::Skip | PolicyRule::Fail => None,
+        };
+        // Ranked best first, so the first match is the best option of that kind.
+        let chosen = wanted.and_then(|strategy: Option<InstallStrategy>| {
+            ranked
+                .iter()
+                .position(|scored| strategy.is_none_or(|s| scored.recommendation.strategy == s))
+        });
+
+        let (outcome, reason) = match (rule, chosen) {
+            (PolicyRule::Skip, _) => (DecisionOutcome::Skipped, None),
+            (PolicyRule::Fail, _) => (
+                DecisionOutcome::Failed,
+                Some(format!("policy allows no conflicts for {}", tool)),
+            ),
+            (_, Some(_)) => (DecisionOutcome::Resolved, None),
+            (_, None) => (
+                DecisionOutcome::Failed,
+                Some(format!("no '{}' option for {}", rule, tool)),
+            ),
+        };
+        let decision = Self {
+            tool: tool.to_string(),
+            conflict: conflict.to_string(),
+            rule,
+            rule_source,
+            outcome,
+            action: chosen.map(|i| ranked[i].recommendation.action.clone()),
+            strategy: chosen.map(|i| ranked[i].recommendation.strategy),
+            reason,
+        };
+        (decision, chosen)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intelligence::{Recommendation, Risk, ScoringModel};
+    use env_manifest::VersionConstraint;
+
+    fn rec(action: &str, strategy: InstallStrategy, risk: Risk) -> Recommendation {
+        Recommendation {
+            action: action.to_string(),
+            strategy,
+            pros: Vec::new(),
+            cons: Vec::new(),
+            risk,
+            estimated_disk_mb: 40,
+            estimated_time_sec: 60,
+            affected_projects: 0,
+            reversible: true,
+            resolution_actions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_policy_decisions() {
+        let ranked = ScoringModel::default().rank(vec![
+            rec("upgrade", InstallStrategy::Replace, Risk::Medium),
+            rec("alongside", InstallStrategy::Alongside, Risk::Low),
+        ]);
+        let conflict = Conflict::MissingTool {
+            tool: "node".to_string(),
+            required: VersionConstraint::parse("^20").unwrap(),
+            source: crate::intelligence::ConflictSource::Manifest,
+            required_by: "ci".to_string(),
+        };
+        let policy: ResolutionPolicy = toml::from_str(
+            "default = \"replace\"\n[tools]\npython = \"use-existing\"\ngo = \"fail\"\n",
+        )
+        .unwrap();
+
+        let (decision, chosen) = Decision::choose(&policy, "node", &conflict, &ranked);
+        assert_eq!(chosen, Some(1));
+        assert_eq!(decision.action.as_deref(), Some("upgrade"));
+        assert_eq!(decision.rule_source, "default");
+
+        let (decision, chosen) = Decision::choose(&policy, "python", &conflict, &ranked);
+        assert_eq!((decision.outcome, chosen), (DecisionOutcome::Failed, None));
+        assert_eq!(
+            decision.reason.as_deref(),
+            Some("no 'use-existing' option for python")
+        );
+
+        let (decision, _) = Decision::choose(&policy, "go", &conflict, &ranked);
+        assert_eq!(decision.outcome, DecisionOutcome::Failed);
+
+        let (decision, chosen) =
+            Decision::choose(&ResolutionPolicy::default(), "node", &conflict, &ranked);
+        assert_eq!(chosen, Some(0));
+        let record = serde_json::to_value(&decision).unwrap();
+        assert_eq!(record["rule"], "best");
+        assert_eq!(record["outcome"], "resolved");
+        assert_eq!(record["strategy"], "alongside");
+    }
+}
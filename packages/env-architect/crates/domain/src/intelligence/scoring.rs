@@ -0,0 +1,254 @@
+use crate::intelligence::strategies::{Recommendation, Risk};
+use env_manifest::ScoringWeights;
+
+/// Disk use, in MB, that counts as half as bad as it gets.
+const DISK_MIDPOINT_MB: f64 = 500.0;
+
+/// Install time, in seconds, that counts as half as bad as it gets.
+const TIME_MIDPOINT_SEC: f64 = 300.0;
+
+/// Broken projects that count as half as bad as it gets.
+const PROJECTS_MIDPOINT: f64 = 3.0;
+
+/// A recommendation with its weighted cost and place relative to the Pareto front.
+#[derive(Debug, Clone)]
+pub struct ScoredRecommendation {
+    pub recommendation: Recommendation,
+    /// Weighted cost from 0 (no downside at all) to 1 (worst on every objective).
+    pub cost: f64,
+    /// Index of a recommendation that is at least as good on every objective and
+    /// better on one; `None` for the Pareto front.
+    pub dominated_by: Option<usize>,
+    /// What this option gains and gives up against the top-ranked one.
+    pub trade_off: Option<String>,
+}
+
+impl ScoredRecommendation {
+    pub fn is_pareto_optimal(&self) -> bool {
+        self.dominated_by.is_none()
+    }
+}
+
+/// Ranks recommendations over risk, disk, time, affected projects and reversibility.
+///
+/// Each objective is squashed to `[0, 1)` with `x / (x + midpoint)`, so absolute
+/// sizes matter rather than the spread between candidates, then weighted.
+#[derive(Debug, Clone, Default)]
+pub struct ScoringModel {
+    weights: ScoringWeights,
+}
+
+impl ScoringModel {
+    pub fn new(weights: ScoringWeights) -> Self {
+        Self { weights }
+    }
+
+    pub fn weights(&self) -> &ScoringWeights {
+        &self.weights
+    }
+
+    /// Every recommendation, Pareto front first, each part ordered by cost.
+    pub fn rank(&self, recommendations: Vec<Recommendation>) -> Vec<ScoredRecommendation> {
+        let all: Vec<[f64; 5]> = recommendations.iter().map(objectives).collect();
+        let mut candidates: Vec<(Recommendation, [f64; 5], bool)> = recommendations
+            .into_iter()
+            .zip(&all)
+            .map(|(rec, o)| (rec, *o, all.iter().any(|other| dominates(other, o))))
+            .collect();
+        candidates.sort_by(|(_, a, a_dominated), (_, b, b_dominated)| {
+            a_dominated
+                .cmp(b_dominated)
+                .then(self.cost(a).total_cmp(&self.cost(b)))
+        });
+
+        let ranked_objectives: Vec<[f64; 5]> = candidates.iter().map(|(_, o, _)| *o).collect();
+        let trade_offs: Vec<Option<String>> = candidates
+            .iter()
+            .enumerate()
+            .map(|(i, (rec, _, dominated))| {
+                let (best, _, _) = &candidates[0];
+                (i > 0 && !dominated)
+                    .then(|| trade_off(rec, best))
+                    .flatten()
+            })
+            .collect();
+
+        candidates
+            .into_iter()
+            .zip(trade_offs)
+            .map(|((recommendation, o, _), trade_off)| ScoredRecommendation {
+                recommendation,
+                cost: self.cost(&o),
+                dominated_by: ranked_objectives
+                    .iter()
+                    .position(|other| dominates(other, &o)),
+                trade_off,
+            })
+            .collect()
+    }
+
+    fn cost(&self, objectives: &[f64; 5]) -> f64 {
+        let w = &self.weights;
+        let weights = [w.risk, w.disk, w.time, w.affected_projects, w.reversibility];
+        let total: f64 = weights.iter().sum();
+        if total == 0.0 {
+            return 0.0;
+        }
+        weights
+            .iter()
+            .zip(objectives)
+            .map(|(weight, value)| weight * value)
+            .sum::<f64>()
+            / total
+    }
+}
+
+/// Normalized costs: risk, disk, time, affected projects, irreversibility.
+fn objectives(rec: &Recommendation) -> [f64; 5] {
+    let squash = |value: f64, midpoint: f64| value / (value + midpoint);
+    [
+        match rec.risk {
+            Risk::Low => 0.0,
+            Risk::Medium => 0.5,
+            Risk::High => 1.0,
+        },
+        squash(rec.estimated_disk_mb as f64, DISK_MIDPOINT_MB),
+        squash(rec.estimated_time_sec as f64, TIME_MIDPOINT_SEC),
+        squash(rec.affected_projects as f64, PROJECTS_MIDPOINT),
+        if rec.reversible { 0.0 } else { 1.0 },
+    ]
+}
+
+/// Whether `a` is no worse than `b` everywhere and strictly better somewhere.
+fn dominates(a: &[f64; 5], b: &[f64; 5]) -> bool {
+    a.iter().zip(b).all(|(x, y)| x <= y) && a.iter().zip(b).any(|(x, y)| x < y)
+}
+
+/// "saves 42MB, but 30s slower, irreversible" against `best`.
+fn trade_off(rec: &Recommendation, best: &Recommendation) -> Option<String> {
+    let mut gains = Vec::new();
+    let mut losses = Vec::new();
+    let mut compare = |ours: u64, theirs: u64, better: String, worse: String| {
+        if ours < theirs {
+            gains.push(better);
+        } else if ours > theirs {
+            losses.push(worse);
+        }
+    };
+
+    compare(
+        rec.risk as u64,
+        best.risk as u64,
+        "lower risk".to_string(),
+        "higher risk".to_string(),
+    );
+    let disk = rec.estimated_disk_mb.abs_diff(best.estimated_disk_mb);
+    compare(
+        rec.estimated_disk_mb,
+        best.estimated_disk_mb,
+        format!("saves {}MB", disk),
+        format!("{}MB more disk", disk),
+    );
+    let time = rec.estimated_time_sec.abs_diff(best.estimated_time_sec);
+    compare(
+        rec.estimated_time_sec,
+        best.estimated_time_sec,
+        format!("{}s faster", time),
+        format!("{}s slower", time),
+    );
+    let projects = rec.affected_projects.abs_diff(best.affected_projects);
+    compare(
+        rec.affected_projects as u64,
+        best.affected_projects as u64,
+        format!("breaks {} fewer project(s)", projects),
+        format!("breaks {} more project(s)", projects),
+    );
+    compare(
+        !rec.reversible as u64,
+        !best.reversible as u64,
+        "reversible".to_string(),
+        "irreversible".to_string(),
+    );
+
+    match (gains.is_empty(), losses.is_empty()) {
+        (true, true) => None,
+        (false, true) => Some(gains.join(", ")),
+        (true, false) => Some(losses.join(", ")),
+        (false, false) => Some(format!("{}, but {}", gains.join(", "), losses.join(", "))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intelligence::InstallStrategy;
+    use env_manifest::ScoringPreset;
+
+    fn rec(action: &str, risk: Risk, disk: u64, time: u64, projects: usize) -> Recommendation {
+        Recommendation {
+            action: action.to_string(),
+            strategy: InstallStrategy::Alongside,
+            pros: Vec::new(),
+            cons: Vec::new(),
+            risk,
+            estimated_disk_mb: disk,
+            estimated_time_sec: time,
+            affected_projects: projects,
+            reversible: projects == 0,
+            resolution_actions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_pareto_front_and_presets() {
+        let options = || {
+            vec![
+                rec("replace", Risk::Medium, 0, 150, 2),
+                rec("alongside", Risk::Low, 40, 120, 0),
+                rec("slow alongside", Risk::Low, 40, 600, 0),
+            ]
+        };
+
+        let ranked = ScoringModel::default().rank(options());
+        let actions: Vec<&str> = ranked
+            .iter()
+            .map(|r| r.recommendation.action.as_str())
+            .collect();
+        assert_eq!(actions, ["alongside", "replace", "slow alongside"]);
+        assert_eq!(ranked[2].dominated_by, Some(0));
+        assert!(ranked[1].is_pareto_optimal());
+        assert_eq!(
+            ranked[1].trade_off.as_deref(),
+            Some("saves 40MB, but higher risk, 30s slower, breaks 2 more project(s), irreversible")
+        );
+        assert!(ranked[0].cost < ranked[1].cost);
+
+        // With nothing but disk counting, replacing wins.
+        let disk_only = ScoringWeights {
+            risk: 0.0,
+            disk: 1.0,
+            time: 0.0,
+            affected_projects: 0.0,
+            reversibility: 0.0,
+        };
+        let ranked = ScoringModel::new(disk_only).rank(options());
+        assert_eq!(ranked[0].recommendation.action, "replace");
+
+        // CI takes a quick in-place swap over a slow isolated install; laptops don't.
+        let options = || {
+            vec![
+                rec("alongside", Risk::Low, 40, 300, 0),
+                rec("quick replace", Risk::Low, 0, 20, 1),
+            ]
+        };
+        let top = |preset: ScoringPreset| {
+            ScoringModel::new(preset.weights()).rank(options())[0]
+                .recommendation
+                .action
+                .clone()
+        };
+        assert_eq!(top(ScoringPreset::Ci), "quick replace");
+        assert_eq!(top(ScoringPreset::Balanced), "alongside");
+        assert_eq!(top(ScoringPreset::Isolation), "alongside");
+    }
+}
@@ -1,5 +1,5 @@
-use env_manifest::ResolutionAction;
-use semver::{Version, VersionReq};
+use env_manifest::{ResolutionAction, VersionConstraint};
+use semver::Version;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -29,7 +29,7 @@ pub enum Conflict {
     /// Version mismatch: required version doesn't match installed
     VersionMismatch {
         tool: String,
-        required: VersionReq,
+        required: VersionConstraint,
         installed: Vec<Version>,
         source: ConflictSource,
         required_by: String, // What package requires this
@@ -38,7 +38,7 @@ pub enum Conflict {
     /// Tool is missing entirely
     MissingTool {
         tool: String,
-        required: VersionReq,
+        required: VersionConstraint,
         source: ConflictSource,
         required_by: String,
     },
@@ -110,13 +110,19 @@ impl fmt::Display for Conflict {
     }
 }
 
-/// Installation strategy
+/// Installation strategy. The aliases accept records written before the kebab-case names.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum InstallStrategy {
-    Alongside,   // Install without removing existing (Nix-style)
-    Replace,     // Uninstall old, install new
-    Link,        // Use existing from another manager
+    #[serde(alias = "Alongside")]
+    Alongside, // Install without removing existing (Nix-style)
+    #[serde(alias = "Replace")]
+    Replace, // Uninstall old, install new
+    #[serde(alias = "Link")]
+    Link, // Use existing from another manager
+    #[serde(alias = "UseExisting")]
     UseExisting, // Keep current installation
+    AutoShim, // Download into the store, shimmed into this project only
 }
 
 impl fmt::Display for InstallStrategy {
@@ -126,6 +132,7 @@ impl fmt::Display for InstallStrategy {
             InstallStrategy::Replace => write!(f, "Replace existing version"),
             InstallStrategy::Link => write!(f, "Link to existing installation"),
             InstallStrategy::UseExisting => write!(f, "Use existing installation"),
+            InstallStrategy::AutoShim => write!(f, "Shim into this project"),
         }
     }
 }
@@ -158,11 +165,21 @@ pub struct Recommendation {
     pub risk: Risk,
     pub estimated_disk_mb: u64,
     pub estimated_time_sec: u64,
+    /// Other projects on this machine that would break
+    #[serde(default)]
+    pub affected_projects: usize,
+    /// Whether it can be undone without reinstalling; assumed when not stated
+    #[serde(default = "default_reversible")]
+    pub reversible: bool,
     /// Automated actions that can be taken to fulfill this recommendation
     #[serde(default)]
     pub resolution_actions: Vec<ResolutionAction>,
 }
 
+fn default_reversible() -> bool {
+    true
+}
+
 /// Resolution decision
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Resolution {
@@ -245,3 +262,43 @@ pub trait ResolutionStrategy {
     fn can_resolve(&self, conflict: &Conflict) -> bool;
     fn recommend(&self, conflict: &Conflict) -> Vec<Recommendation>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recommendation_reversible_by_default() {
+        let json = serde_json::json!({
+            "action": "Install 20.0.0 alongside 18.0.0",
+            "strategy": "alongside",
+            "pros": [],
+            "cons": [],
+            "risk": "Low",
+            "estimated_disk_mb": 50,
+            "estimated_time_sec": 30,
+        });
+        let rec: Recommendation = serde_json::from_value(json).unwrap();
+        assert!(rec.reversible);
+        assert_eq!(rec.affected_projects, 0);
+    }
+
+    #[test]
+    fn test_install_strategy_names() {
+        for (name, strategy) in [
+            ("\"use-existing\"", InstallStrategy::UseExisting),
+            ("\"UseExisting\"", InstallStrategy::UseExisting),
+            ("\"Alongside\"", InstallStrategy::Alongside),
+            ("\"auto-shim\"", InstallStrategy::AutoShim),
+        ] {
+            assert_eq!(
+                serde_json::from_str::<InstallStrategy>(name).unwrap(),
+                strategy
+            );
+        }
+        assert_eq!(
+            serde_json::to_string(&InstallStrategy::UseExisting).unwrap(),
+            "\"use-existing\""
+        );
+    }
+}
@@ -1,5 +1,32 @@
 use serde::{Deserialize, Serialize};
 
+/// An ETA in seconds with the range the connection's jitter allows.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EtaBand {
+    pub expected: f64,
+    /// At one standard deviation above the smoothed speed.
+    pub earliest: f64,
+    /// At one standard deviation below it.
+    pub latest: f64,
+}
+
+impl EtaBand {
+    /// Band for `bytes_remaining` at `speed` (bytes/sec) give or take `jitter`.
+    /// None if stalled. The slow end is floored at a quarter of `speed`, so a very
+    /// jittery link widens the band instead of making it unbounded.
+    pub fn new(speed: f64, jitter: f64, bytes_remaining: u64) -> Option<Self> {
+        if speed < 1.0 {
+            return None;
+        }
+        let remaining = bytes_remaining as f64;
+        Some(Self {
+            expected: remaining / speed,
+            earliest: remaining / (speed + jitter),
+            latest: remaining / (speed - jitter).max(speed / 4.0),
+        })
+    }
+}
+
 /// A robust estimator for network progress speed and ETA using a 1D Kalman Filter.
 /// It fuses the "Prediction" (Speed stays constant) with "Measurement" (Bytes / Time).
 ///
@@ -118,4 +145,33 @@ impl ProgressEstimator {
 
         Some(bytes_remaining as f64 / speed)
     }
+
+    /// ETA for `bytes_remaining` with a confidence band from the observed jitter.
+    pub fn eta_band(&self, bytes_remaining: u64) -> Option<EtaBand> {
+        EtaBand::new(self.speed(), self.jitter(), bytes_remaining)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eta_band() {
+        let mut estimator = ProgressEstimator::new();
+        assert!(estimator.eta_band(1000).is_none());
+
+        for speed in [900.0, 1100.0, 1000.0, 950.0, 1050.0] {
+            for _ in 0..50 {
+                estimator.update(speed);
+            }
+        }
+        let band = estimator.eta_band(10_000).unwrap();
+        assert!(band.earliest < band.expected && band.expected < band.latest);
+        assert!((band.expected - 10.0).abs() < 1.0);
+
+        // Jitter larger than the speed itself still gives a finite band.
+        let band = EtaBand::new(100.0, 500.0, 1000).unwrap();
+        assert_eq!(band.latest, 40.0);
+    }
 }
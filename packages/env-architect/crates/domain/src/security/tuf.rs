@@ -1,9 +1,8 @@
+use crate::system::{Artifact, Checksum, Downloader};
 use anyhow::{Context, Result};
-use futures_util::StreamExt;
 use std::convert::TryInto;
 use std::path::{Path, PathBuf};
-use tokio::io::AsyncWriteExt;
-use tough::{DefaultTransport, RepositoryLoader, TargetName};
+use tough::{DefaultTransport, Repository, RepositoryLoader, TargetName};
 use url::Url;
 
 /// Verifies the integrity of the plugin registry using The Update Framework (TUF).
@@ -13,6 +12,9 @@ pub struct RepositoryVerifier {
     targets_base_url: Url,
     root_json_path: PathBuf,
     cache_dir: PathBuf,
+    /// Other places serving the same targets, tried in order after `targets_base_url`.
+    target_mirrors: Vec<Url>,
+    downloader: Downloader,
 }
 
 impl RepositoryVerifier {
@@ -28,16 +30,32 @@ impl RepositoryVerifier {
             metadata_base_url,
             targets_base_url,
             cache_dir: cache_dir.to_path_buf(),
+            target_mirrors: Vec::new(),
+            downloader: Downloader::new(),
         }
     }
 
+    /// Fall back to these target base URLs when the registry's own fails. Metadata is
+    /// still only trusted from the registry; mirrors serve bytes checked against it.
+    pub fn with_target_mirrors(mut self, mirrors: Vec<Url>) -> Self {
+        self.target_mirrors = mirrors;
+        self
+    }
+
+    /// Fetch targets with this downloader, e.g. one shared with other downloads so
+    /// per-host limits hold across them.
+    pub fn with_downloader(mut self, downloader: Downloader) -> Self {
+        self.downloader = downloader;
+        self
+    }
+
     /// Load the repository and verify all metadata.
     /// This performs a 'refresh' to ensure we have the latest trusted state.
-    pub async fn verify_and_download(&self, target_name_str: &str) -> Result<PathBuf> {
+    async fn load(&self) -> Result<Repository> {
         let root_data = std::fs::read(&self.root_json_path).context("Failed to read root.json")?;
 
         // In tough 0.21, load() is async.
-        let repo = RepositoryLoader::new(
+        RepositoryLoader::new(
             &root_data,
             self.metadata_base_url.clone(),
             self.targets_base_url.clone(),
@@ -45,42 +63,99 @@ impl RepositoryVerifier {
         .transport(DefaultTransport::default())
         .load()
         .await
-        .context("TUF repository load/refresh failed")?;
+        .context("TUF repository load/refresh failed")
+    }
 
-        // Download and verify the target
-        let target_path = self.cache_dir.join("targets").join(target_name_str);
-        if let Some(parent) = target_path.parent() {
-            std::fs::create_dir_all(parent)?;
-        }
+    /// The verified `targets.json`, listing every published artifact with its size.
+    /// Cache it for [`ReleaseIndex::open`](crate::dependency::ReleaseIndex::open).
+    pub async fn fetch_targets(&self) -> Result<Vec<u8>> {
+        let repo = self.load().await?;
+        Ok(serde_json::to_vec(repo.targets())?)
+    }
+
+    /// Download a target into the cache, verifying it against the signed metadata.
+    pub async fn verify_and_download(&self, target_name_str: &str) -> Result<PathBuf> {
+        self.verify_and_download_with_progress(target_name_str, |_, _| {})
+            .await
+    }
+
+    /// Like [`Self::verify_and_download`], calling `progress` with the bytes received so
+    /// far and the target's signed length as each chunk arrives.
+    pub async fn verify_and_download_with_progress(
+        &self,
+        target_name_str: &str,
+        progress: impl FnMut(u64, Option<u64>),
+    ) -> Result<PathBuf> {
+        let repo = self.load().await?;
 
         // Convert string to TargetName (using the public type)
         let target_name: TargetName = target_name_str
             .try_into()
             .map_err(|e| anyhow::anyhow!("Invalid target name: {}", e))?;
+        let target = repo
+            .targets()
+            .signed
+            .find_target(&target_name, false)
+            .with_context(|| {
+                format!(
+                    "Failed to find target '{}' in TUF manifest",
+                    target_name_str
+                )
+            })?;
 
-        // read_target returns a stream of verified chunks.
-        let mut stream = repo
-            .read_target(&target_name)
-            .await
-            .context(format!(
-                "Failed to find target '{}' in TUF manifest",
-                target_name_str
-            ))?
-            .context("Target not found")?;
+        let sha256 = hex::encode(&target.hashes.sha256);
+        let file_name = target_file_name(
+            target_name.resolved(),
+            &sha256,
+            repo.root().signed.consistent_snapshot,
+        );
+        let mirrors = std::iter::once(&self.targets_base_url)
+            .chain(&self.target_mirrors)
+            .map(|base| directory(base).join(&file_name))
+            .collect::<Result<Vec<_>, _>>()?;
+        let artifact = Artifact {
+            mirrors,
+            checksum: Checksum::Sha256(sha256),
+            size: Some(target.length),
+        };
 
-        let mut file = tokio::fs::File::create(&target_path)
+        // Signed length and hash are enforced while streaming into a partial file, so an
+        // interrupted download never leaves a truncated target behind.
+        let target_path = self.cache_dir.join("targets").join(target_name_str);
+        self.downloader
+            .fetch_with_progress(&artifact, &target_path, progress)
             .await
-            .context("Failed to create local target file")?;
+            .with_context(|| format!("Failed to download target '{}'", target_name_str))?;
 
-        while let Some(chunk_result) = stream.next().await {
-            let chunk = chunk_result.context("Failure while downloading/verifying target chunk")?;
-            file.write_all(&chunk)
-                .await
-                .context("Failed to write target chunk to disk")?;
-        }
+        Ok(target_path)
+    }
+}
+
+/// The file a target is served as: `<sha256>.<name>` under consistent snapshots, like
+/// tough expects, otherwise its name.
+pub fn target_file_name(name: &str, sha256: &str, consistent_snapshot: bool) -> String {
+    if consistent_snapshot {
+        format!("{}.{}", sha256, name)
+    } else {
+        name.to_string()
+    }
+}
 
-        file.flush().await?;
+/// Whether the trusted root at `root_json_path` serves targets under consistent
+/// snapshots. An unreadable root counts as no.
+pub fn consistent_snapshot(root_json_path: &Path) -> bool {
+    std::fs::read(root_json_path)
+        .ok()
+        .and_then(|root| serde_json::from_slice::<serde_json::Value>(&root).ok())
+        .and_then(|root| root.pointer("/signed/consistent_snapshot")?.as_bool())
+        .unwrap_or(false)
+}
 
-        Ok(target_path)
+/// `base` as a directory URL, so joining a file name appends rather than replaces.
+fn directory(base: &Url) -> Url {
+    let mut base = base.clone();
+    if !base.path().ends_with('/') {
+        base.set_path(&format!("{}/", base.path()));
     }
+    base
 }
@@ -0,0 +1,128 @@
+use anyhow::{Context, Result};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// How long a cached `--version` result is trusted before the binary is run again.
+pub const DEFAULT_SCAN_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CacheEntry {
+    /// Modification time of the binary when it was probed, in nanoseconds.
+    mtime: u64,
+    version: Version,
+    /// When it was probed, in seconds since the epoch.
+    scanned_at: u64,
+}
+
+/// On-disk cache of tool versions, keyed by binary path plus mtime.
+///
+/// Reinstalling or upgrading a tool changes its binary's mtime, which invalidates the
+/// entry; the TTL catches anything else (e.g. a wrapper script whose target changed).
+pub struct ScanCache {
+    path: PathBuf,
+    ttl: Duration,
+    entries: HashMap<PathBuf, CacheEntry>,
+}
+
+impl ScanCache {
+    /// `~/.architect/cache/tool-scan.json`
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| home.join(".architect").join("cache").join("tool-scan.json"))
+    }
+
+    /// Load the cache at `path`; a missing or unreadable file starts empty.
+    pub fn open(path: impl Into<PathBuf>, ttl: Duration) -> Self {
+        let path = path.into();
+        let entries = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { path, ttl, entries }
+    }
+
+    /// The cached version of `binary`, if it hasn't changed or expired since.
+    pub fn get(&self, binary: &Path) -> Option<Version> {
+        let entry = self.entries.get(binary)?;
+        let fresh = now().saturating_sub(entry.scanned_at) < self.ttl.as_secs();
+        (fresh && mtime(binary)? == entry.mtime).then(|| entry.version.clone())
+    }
+
+    pub fn insert(&mut self, binary: &Path, version: &Version) {
+        if let Some(mtime) = mtime(binary) {
+            self.entries.insert(
+                binary.to_path_buf(),
+                CacheEntry {
+                    mtime,
+                    version: version.clone(),
+                    scanned_at: now(),
+                },
+            );
+        }
+    }
+
+    /// Forget every entry, so the next scan probes each binary again.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string(&self.entries)?)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+}
+
+fn mtime(path: &Path) -> Option<u64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    let nanos = modified.duration_since(UNIX_EPOCH).ok()?.as_nanos();
+    u64::try_from(nanos).ok()
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cache_hits_and_invalidation() {
+        let temp = tempfile::tempdir().unwrap();
+        let binary = temp.path().join("node");
+        std::fs::write(&binary, "v1").unwrap();
+        let cache_path = temp.path().join("cache/tool-scan.json");
+
+        let mut cache = ScanCache::open(&cache_path, DEFAULT_SCAN_TTL);
+        assert_eq!(cache.get(&binary), None);
+        cache.insert(&binary, &Version::new(20, 9, 0));
+        cache.save().unwrap();
+
+        // Survives a reload.
+        let mut cache = ScanCache::open(&cache_path, DEFAULT_SCAN_TTL);
+        assert_eq!(cache.get(&binary), Some(Version::new(20, 9, 0)));
+
+        // A rewritten binary is probed again.
+        let file = std::fs::File::options().write(true).open(&binary).unwrap();
+        file.set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        assert_eq!(cache.get(&binary), None);
+
+        cache.insert(&binary, &Version::new(21, 0, 0));
+        assert!(cache.get(&binary).is_some());
+        cache.clear();
+        assert_eq!(cache.get(&binary), None);
+
+        // Entries older than the TTL are ignored.
+        let mut expired = ScanCache::open(&cache_path, Duration::ZERO);
+        expired.insert(&binary, &Version::new(21, 0, 0));
+        assert_eq!(expired.get(&binary), None);
+    }
+}
@@ -0,0 +1,579 @@
+use super::registry::{find_executable, InstalledVersion, ToolManager};
+use semver::Version;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Distro package names worth recording, and the tool each provides.
+const DISTRO_PACKAGES: &[(&str, &str)] = &[
+    ("nodejs", "node"),
+    ("npm", "npm"),
+    ("yarn", "yarn"),
+    ("python", "python"),
+    ("python3", "python3"),
+    ("rust", "rustc"),
+    ("rustc", "rustc"),
+    ("cargo", "cargo"),
+    ("golang", "go"),
+    ("golang-go", "go"),
+    ("go", "go"),
+    ("git", "git"),
+    ("make", "make"),
+];
+
+/// A source of installed tool versions beyond what `PATH` shows.
+pub trait DiscoveryStrategy: Send + Sync {
+    /// The manager whose installs this strategy finds.
+    fn manager(&self) -> ToolManager;
+
+    /// Every installed version it can see. A missing install root yields nothing.
+    fn discover(&self) -> Vec<InstalledVersion>;
+}
+
+/// The strategies for this machine, rooted where each manager keeps its installs
+/// (honouring `NVM_DIR`, `PYENV_ROOT`, `RUSTUP_HOME` and friends).
+pub fn default_strategies() -> Vec<Box<dyn DiscoveryStrategy>> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+    let env_or =
+        |var: &str, default: PathBuf| std::env::var_os(var).map(PathBuf::from).unwrap_or(default);
+
+    let mut strategies: Vec<Box<dyn DiscoveryStrategy>> = vec![
+        Box::new(VersionDirs {
+            manager: ToolManager::Nvm,
+            tool: "node",
+            root: env_or("NVM_DIR", home.join(".nvm"))
+                .join("versions")
+                .join("node"),
+            binary: "bin/node",
+        }),
+        Box::new(VersionDirs {
+            manager: ToolManager::Fnm,
+            tool: "node",
+            root: env_or("FNM_DIR", fnm_default_dir(&home)).join("node-versions"),
+            binary: "installation/bin/node",
+        }),
+        Box::new(VersionDirs {
+            manager: ToolManager::Pyenv,
+            tool: "python",
+            root: env_or("PYENV_ROOT", home.join(".pyenv")).join("versions"),
+            binary: "bin/python",
+        }),
+        Box::new(Rustup {
+            root: env_or("RUSTUP_HOME", home.join(".rustup")),
+        }),
+        Box::new(ToolDirs {
+            manager: ToolManager::Asdf,
+            root: env_or("ASDF_DATA_DIR", home.join(".asdf")).join("installs"),
+        }),
+        Box::new(ToolDirs {
+            manager: ToolManager::Mise,
+            root: env_or("MISE_DATA_DIR", home.join(".local/share/mise")).join("installs"),
+        }),
+    ];
+
+    let cellars = match std::env::var_os("HOMEBREW_CELLAR") {
+        Some(cellar) => vec![PathBuf::from(cellar)],
+        None => vec![
+            PathBuf::from("/opt/homebrew/Cellar"),
+            PathBuf::from("/usr/local/Cellar"),
+            PathBuf::from("/home/linuxbrew/.linuxbrew/Cellar"),
+        ],
+    };
+    for root in cellars {
+        strategies.push(Box::new(ToolDirs {
+            manager: ToolManager::Homebrew,
+            root,
+        }));
+    }
+
+    strategies.push(Box::new(Dpkg {
+        status: PathBuf::from("/var/lib/dpkg/status"),
+    }));
+    strategies.push(Box::new(Pacman {
+        local: PathBuf::from("/var/lib/pacman/local"),
+    }));
+    strategies.push(Box::new(Rpm));
+    strategies
+}
+
+/// Package and version managers usable on this machine, version managers first.
+/// nvm is a shell function rather than a binary, so it counts when `nvm.sh` exists.
+pub fn detect_managers(path: &OsStr) -> Vec<ToolManager> {
+    let mut managers = Vec::new();
+    let nvm_dir = std::env::var_os("NVM_DIR")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".nvm")));
+    if nvm_dir.is_some_and(|dir| dir.join("nvm.sh").is_file()) {
+        managers.push(ToolManager::Nvm);
+    }
+
+    let binaries = [
+        ("fnm", ToolManager::Fnm),
+        ("pyenv", ToolManager::Pyenv),
+        ("rustup", ToolManager::Rustup),
+        ("mise", ToolManager::Mise),
+        ("asdf", ToolManager::Asdf),
+        ("brew", ToolManager::Homebrew),
+        ("apt-get", ToolManager::Apt),
+        ("dnf", ToolManager::Dnf),
+        ("yum", ToolManager::Yum),
+        ("pacman", ToolManager::Pacman),
+    ];
+    for (binary, manager) in binaries {
+        if find_executable(binary, path).is_some() {
+            managers.push(manager);
+        }
+    }
+    managers
+}
+
+fn fnm_default_dir(home: &Path) -> PathBuf {
+    if cfg!(target_os = "macos") {
+        home.join("Library/Application Support/fnm")
+    } else {
+        home.join(".local/share/fnm")
+    }
+}
+
+/// One directory per version of a single tool, e.g. `~/.nvm/versions/node/v20.9.0`.
+/// Used for nvm, fnm and pyenv.
+pub struct VersionDirs {
+    pub manager: ToolManager,
+    pub tool: &'static str,
+    pub root: PathBuf,
+    /// The executable inside a version directory; installs without it are skipped.
+    pub binary: &'static str,
+}
+
+impl DiscoveryStrategy for VersionDirs {
+    fn manager(&self) -> ToolManager {
+        self.manager.clone()
+    }
+
+    fn discover(&self) -> Vec<InstalledVersion> {
+        subdirs(&self.root)
+            .into_iter()
+            .filter_map(|(name, dir)| {
+                let location = dir.join(self.binary);
+                Some(InstalledVersion {
+                    tool: self.tool.to_string(),
+                    version: parse_version_name(&name)?,
+                    location: location.exists().then_some(location)?,
+                    managed_by: self.manager.clone(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// `<root>/<tool>/<version>` for any tool: asdf and mise installs, Homebrew's Cellar.
+pub struct ToolDirs {
+    pub manager: ToolManager,
+    pub root: PathBuf,
+}
+
+impl DiscoveryStrategy for ToolDirs {
+    fn manager(&self) -> ToolManager {
+        self.manager.clone()
+    }
+
+    fn discover(&self) -> Vec<InstalledVersion> {
+        let mut found = Vec::new();
+        for (name, tool_dir) in subdirs(&self.root) {
+            let tool = tool_name(&name);
+            for (version_name, dir) in subdirs(&tool_dir) {
+                let Some(version) = parse_version_name(&version_name) else {
+                    continue;
+                };
+                let binary = dir.join("bin").join(&tool);
+                found.push(InstalledVersion {
+                    tool: tool.clone(),
+                    version,
+                    location: if binary.exists() { binary } else { dir },
+                    managed_by: self.manager.clone(),
+                });
+            }
+        }
+        found
+    }
+}
+
+/// rustup toolchains. Channel toolchains (`stable-x86_64-...`) are versioned through
+/// their channel manifest rather than the directory name.
+pub struct Rustup {
+    pub root: PathBuf,
+}
+
+impl DiscoveryStrategy for Rustup {
+    fn manager(&self) -> ToolManager {
+        ToolManager::Rustup
+    }
+
+    fn discover(&self) -> Vec<InstalledVersion> {
+        let mut found = Vec::new();
+        for (name, dir) in subdirs(&self.root.join("toolchains")) {
+            let manifest =
+                fs::read_to_string(dir.join("lib/rustlib/multirust-channel-manifest.toml"))
+                    .ok()
+                    .and_then(|content| content.parse::<toml::Table>().ok());
+            let package_version = |package: &str| {
+                manifest
+                    .as_ref()?
+                    .get("pkg")?
+                    .get(package)?
+                    .get("version")?
+                    .as_str()?
+                    .split_whitespace()
+                    .next()
+                    .and_then(parse_version_name)
+            };
+
+            // `1.75.0-x86_64-unknown-linux-gnu`
+            let from_name = name.split('-').next().and_then(parse_version_name);
+            let Some(rustc) = package_version("rustc").or(from_name) else {
+                continue;
+            };
+            let cargo = package_version("cargo").unwrap_or_else(|| rustc.clone());
+
+            for (tool, version) in [("rustc", rustc), ("cargo", cargo)] {
+                let location = dir.join("bin").join(tool);
+                if location.exists() {
+                    found.push(InstalledVersion {
+                        tool: tool.to_string(),
+                        version,
+                        location,
+                        managed_by: ToolManager::Rustup,
+                    });
+                }
+            }
+        }
+        found
+    }
+}
+
+/// Debian/Ubuntu: the dpkg status database.
+pub struct Dpkg {
+    pub status: PathBuf,
+}
+
+impl DiscoveryStrategy for Dpkg {
+    fn manager(&self) -> ToolManager {
+        ToolManager::Apt
+    }
+
+    fn discover(&self) -> Vec<InstalledVersion> {
+        let Ok(content) = fs::read_to_string(&self.status) else {
+            return Vec::new();
+        };
+
+        content
+            .split("\n\n")
+            .filter_map(|stanza| {
+                let field = |key: &str| {
+                    stanza.lines().find_map(|line| {
+                        line.strip_prefix(key)
+                            .and_then(|rest| rest.strip_prefix(':'))
+                            .map(str::trim)
+                    })
+                };
+                if !field("Status")?.ends_with(" installed") {
+                    return None;
+                }
+                distro_package(field("Package")?, field("Version")?, ToolManager::Apt)
+            })
+            .collect()
+    }
+}
+
+/// Arch: one `<name>-<version>-<release>/desc` directory per package.
+pub struct Pacman {
+    pub local: PathBuf,
+}
+
+impl DiscoveryStrategy for Pacman {
+    fn manager(&self) -> ToolManager {
+        ToolManager::Pacman
+    }
+
+    fn discover(&self) -> Vec<InstalledVersion> {
+        subdirs(&self.local)
+            .into_iter()
+            .filter_map(|(_, dir)| {
+                let desc = fs::read_to_string(dir.join("desc")).ok()?;
+                let field = |key: &str| {
+                    let mut lines = desc.lines();
+                    lines.find(|line| *line == key)?;
+                    lines.next()
+                };
+                distro_package(field("%NAME%")?, field("%VERSION%")?, ToolManager::Pacman)
+            })
+            .collect()
+    }
+}
+
+/// Fedora/RHEL: the rpm database is binary, so ask `rpm` itself.
+pub struct Rpm;
+
+impl DiscoveryStrategy for Rpm {
+    fn manager(&self) -> ToolManager {
+        if Path::new("/usr/bin/dnf").exists() {
+            ToolManager::Dnf
+        } else {
+            ToolManager::Yum
+        }
+    }
+
+    fn discover(&self) -> Vec<InstalledVersion> {
+        if !Path::new("/var/lib/rpm").exists() {
+            return Vec::new();
+        }
+        let Ok(output) = Command::new("rpm")
+            .args(["-qa", "--queryformat", "%{NAME} %{VERSION}\\n"])
+            .output()
+        else {
+            return Vec::new();
+        };
+
+        let manager = self.manager();
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let (name, version) = line.split_once(' ')?;
+                distro_package(name, version, manager.clone())
+            })
+            .collect()
+    }
+}
+
+/// An installed distro package, if it provides a tool we track.
+fn distro_package(name: &str, version: &str, manager: ToolManager) -> Option<InstalledVersion> {
+    let tool = DISTRO_PACKAGES
+        .iter()
+        .find(|(package, _)| *package == name)
+        .map(|(_, tool)| *tool)
+        .or_else(|| name.strip_prefix("python3.").map(|_| "python3"))?;
+
+    // `1:2.43.0-1ubuntu7`, `18.19.1+dfsg-6`, `3.12.3-1`
+    let upstream = version.split_once(':').map_or(version, |(_, rest)| rest);
+    let upstream = upstream.split(['-', '+', '~']).next()?;
+
+    Some(InstalledVersion {
+        tool: tool.to_string(),
+        version: parse_version_name(upstream)?,
+        location: PathBuf::from("/usr/bin").join(tool),
+        managed_by: manager,
+    })
+}
+
+/// Manager install directories use other names for some tools (asdf's `nodejs`,
+/// Homebrew's versioned `python@3.11`).
+fn tool_name(name: &str) -> String {
+    let name = name.split('@').next().unwrap_or(name);
+    match name {
+        "nodejs" => "node".to_string(),
+        "golang" => "go".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// `v20.9.0`, `3.11`, `3.11.4_1` (Homebrew revision). Names that aren't versions, such
+/// as `system`, `latest` or `pypy3.9-7.3.9`, yield `None`.
+pub fn parse_version_name(name: &str) -> Option<Version> {
+    let name = name.strip_prefix('v').unwrap_or(name);
+    let name = name.split('_').next()?;
+    if let Ok(version) = Version::parse(name) {
+        return Some(version);
+    }
+
+    let mut parts = name.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().map(str::parse).transpose().ok()?.unwrap_or(0);
+    match parts.next() {
+        None => Some(Version::new(major, minor, 0)),
+        Some(_) => None,
+    }
+}
+
+/// Real subdirectories as `(name, path)`, sorted by name. Symlinks are skipped: mise and
+/// asdf alias versions (`20 -> 20.9.0`, `latest`) that way.
+fn subdirs(root: &Path) -> Vec<(String, PathBuf)> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<(String, PathBuf)> = entries
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|t| t.is_dir()))
+        .filter_map(|entry| Some((entry.file_name().into_string().ok()?, entry.path())))
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn touch(path: &Path) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "").unwrap();
+    }
+
+    fn versions(found: &[InstalledVersion]) -> Vec<(String, String)> {
+        found
+            .iter()
+            .map(|v| (v.tool.clone(), v.version.to_string()))
+            .collect()
+    }
+
+    fn pair(tool: &str, version: &str) -> (String, String) {
+        (tool.to_string(), version.to_string())
+    }
+
+    #[test]
+    fn test_version_dirs_and_tool_dirs() {
+        let temp = tempfile::tempdir().unwrap();
+        let nvm = temp.path().join("nvm/versions/node");
+        touch(&nvm.join("v18.19.0/bin/node"));
+        touch(&nvm.join("v20.9.0/bin/node"));
+        fs::create_dir_all(nvm.join("v21.0.0")).unwrap(); // half-installed
+
+        let found = VersionDirs {
+            manager: ToolManager::Nvm,
+            tool: "node",
+            root: nvm.clone(),
+            binary: "bin/node",
+        }
+        .discover();
+        assert_eq!(
+            versions(&found),
+            vec![pair("node", "18.19.0"), pair("node", "20.9.0")]
+        );
+        assert_eq!(found[1].location, nvm.join("v20.9.0/bin/node"));
+        assert_eq!(found[1].managed_by, ToolManager::Nvm);
+
+        let cellar = temp.path().join("Cellar");
+        touch(&cellar.join("python@3.11/3.11.7_1/bin/python3"));
+        touch(&cellar.join("node/21.5.0/bin/node"));
+        let found = ToolDirs {
+            manager: ToolManager::Homebrew,
+            root: cellar.clone(),
+        }
+        .discover();
+        assert_eq!(
+            versions(&found),
+            vec![pair("node", "21.5.0"), pair("python", "3.11.7")]
+        );
+        assert_eq!(found[0].location, cellar.join("node/21.5.0/bin/node"));
+
+        let installs = temp.path().join("asdf/installs");
+        touch(&installs.join("nodejs/20.9.0/bin/node"));
+        fs::create_dir_all(installs.join("golang/1.22.0")).unwrap();
+        fs::create_dir_all(installs.join("python/system")).unwrap();
+        let found = ToolDirs {
+            manager: ToolManager::Asdf,
+            root: installs,
+        }
+        .discover();
+        assert_eq!(
+            versions(&found),
+            vec![pair("go", "1.22.0"), pair("node", "20.9.0")]
+        );
+    }
+
+    #[test]
+    fn test_rustup_toolchains() {
+        let temp = tempfile::tempdir().unwrap();
+        let toolchains = temp.path().join("toolchains");
+        let stable = toolchains.join("stable-x86_64-unknown-linux-gnu");
+        touch(&stable.join("bin/rustc"));
+        touch(&stable.join("bin/cargo"));
+        let manifest = stable.join("lib/rustlib/multirust-channel-manifest.toml");
+        touch(&manifest);
+        fs::write(
+            &manifest,
+            "[pkg.rustc]\nversion = \"1.76.0 (07dca489a 2024-02-04)\"\n\
+             [pkg.cargo]\nversion = \"1.76.0 (c84b36747 2024-01-18)\"\n",
+        )
+        .unwrap();
+        touch(&toolchains.join("1.70.0-x86_64-unknown-linux-gnu/bin/rustc"));
+
+        let found = Rustup {
+            root: temp.path().to_path_buf(),
+        }
+        .discover();
+        assert_eq!(
+            versions(&found),
+            vec![
+                pair("rustc", "1.70.0"),
+                pair("rustc", "1.76.0"),
+                pair("cargo", "1.76.0"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_distro_databases() {
+        let temp = tempfile::tempdir().unwrap();
+        let status = temp.path().join("status");
+        fs::write(
+            &status,
+            "Package: git\nStatus: install ok installed\nVersion: 1:2.43.0-1ubuntu7\n\n\
+             Package: nodejs\nStatus: deinstall ok config-files\nVersion: 18.19.1+dfsg-6\n\n\
+             Package: python3.12\nStatus: install ok installed\nVersion: 3.12.3-1\n\n\
+             Package: libc6\nStatus: install ok installed\nVersion: 2.39-0ubuntu8\n",
+        )
+        .unwrap();
+        let found = Dpkg { status }.discover();
+        assert_eq!(
+            versions(&found),
+            vec![pair("git", "2.43.0"), pair("python3", "3.12.3")]
+        );
+        assert_eq!(found[0].managed_by, ToolManager::Apt);
+
+        let local = temp.path().join("local");
+        let desc = local.join("go-2:1.22.1-1/desc");
+        touch(&desc);
+        fs::write(&desc, "%NAME%\ngo\n\n%VERSION%\n2:1.22.1-1\n").unwrap();
+        assert_eq!(
+            versions(&Pacman { local }.discover()),
+            vec![pair("go", "1.22.1")]
+        );
+    }
+
+    #[test]
+    fn test_parse_version_name() {
+        assert_eq!(parse_version_name("v20.9.0"), Some(Version::new(20, 9, 0)));
+        assert_eq!(parse_version_name("3.11"), Some(Version::new(3, 11, 0)));
+        assert_eq!(parse_version_name("3.11.7_1"), Some(Version::new(3, 11, 7)));
+        assert_eq!(parse_version_name("system"), None);
+        assert_eq!(parse_version_name("pypy3.9-7.3.9"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_detect_managers() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = tempfile::tempdir().unwrap();
+        for binary in ["brew", "fnm", "pacman"] {
+            let path = temp.path().join(binary);
+            touch(&path);
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+        }
+
+        let managers: Vec<_> = detect_managers(temp.path().as_os_str())
+            .into_iter()
+            .filter(|m| *m != ToolManager::Nvm)
+            .collect();
+        assert_eq!(
+            managers,
+            [ToolManager::Fnm, ToolManager::Homebrew, ToolManager::Pacman]
+        );
+        assert!(ToolManager::Fnm.supports("nodejs"));
+        assert!(!ToolManager::Fnm.supports("python"));
+        assert!(ToolManager::Homebrew.supports("python"));
+    }
+}
@@ -0,0 +1,627 @@
+use anyhow::{Context, Result};
+use env_manifest::{parse_checksum, ChecksumAlgorithm};
+use futures_util::StreamExt;
+use reqwest::header::{CONTENT_RANGE, RANGE};
+use reqwest::StatusCode;
+use sha2::digest::DynDigest;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use url::Url;
+
+/// Attempts per mirror after the first, before failing over to the next one.
+const DEFAULT_RETRIES: u32 = 3;
+
+/// Delay before the first retry; each one after waits twice as long.
+const DEFAULT_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Downloads allowed to run against one host at a time.
+const DEFAULT_PER_HOST: usize = 4;
+
+/// Redirects followed per request, as reqwest's default policy allows.
+const MAX_REDIRECTS: usize = 10;
+
+/// Decides which hosts a [`Downloader`] may contact.
+type HostFilter = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// A digest an artifact must match, checked as it streams in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Checksum {
+    Sha256(String),
+    Sha384(String),
+    Sha512(String),
+}
+
+impl Checksum {
+    /// Parse `<algorithm>:<hex>` as manifests write it (`sha256:…`).
+    pub fn parse(raw: &str) -> Result<Self> {
+        let (algorithm, digest) =
+            parse_checksum(raw).map_err(|e| anyhow::anyhow!("Checksum '{}' {}", raw, e))?;
+        Ok(match algorithm {
+            ChecksumAlgorithm::Sha256 => Self::Sha256(digest),
+            ChecksumAlgorithm::Sha384 => Self::Sha384(digest),
+            ChecksumAlgorithm::Sha512 => Self::Sha512(digest),
+        })
+    }
+
+    fn digest(&self) -> &str {
+        match self {
+            Self::Sha256(digest) | Self::Sha384(digest) | Self::Sha512(digest) => digest,
+        }
+    }
+
+    fn hasher(&self) -> Box<dyn DynDigest + Send> {
+        match self {
+            Self::Sha256(_) => Box::new(sha2::Sha256::default()),
+            Self::Sha384(_) => Box::new(sha2::Sha384::default()),
+            Self::Sha512(_) => Box::new(sha2::Sha512::default()),
+        }
+    }
+
+    fn matches(&self, hasher: Box<dyn DynDigest + Send>) -> Result<(), String> {
+        let actual = hex::encode(hasher.finalize());
+        if actual.eq_ignore_ascii_case(self.digest()) {
+            Ok(())
+        } else {
+            Err(actual)
+        }
+    }
+}
+
+impl fmt::Display for Checksum {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let algorithm = match self {
+            Self::Sha256(_) => "sha256",
+            Self::Sha384(_) => "sha384",
+            Self::Sha512(_) => "sha512",
+        };
+        write!(f, "{}:{}", algorithm, self.digest())
+    }
+}
+
+/// Something to download: the same bytes, wherever they're served from.
+#[derive(Debug, Clone)]
+pub struct Artifact {
+    /// Tried in order; the next one takes over when a mirror keeps failing.
+    pub mirrors: Vec<Url>,
+    pub checksum: Checksum,
+    /// Exact size when known (a TUF target's signed length); more bytes than this is
+    /// an error rather than something to keep reading.
+    pub size: Option<u64>,
+}
+
+impl Artifact {
+    pub fn new(url: Url, checksum: Checksum) -> Self {
+        Self {
+            mirrors: vec![url],
+            checksum,
+            size: None,
+        }
+    }
+}
+
+/// The one way artifacts are fetched: TUF targets, manifest assets and AutoShim
+/// binaries alike.
+///
+/// Bytes go to `<dest>.partial`, which later attempts resume with a range request.
+/// Failures are retried with backoff, then the next mirror is tried. The checksum is
+/// computed while streaming, and the file only appears at `dest` once it matches.
+#[derive(Clone)]
+pub struct Downloader {
+    client: reqwest::Client,
+    retries: u32,
+    backoff: Duration,
+    per_host: usize,
+    hosts: Arc<Mutex<HashMap<String, Arc<Semaphore>>>>,
+    allow_host: Option<HostFilter>,
+}
+
+/// Why an attempt failed, and whether the same mirror is worth another try.
+enum Failure {
+    Retry(anyhow::Error),
+    Abandon(anyhow::Error),
+}
+
+impl Default for Downloader {
+    fn default() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            retries: DEFAULT_RETRIES,
+            backoff: DEFAULT_BACKOFF,
+            per_host: DEFAULT_PER_HOST,
+            hosts: Arc::default(),
+            allow_host: None,
+        }
+    }
+}
+
+impl Downloader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Retries per mirror after the first attempt.
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Delay before the first retry, doubled for each later one.
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// How many downloads may run against one host at a time.
+    pub fn with_per_host_limit(mut self, limit: usize) -> Self {
+        self.per_host = limit.max(1);
+        self
+    }
+
+    /// Only contact hosts `allow` accepts: every mirror, and every host a redirect
+    /// leads to along the way.
+    pub fn with_allowed_hosts(
+        mut self,
+        allow: impl Fn(&str) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        let allow: HostFilter = Arc::new(allow);
+        let redirects = Arc::clone(&allow);
+        let policy =
+            reqwest::redirect::Policy::custom(move |attempt| match attempt.url().host_str() {
+                _ if attempt.previous().len() > MAX_REDIRECTS => {
+                    attempt.error("too many redirects")
+                }
+                Some(host) if redirects(host) => attempt.follow(),
+                host => {
+                    let error = format!("redirect to {} is not allowed", host.unwrap_or("no host"));
+                    attempt.error(error)
+                }
+            });
+        self.client = reqwest::Client::builder()
+            .redirect(policy)
+            .build()
+            .expect("HTTP client should build");
+        self.allow_host = Some(allow);
+        self
+    }
+
+    /// Download `artifact` to `dest`.
+    pub async fn fetch(&self, artifact: &Artifact, dest: &Path) -> Result<()> {
+        self.fetch_with_progress(artifact, dest, |_, _| {}).await
+    }
+
+    /// Like [`Self::fetch`], calling `progress` with the bytes received so far (counting
+    /// any resumed part) and the total, when known, as chunks arrive.
+    pub async fn fetch_with_progress(
+        &self,
+        artifact: &Artifact,
+        dest: &Path,
+        mut progress: impl FnMut(u64, Option<u64>),
+    ) -> Result<()> {
+        anyhow::ensure!(
+            !artifact.mirrors.is_empty(),
+            "No URL to download {} from",
+            dest.display()
+        );
+        // Already there and intact, e.g. a target cached by an earlier install.
+        if hash_file(dest, &artifact.checksum).await.is_ok_and(|ok| ok) {
+            return Ok(());
+        }
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        let partial = partial_path(dest);
+        let mut errors = Vec::new();
+        for url in &artifact.mirrors {
+            match self
+                .fetch_from(url, artifact, &partial, &mut progress)
+                .await
+            {
+                Ok(()) => {
+                    return tokio::fs::rename(&partial, dest).await.with_context(|| {
+                        format!("Failed to move download into {}", dest.display())
+                    })
+                }
+                Err(e) => errors.push(format!("{}: {:#}", url, e)),
+            }
+        }
+        anyhow::bail!(
+            "Failed to download {}:\n  {}",
+            dest.display(),
+            errors.join("\n  ")
+        )
+    }
+
+    /// One mirror, with retries.
+    async fn fetch_from(
+        &self,
+        url: &Url,
+        artifact: &Artifact,
+        partial: &Path,
+        progress: &mut dyn FnMut(u64, Option<u64>),
+    ) -> Result<()> {
+        if let Some(allow) = &self.allow_host {
+            let host = url.host_str().unwrap_or_default();
+            anyhow::ensure!(allow(host), "{} is not an allowed host", host);
+        }
+
+        let mut attempt = 0;
+        loop {
+            match self.attempt(url, artifact, partial, progress).await {
+                Ok(()) => return Ok(()),
+                Err(Failure::Retry(_)) if attempt < self.retries => {
+                    tokio::time::sleep(self.backoff * 2u32.pow(attempt)).await;
+                    attempt += 1;
+                }
+                Err(Failure::Retry(e) | Failure::Abandon(e)) => return Err(e),
+            }
+        }
+    }
+
+    /// A single request, resuming whatever `partial` already holds.
+    async fn attempt(
+        &self,
+        url: &Url,
+        artifact: &Artifact,
+        partial: &Path,
+        progress: &mut dyn FnMut(u64, Option<u64>),
+    ) -> Result<(), Failure> {
+        let _permit = self.permit(url).await;
+        let offset = tokio::fs::metadata(partial)
+            .await
+            .map(|meta| meta.len())
+            .unwrap_or(0);
+
+        let mut request = self.client.get(url.clone());
+        if offset > 0 {
+            request = request.header(RANGE, format!("bytes={}-", offset));
+        }
+        let response = request.send().await.map_err(|e| {
+            // A refused redirect won't be allowed on the next try either.
+            let redirect = e.is_redirect();
+            let error = anyhow::Error::new(e).context("Request failed");
+            if redirect {
+                Failure::Abandon(error)
+            } else {
+                Failure::Retry(error)
+            }
+        })?;
+
+        let status = response.status();
+        let resumed = offset > 0 && status == StatusCode::PARTIAL_CONTENT;
+        if offset > 0 && status == StatusCode::RANGE_NOT_SATISFIABLE {
+            // Nothing left to send: either the partial is complete or it's not ours.
+            return match hash_file(partial, &artifact.checksum).await {
+                Ok(true) => Ok(()),
+                _ => Err(restart(partial, "Partial download does not match").await),
+            };
+        }
+        if !status.is_success() {
+            let error = anyhow::anyhow!("Server returned {}", status);
+            let transient = status.is_server_error()
+                || status == StatusCode::TOO_MANY_REQUESTS
+                || status == StatusCode::REQUEST_TIMEOUT;
+            return Err(if transient {
+                Failure::Retry(error)
+            } else {
+                Failure::Abandon(error)
+            });
+        }
+        if resumed && content_range_start(&response) != Some(offset) {
+            return Err(restart(partial, "Server resumed from the wrong offset").await);
+        }
+
+        let mut hasher = artifact.checksum.hasher();
+        let (mut file, mut received) = if resumed {
+            let file = hash_into(partial, hasher.as_mut())
+                .await
+                .map_err(Failure::Abandon)?;
+            (file, offset)
+        } else {
+            // A fresh request, or a server that ignored the range: start over.
+            let file = tokio::fs::File::create(partial)
+                .await
+                .with_context(|| format!("Failed to create {}", partial.display()))
+                .map_err(Failure::Abandon)?;
+            (file, 0)
+        };
+        let total = artifact
+            .size
+            .or_else(|| response.content_length().map(|length| received + length));
+
+        progress(received, total);
+        let mut stream = response.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|e| {
+                Failure::Retry(anyhow::Error::new(e).context("Download was interrupted"))
+            })?;
+            received += chunk.len() as u64;
+            if let Some(size) = artifact.size.filter(|size| received > *size) {
+                drop(file);
+                let _ = tokio::fs::remove_file(partial).await;
+                return Err(Failure::Abandon(anyhow::anyhow!(
+                    "Server sent more than the expected {} bytes",
+                    size
+                )));
+            }
+            hasher.update(&chunk);
+            file.write_all(&chunk)
+                .await
+                .context("Failed to write download to disk")
+                .map_err(Failure::Abandon)?;
+            progress(received, total);
+        }
+        file.flush().await.map_err(|e| Failure::Abandon(e.into()))?;
+        drop(file);
+
+        if artifact.size.is_some_and(|size| received < size) {
+            // Keep what arrived; the retry picks up from here.
+            return Err(Failure::Retry(anyhow::anyhow!(
+                "Connection closed after {} of {} bytes",
+                received,
+                artifact.size.unwrap_or_default()
+            )));
+        }
+        match artifact.checksum.matches(hasher) {
+            Ok(()) => Ok(()),
+            Err(actual) => {
+                let _ = tokio::fs::remove_file(partial).await;
+                let error = anyhow::anyhow!(
+                    "Checksum mismatch: expected {}, got {}",
+                    artifact.checksum,
+                    actual
+                );
+                // A stale partial may be to blame; only a clean download condemns the mirror.
+                Err(if resumed {
+                    Failure::Retry(error)
+                } else {
+                    Failure::Abandon(error)
+                })
+            }
+        }
+    }
+
+    /// Wait for a slot on `url`'s host.
+    async fn permit(&self, url: &Url) -> Option<OwnedSemaphorePermit> {
+        let host = format!(
+            "{}:{}",
+            url.host_str().unwrap_or_default(),
+            url.port_or_known_default().unwrap_or_default()
+        );
+        let semaphore = self
+            .hosts
+            .lock()
+            .unwrap()
+            .entry(host)
+            .or_insert_with(|| Arc::new(Semaphore::new(self.per_host)))
+            .clone();
+        semaphore.acquire_owned().await.ok()
+    }
+}
+
+/// `node.tar.gz` downloads into `node.tar.gz.partial`.
+fn partial_path(dest: &Path) -> PathBuf {
+    let mut name = dest.file_name().unwrap_or_default().to_os_string();
+    name.push(".partial");
+    dest.with_file_name(name)
+}
+
+/// Drop `partial` and have the next attempt start from scratch.
+async fn restart(partial: &Path, reason: &str) -> Failure {
+    let _ = tokio::fs::remove_file(partial).await;
+    Failure::Retry(anyhow::anyhow!("{}; restarting", reason))
+}
+
+/// First byte of a `206` response, from `Content-Range: bytes <start>-<end>/<total>`.
+fn content_range_start(response: &reqwest::Response) -> Option<u64> {
+    let value = response.headers().get(CONTENT_RANGE)?.to_str().ok()?;
+    let range = value.strip_prefix("bytes ")?;
+    range.split(['-', '/']).next()?.trim().parse().ok()
+}
+
+/// Feed the existing contents of `path` to `hasher`, returning it open for appending.
+async fn hash_into(path: &Path, hasher: &mut (dyn DynDigest + Send)) -> Result<tokio::fs::File> {
+    let mut file = tokio::fs::OpenOptions::new()
+        .read(true)
+        .append(true)
+        .open(path)
+        .await
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer).await?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(file)
+}
+
+/// Whether the file at `path` matches `checksum`.
+async fn hash_file(path: &Path, checksum: &Checksum) -> Result<bool> {
+    let mut hasher = checksum.hasher();
+    hash_into(path, hasher.as_mut()).await?;
+    Ok(checksum.matches(hasher).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::{Digest, Sha256};
+    use tokio::net::TcpListener;
+
+    const BODY: &[u8] = b"#!/bin/sh\necho hello from a resumable download\n";
+
+    /// Serve `BODY` over plain HTTP with `status`, honouring `Range: bytes=N-`. The first
+    /// `cut` responses stop halfway. Returns the URL and each request's range header.
+    async fn serve(status: u16, cut: usize) -> (Url, Arc<Mutex<Vec<Option<String>>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("http://{}/tool", listener.local_addr().unwrap())).unwrap();
+        let ranges = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&ranges);
+        tokio::spawn(async move {
+            let mut served = 0;
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = [0u8; 1024];
+                let read = socket.read(&mut request).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&request[..read]).to_ascii_lowercase();
+                let range = request
+                    .lines()
+                    .find_map(|line| line.strip_prefix("range: bytes="))
+                    .map(|range| range.trim_end_matches('-').to_string());
+                seen.lock().unwrap().push(range.clone());
+
+                let start: usize = range.and_then(|r| r.parse().ok()).unwrap_or(0);
+                let (code, body) = match status {
+                    200 if start > 0 => (206, &BODY[start..]),
+                    200 => (200, BODY),
+                    other => (other, &b""[..]),
+                };
+                let mut head = format!(
+                    "HTTP/1.1 {} X\r\ncontent-length: {}\r\nconnection: close\r\n",
+                    code,
+                    body.len()
+                );
+                if code == 206 {
+                    head += &format!(
+                        "content-range: bytes {}-{}/{}\r\n",
+                        start,
+                        BODY.len() - 1,
+                        BODY.len()
+                    );
+                }
+                head += "\r\n";
+                let _ = socket.write_all(head.as_bytes()).await;
+                let body = if served < cut {
+                    &body[..body.len() / 2]
+                } else {
+                    body
+                };
+                served += 1;
+                let _ = socket.write_all(body).await;
+            }
+        });
+        (url, ranges)
+    }
+
+    /// Answer every request with a redirect to `location`.
+    async fn redirect(location: Url) -> Url {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("http://{}/tool", listener.local_addr().unwrap())).unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut request = [0u8; 1024];
+                let _ = socket.read(&mut request).await;
+                let head = format!(
+                    "HTTP/1.1 302 X\r\nlocation: {}\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                    location
+                );
+                let _ = socket.write_all(head.as_bytes()).await;
+            }
+        });
+        url
+    }
+
+    fn sha256() -> Checksum {
+        Checksum::Sha256(hex::encode(Sha256::digest(BODY)))
+    }
+
+    #[tokio::test]
+    async fn test_download_verified() {
+        let (url, _) = serve(200, 0).await;
+        let temp = tempfile::tempdir().unwrap();
+        let dest = temp.path().join("downloads/tool");
+        let downloader = Downloader::new().with_backoff(Duration::from_millis(1));
+
+        let wrong = Artifact::new(url.clone(), Checksum::Sha256("0".repeat(64)));
+        let err = downloader.fetch(&wrong, &dest).await.unwrap_err();
+        assert!(format!("{:#}", err).contains("Checksum mismatch"));
+        assert!(!dest.exists() && !partial_path(&dest).exists());
+
+        let checksum = Checksum::parse(&format!(
+            "sha256:{}",
+            hex::encode(Sha256::digest(BODY)).to_uppercase()
+        ))
+        .unwrap();
+        downloader
+            .fetch(&Artifact::new(url, checksum), &dest)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), BODY);
+        let blake3 = format!("blake3:{}", "ab".repeat(32));
+        let err = Checksum::parse(&blake3).unwrap_err();
+        assert!(err.to_string().contains("unsupported algorithm 'blake3'"));
+    }
+
+    #[tokio::test]
+    async fn test_resume_and_failover() {
+        let (missing, _) = serve(404, 0).await;
+        let (flaky, ranges) = serve(200, 1).await;
+        let temp = tempfile::tempdir().unwrap();
+        let dest = temp.path().join("tool.tar.gz");
+
+        let artifact = Artifact {
+            mirrors: vec![missing, flaky],
+            checksum: sha256(),
+            size: Some(BODY.len() as u64),
+        };
+        let mut seen = 0;
+        Downloader::new()
+            .with_backoff(Duration::from_millis(1))
+            .fetch_with_progress(&artifact, &dest, |received, total| {
+                assert_eq!(total, Some(BODY.len() as u64));
+                seen = received;
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(std::fs::read(&dest).unwrap(), BODY);
+        assert_eq!(seen, BODY.len() as u64);
+        // Cut off halfway, then resumed from where it stopped.
+        let half = (BODY.len() / 2).to_string();
+        assert_eq!(*ranges.lock().unwrap(), [None, Some(half)]);
+        assert!(!temp.path().join("tool.tar.gz.partial").exists());
+    }
+
+    #[tokio::test]
+    async fn test_redirects_stay_on_allowed_hosts() {
+        let (origin, ranges) = serve(200, 0).await;
+        let mut elsewhere = origin.clone();
+        elsewhere.set_host(Some("localhost")).unwrap();
+        let url = redirect(elsewhere).await;
+        let temp = tempfile::tempdir().unwrap();
+        let dest = temp.path().join("tool");
+        let artifact = Artifact::new(url, sha256());
+
+        let downloader = Downloader::new()
+            .with_backoff(Duration::from_millis(1))
+            .with_allowed_hosts(|host| host == "127.0.0.1");
+        let err = downloader.fetch(&artifact, &dest).await.unwrap_err();
+        assert!(
+            format!("{:#}", err).contains("redirect to localhost is not allowed"),
+            "{:#}",
+            err
+        );
+        assert!(ranges.lock().unwrap().is_empty() && !dest.exists());
+
+        let err = Downloader::new()
+            .with_allowed_hosts(|host| host == "localhost")
+            .fetch(&artifact, &dest)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("127.0.0.1 is not an allowed host"));
+
+        Downloader::new()
+            .with_allowed_hosts(|host| ["127.0.0.1", "localhost"].contains(&host))
+            .fetch(&artifact, &dest)
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), BODY);
+    }
+}
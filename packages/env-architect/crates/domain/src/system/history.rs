@@ -0,0 +1,97 @@
+use super::registry::tool_names;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Durations kept per tool; older installs age out.
+const MAX_SAMPLES: usize = 10;
+
+/// How long installs of each tool took on this machine.
+pub struct InstallHistory {
+    path: PathBuf,
+    /// Seconds, oldest first.
+    durations: HashMap<String, Vec<u64>>,
+}
+
+impl InstallHistory {
+    /// `~/.architect/cache/install-times.json`
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| {
+            home.join(".architect")
+                .join("cache")
+                .join("install-times.json")
+        })
+    }
+
+    /// Load the history at `path`; a missing or unreadable file starts empty.
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let durations = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { path, durations }
+    }
+
+    /// Add an install of `tool`, filed under the first of its names so aliases share samples.
+    pub fn record(&mut self, tool: &str, duration: Duration) {
+        let name = tool_names(tool)[0];
+        let samples = self.durations.entry(name.to_string()).or_default();
+        samples.push(duration.as_secs().max(1));
+        if samples.len() > MAX_SAMPLES {
+            samples.drain(..samples.len() - MAX_SAMPLES);
+        }
+    }
+
+    /// The median of recent installs of `tool` under any of its names.
+    pub fn estimate(&self, tool: &str) -> Option<Duration> {
+        let mut samples: Vec<u64> = tool_names(tool)
+            .into_iter()
+            .filter_map(|name| self.durations.get(name))
+            .flatten()
+            .copied()
+            .collect();
+        if samples.is_empty() {
+            return None;
+        }
+        samples.sort_unstable();
+        Some(Duration::from_secs(samples[samples.len() / 2]))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string(&self.durations)?)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_install_history() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("cache/install-times.json");
+
+        let mut history = InstallHistory::open(&path);
+        assert_eq!(history.estimate("node"), None);
+        for (tool, secs) in [("node", 40), ("nodejs", 300), ("node", 45)] {
+            history.record(tool, Duration::from_secs(secs));
+        }
+        assert_eq!(history.durations.keys().collect::<Vec<_>>(), ["node"]);
+        history.save().unwrap();
+
+        // The median ignores the one slow install, and aliases share samples.
+        let mut history = InstallHistory::open(&path);
+        assert_eq!(history.estimate("nodejs"), Some(Duration::from_secs(45)));
+
+        for _ in 0..MAX_SAMPLES {
+            history.record("node", Duration::from_secs(20));
+        }
+        assert_eq!(history.estimate("node"), Some(Duration::from_secs(20)));
+    }
+}
@@ -1,7 +1,19 @@
+pub mod cache;
+pub mod discovery;
+pub mod download;
+pub mod history;
 pub mod platform;
+pub mod projects;
 pub mod registry;
+pub mod sizes;
 pub mod store;
 
+pub use cache::{ScanCache, DEFAULT_SCAN_TTL};
+pub use discovery::DiscoveryStrategy;
+pub use download::{Artifact, Checksum, Downloader};
+pub use history::InstallHistory;
 pub use platform::{Architecture, OsType, PlatformDetector, PlatformInfo};
+pub use projects::{Dependent, IndexedProject, ProjectIndex};
 pub use registry::{InstalledToolsRegistry, InstalledVersion, ToolManager};
+pub use sizes::SizeCache;
 pub use store::StoreManager;
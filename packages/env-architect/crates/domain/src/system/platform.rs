@@ -1,3 +1,4 @@
+use env_manifest::{CpuArchitecture, OperatingSystem, TargetEnv, TargetPlatform};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -55,6 +56,53 @@ pub struct PlatformInfo {
     pub arch: Architecture,
     pub distro: Option<String>, // For Linux distributions
     pub kernel_version: Option<String>,
+    /// C library on Linux (glibc or musl)
+    #[serde(default)]
+    pub libc: Option<TargetEnv>,
+}
+
+impl PlatformInfo {
+    /// The manifest's view of this platform, used to evaluate `[target.*]` tables
+    /// and `platform.requirements`.
+    pub fn target(&self) -> TargetPlatform {
+        // Rolling distributions report no release number. The kernel's isn't a stand-in
+        // for it, so version requirements are left unchecked (with a warning) instead.
+        let os_version = env_manifest::normalize_os_version(&self.os_version)
+            .is_some()
+            .then(|| self.os_version.clone());
+
+        TargetPlatform {
+            os: self.os_type.into(),
+            arch: self.arch.into(),
+            env: self.libc,
+            os_version,
+        }
+    }
+}
+
+impl From<OsType> for OperatingSystem {
+    fn from(os: OsType) -> Self {
+        match os {
+            OsType::MacOS => OperatingSystem::Macos,
+            OsType::Linux => OperatingSystem::Linux,
+            OsType::Windows => OperatingSystem::Windows,
+            OsType::FreeBSD => OperatingSystem::Freebsd,
+            OsType::OpenBSD => OperatingSystem::Openbsd,
+            OsType::Unknown => OperatingSystem::Any,
+        }
+    }
+}
+
+impl From<Architecture> for CpuArchitecture {
+    fn from(arch: Architecture) -> Self {
+        match arch {
+            Architecture::X86_64 => CpuArchitecture::X86_64,
+            Architecture::Aarch64 => CpuArchitecture::Aarch64,
+            Architecture::Arm => CpuArchitecture::Arm,
+            Architecture::I686 => CpuArchitecture::X86,
+            Architecture::Unknown => CpuArchitecture::Any,
+        }
+    }
 }
 
 impl fmt::Display for PlatformInfo {
@@ -85,9 +133,28 @@ impl PlatformDetector {
             arch,
             distro,
             kernel_version: sysinfo::System::kernel_version(),
+            libc: Self::detect_libc(os_type),
         }
     }
 
+    /// musl ships its dynamic loader as `/lib/ld-musl-<arch>.so.1`; anything else is glibc.
+    fn detect_libc(os_type: OsType) -> Option<TargetEnv> {
+        if os_type != OsType::Linux {
+            return None;
+        }
+
+        let musl = std::fs::read_dir("/lib").is_ok_and(|entries| {
+            entries
+                .flatten()
+                .any(|e| e.file_name().to_string_lossy().starts_with("ld-musl-"))
+        });
+        Some(if musl {
+            TargetEnv::Musl
+        } else {
+            TargetEnv::Gnu
+        })
+    }
+
     fn detect_os_type(info: &os_info::Info) -> OsType {
         match info.os_type() {
             os_info::Type::Macos => OsType::MacOS,
@@ -196,4 +263,40 @@ mod tests {
         // Should match with no constraints
         assert!(PlatformDetector::matches(&info, None, None));
     }
+
+    #[test]
+    fn test_target_evaluates_cfg() {
+        let info = PlatformInfo {
+            os_type: OsType::Linux,
+            os_version: "3.19".to_string(),
+            arch: Architecture::Aarch64,
+            distro: Some("Alpine Linux".to_string()),
+            kernel_version: None,
+            libc: Some(TargetEnv::Musl),
+        };
+
+        let cfg = env_manifest::CfgExpr::parse(
+            r#"cfg(all(target_os = "linux", target_arch = "aarch64", libc = "musl"))"#,
+        )
+        .unwrap();
+        assert!(cfg.eval(&info.target()));
+    }
+
+    #[test]
+    fn test_target_mapping() {
+        let info = PlatformInfo {
+            os_type: OsType::Linux,
+            os_version: "Rolling Release".to_string(),
+            arch: Architecture::X86_64,
+            distro: Some("Arch Linux".to_string()),
+            kernel_version: Some("6.9.1-arch1-1".to_string()),
+            libc: Some(TargetEnv::Gnu),
+        };
+        let target = info.target();
+
+        // Manifests may spell the architecture `amd64`.
+        assert!(CpuArchitecture::Amd64.admits(target.arch));
+        assert_eq!(target.os, OperatingSystem::Linux);
+        assert_eq!(target.os_version, None);
+    }
 }
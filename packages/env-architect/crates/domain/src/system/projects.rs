@@ -0,0 +1,291 @@
+use super::registry::tool_names;
+use anyhow::{Context, Result};
+use env_manifest::{
+    ChannelRegistry, Ecosystem, EnhancedManifest, PackageManager, VersionConstraint, VersionRange,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// A registered project and the tool versions it declares.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexedProject {
+    pub name: String,
+    pub manifest: PathBuf,
+    /// Manifest mtime (seconds) when it was indexed; a newer file needs re-reading.
+    #[serde(default)]
+    pub modified: Option<u64>,
+    /// Requirements from every dependency section, runtime ones winning, keyed by the
+    /// tool's first alias (`node` for `nodejs`).
+    pub requirements: BTreeMap<String, VersionConstraint>,
+    /// Package managers named by those requirements, which set their syntax.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub managers: BTreeMap<String, PackageManager>,
+}
+
+/// A project that depends on a tool, with what it asks for.
+#[derive(Debug, Clone, Copy)]
+pub struct Dependent<'a> {
+    pub root: &'a Path,
+    pub project: &'a IndexedProject,
+    pub requirement: &'a VersionConstraint,
+}
+
+/// Every project on this machine that Architect knows about, so impact analysis doesn't
+/// have to walk the filesystem. Fed by project installs and `GlobalManifest.projects`.
+pub struct ProjectIndex {
+    path: PathBuf,
+    projects: BTreeMap<PathBuf, IndexedProject>,
+}
+
+impl ProjectIndex {
+    /// `~/.architect/projects.json`
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| home.join(".architect").join("projects.json"))
+    }
+
+    /// Load the index at `path`; a missing or unreadable file starts empty.
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let projects = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { path, projects }
+    }
+
+    /// Record (or refresh) the project at `root` from its manifest.
+    pub fn register(&mut self, root: &Path, manifest_path: &Path, manifest: &EnhancedManifest) {
+        let mut requirements = BTreeMap::new();
+        let mut managers = BTreeMap::new();
+        // Later sections don't override earlier ones: runtime requirements win.
+        for deps in [
+            &manifest.dependencies,
+            &manifest.dev_dependencies,
+            &manifest.test_dependencies,
+            &manifest.build_dependencies,
+        ] {
+            for (tool, spec) in deps {
+                let name = tool_names(tool)[0];
+                if requirements.contains_key(name) {
+                    continue;
+                }
+                requirements.insert(name.to_string(), spec.constraint().clone());
+                if let Some(manager) = spec.manager() {
+                    managers.insert(name.to_string(), manager);
+                }
+            }
+        }
+
+        self.projects.insert(
+            root.to_path_buf(),
+            IndexedProject {
+                name: manifest.project.name.clone(),
+                manifest: manifest_path.to_path_buf(),
+                modified: mtime(manifest_path),
+                requirements,
+                managers,
+            },
+        );
+    }
+
+    /// Whether `root` is indexed from `manifest_path` as it is now.
+    pub fn is_current(&self, root: &Path, manifest_path: &Path) -> bool {
+        self.projects.get(root).is_some_and(|project| {
+            project.manifest == manifest_path && project.modified == mtime(manifest_path)
+        })
+    }
+
+    /// Forget projects whose manifest is gone. Returns how many were dropped.
+    pub fn prune(&mut self) -> usize {
+        let before = self.projects.len();
+        self.projects.retain(|_, project| project.manifest.exists());
+        before - self.projects.len()
+    }
+
+    pub fn projects(&self) -> impl Iterator<Item = (&Path, &IndexedProject)> {
+        self.projects
+            .iter()
+            .map(|(root, project)| (root.as_path(), project))
+    }
+
+    /// Projects that declare `tool` under any of its names.
+    pub fn dependents(&self, tool: &str) -> Vec<Dependent<'_>> {
+        let name = tool_names(tool)[0];
+        self.projects()
+            .filter_map(|(root, project)| {
+                let requirement = project.requirements.get(name)?;
+                Some(Dependent {
+                    root,
+                    project,
+                    requirement,
+                })
+            })
+            .collect()
+    }
+
+    /// Projects that accept some version in `replaced` and would no longer be satisfied
+    /// once it's gone: with a `replacement`, those that accept none of it either.
+    ///
+    /// Requirements follow the syntax of the manager their project names, if any.
+    /// Channels resolve through `channels`; requirements that don't resolve count as
+    /// broken, since nothing says they'd still be met.
+    pub fn broken_by(
+        &self,
+        tool: &str,
+        replaced: &VersionRange,
+        replacement: Option<&VersionRange>,
+        channels: &dyn ChannelRegistry,
+    ) -> Vec<Dependent<'_>> {
+        let name = tool_names(tool)[0];
+        self.dependents(tool)
+            .into_iter()
+            .filter(|dependent| {
+                if dependent.requirement.is_any() {
+                    return false;
+                }
+                let manager = dependent.project.managers.get(name).copied();
+                let ecosystem = Ecosystem::for_dependency(tool, manager);
+                match dependent.requirement.resolve(tool, ecosystem, channels) {
+                    Ok(range) => {
+                        range.intersects(replaced)
+                            && !replacement.is_some_and(|r| range.intersects(r))
+                    }
+                    Err(_) => true,
+                }
+            })
+            .collect()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.projects)?)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+}
+
+fn mtime(path: &Path) -> Option<u64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.duration_since(UNIX_EPOCH).ok()?.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dependency::ChannelIndex;
+    use env_manifest::{DependencyDetails, DependencySpec};
+    use semver::VersionReq;
+
+    fn project(name: &str, deps: &[(&str, &str)], dev: &[(&str, &str)]) -> EnhancedManifest {
+        let mut manifest = EnhancedManifest::default();
+        manifest.project.name = name.to_string();
+        for (tool, req) in deps {
+            manifest.dependencies.insert(
+                tool.to_string(),
+                DependencySpec::Simple(VersionConstraint::parse(req).unwrap()),
+            );
+        }
+        for (tool, req) in dev {
+            manifest.dev_dependencies.insert(
+                tool.to_string(),
+                DependencySpec::Simple(VersionConstraint::parse(req).unwrap()),
+            );
+        }
+        manifest
+    }
+
+    #[test]
+    fn test_project_index_impact() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("projects.json");
+        let manifest_path = |name: &str| {
+            let dir = temp.path().join(name);
+            std::fs::create_dir_all(&dir).unwrap();
+            let file = dir.join("env.json");
+            std::fs::write(&file, "{}").unwrap();
+            (dir, file)
+        };
+
+        let mut index = ProjectIndex::open(&path);
+        let (legacy, legacy_manifest) = manifest_path("legacy");
+        index.register(
+            &legacy,
+            &legacy_manifest,
+            &project("legacy", &[("nodejs", "^18")], &[("node", "^20")]),
+        );
+        let (modern, modern_manifest) = manifest_path("modern");
+        index.register(
+            &modern,
+            &modern_manifest,
+            &project("modern", &[("node", ">=18")], &[]),
+        );
+        let (web, web_manifest) = manifest_path("web");
+        index.register(&web, &web_manifest, &project("web", &[("node", "*")], &[]));
+        index.save().unwrap();
+
+        let index = ProjectIndex::open(&path);
+        assert!(index.is_current(&legacy, &legacy_manifest));
+        assert_eq!(index.dependents("node").len(), 3);
+
+        let node18 = VersionRange::from(VersionReq::parse("^18").unwrap());
+        let node20 = VersionRange::from(VersionReq::parse("^20").unwrap());
+        let channels = ChannelIndex::new();
+        let names = |dependents: Vec<Dependent<'_>>| {
+            dependents
+                .iter()
+                .map(|d| d.project.name.clone())
+                .collect::<Vec<_>>()
+        };
+        // Runtime `^18` wins over the dev `^20`; `*` never breaks.
+        assert_eq!(
+            names(index.broken_by("node", &node18, None, &channels)),
+            ["legacy", "modern"]
+        );
+        assert_eq!(
+            names(index.broken_by("node", &node18, Some(&node20), &channels)),
+            ["legacy"]
+        );
+
+        let mut index = index;
+        std::fs::remove_file(&web_manifest).unwrap();
+        assert_eq!(index.prune(), 1);
+    }
+
+    #[test]
+    fn test_impact_follows_dependency_manager() {
+        let temp = tempfile::tempdir().unwrap();
+        let mut index = ProjectIndex::open(temp.path().join("projects.json"));
+        let detailed = |manager| {
+            DependencySpec::Detailed(DependencyDetails {
+                version: VersionConstraint::parse("23.1").unwrap(),
+                manager,
+                source: None,
+                optional: false,
+            })
+        };
+        for (name, manager) in [("pinned", Some(PackageManager::Pip)), ("caret", None)] {
+            let mut manifest = project(name, &[], &[]);
+            manifest
+                .dependencies
+                .insert("black".to_string(), detailed(manager));
+            let root = temp.path().join(name);
+            index.register(&root, &root.join("env.json"), &manifest);
+        }
+        index.save().unwrap();
+
+        // Bare `23.1` is `^23.1` by default, but pip reads it as exactly 23.1.
+        let index = ProjectIndex::open(temp.path().join("projects.json"));
+        let black_23_5 = VersionRange::from(VersionReq::parse("=23.5.0").unwrap());
+        let broken = index.broken_by("black", &black_23_5, None, &ChannelIndex::new());
+        assert_eq!(
+            broken
+                .iter()
+                .map(|d| d.project.name.as_str())
+                .collect::<Vec<_>>(),
+            ["caret"]
+        );
+    }
+}
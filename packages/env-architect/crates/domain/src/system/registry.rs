@@ -1,7 +1,12 @@
+use super::cache::ScanCache;
+use super::discovery::{self, DiscoveryStrategy};
 use anyhow::{Context, Result};
+use env_manifest::ToolFamilyConfig;
 use semver::Version;
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::ffi::OsStr;
+use std::fmt;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 /// Which tool manager installed this tool
@@ -15,10 +20,107 @@ pub enum ToolManager {
     Pacman,
     Cargo,
     Npm,
+    Nvm,
+    Fnm,
+    Pyenv,
+    Rustup,
+    Asdf,
+    Mise,
     System,
     Unknown,
 }
 
+impl ToolManager {
+    /// Guess the manager from where a binary lives, for tools found on `PATH`.
+    pub fn from_location(location: &Path) -> Self {
+        let resolved = std::fs::canonicalize(location).unwrap_or_else(|_| location.to_path_buf());
+        let path = resolved.to_string_lossy();
+        let markers = [
+            ("/.nvm/", ToolManager::Nvm),
+            ("/fnm/", ToolManager::Fnm),
+            ("/.pyenv/", ToolManager::Pyenv),
+            ("/.rustup/", ToolManager::Rustup),
+            ("/.cargo/bin/", ToolManager::Rustup),
+            ("/.asdf/", ToolManager::Asdf),
+            ("/mise/", ToolManager::Mise),
+            ("/Cellar/", ToolManager::Homebrew),
+            ("/homebrew/", ToolManager::Homebrew),
+            ("/.architect/", ToolManager::EnvArchitect),
+        ];
+        if let Some((_, manager)) = markers.iter().find(|(marker, _)| path.contains(marker)) {
+            return manager.clone();
+        }
+        if path.starts_with("/usr/bin/") || path.starts_with("/bin/") {
+            ToolManager::System
+        } else {
+            ToolManager::Unknown
+        }
+    }
+
+    /// Whether it installs versions side by side (nvm, pyenv, rustup, ...).
+    pub fn is_version_manager(&self) -> bool {
+        matches!(
+            self,
+            ToolManager::EnvArchitect
+                | ToolManager::Nvm
+                | ToolManager::Fnm
+                | ToolManager::Pyenv
+                | ToolManager::Rustup
+                | ToolManager::Asdf
+                | ToolManager::Mise
+        )
+    }
+}
+
+impl ToolManager {
+    /// Whether it can install `tool`: nvm and fnm only Node, pyenv only Python, rustup
+    /// only Rust; asdf, mise and the system package managers anything.
+    pub fn supports(&self, tool: &str) -> bool {
+        let names = tool_names(tool);
+        let any = |candidates: &[&str]| names.iter().any(|n| candidates.contains(n));
+        match self {
+            ToolManager::Nvm | ToolManager::Fnm => any(&["node", "npm"]),
+            ToolManager::Pyenv => any(&["python"]),
+            ToolManager::Rustup => any(&["rust", "cargo"]),
+            ToolManager::EnvArchitect
+            | ToolManager::Asdf
+            | ToolManager::Mise
+            | ToolManager::Homebrew
+            | ToolManager::Apt
+            | ToolManager::Yum
+            | ToolManager::Dnf
+            | ToolManager::Pacman => true,
+            ToolManager::Cargo | ToolManager::Npm | ToolManager::System | ToolManager::Unknown => {
+                false
+            }
+        }
+    }
+}
+
+impl fmt::Display for ToolManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ToolManager::EnvArchitect => "architect",
+            ToolManager::Homebrew => "brew",
+            ToolManager::Apt => "apt",
+            ToolManager::Yum => "yum",
+            ToolManager::Dnf => "dnf",
+            ToolManager::Pacman => "pacman",
+            ToolManager::Cargo => "cargo",
+            ToolManager::Npm => "npm",
+            ToolManager::Nvm => "nvm",
+            ToolManager::Fnm => "fnm",
+            ToolManager::Pyenv => "pyenv",
+            ToolManager::Rustup => "rustup",
+            ToolManager::Asdf => "asdf",
+            ToolManager::Mise => "mise",
+            ToolManager::System => "system",
+            ToolManager::Unknown => "unknown",
+        };
+        f.write_str(name)
+    }
+}
+
 /// An installed version of a tool
 #[derive(Debug, Clone)]
 pub struct InstalledVersion {
@@ -53,6 +155,19 @@ impl ToolFamily {
         self
     }
 
+    /// A family declared in `[families]` (manifest or user config)
+    pub fn from_config(name: impl Into<String>, config: &ToolFamilyConfig) -> Self {
+        Self::new(name, config.members.clone()).with_preference(config.preference_order())
+    }
+
+    /// Position of a member in the preference order; members left out of it rank last
+    pub fn rank(&self, tool: &str) -> usize {
+        self.preference_order
+            .iter()
+            .position(|t| t == tool)
+            .unwrap_or(self.preference_order.len())
+    }
+
     /// Check if a tool belongs to this family
     pub fn contains(&self, tool: &str) -> bool {
         self.members.iter().any(|m| m == tool)
@@ -68,7 +183,8 @@ impl ToolFamily {
                 return Some(version);
             }
         }
-        None
+        // Members without a preference still beat nothing.
+        installed.iter().find(|v| self.contains(&v.tool))
     }
 }
 
@@ -76,6 +192,12 @@ impl ToolFamily {
 pub struct InstalledToolsRegistry {
     cache: HashMap<String, Vec<InstalledVersion>>,
     families: Vec<ToolFamily>,
+    strategies: Vec<Box<dyn DiscoveryStrategy>>,
+    scan_cache: Option<ScanCache>,
+    /// Managers usable on this machine, version managers first
+    managers: Vec<ToolManager>,
+    /// How many families at the front of `families` were registered by the user
+    custom_families: usize,
 }
 
 impl InstalledToolsRegistry {
@@ -83,6 +205,10 @@ impl InstalledToolsRegistry {
         let mut registry = Self {
             cache: HashMap::new(),
             families: Vec::new(),
+            strategies: discovery::default_strategies(),
+            scan_cache: None,
+            managers: Vec::new(),
+            custom_families: 0,
         };
 
         // Register known tool families
@@ -143,70 +269,162 @@ impl InstalledToolsRegistry {
         );
     }
 
-    /// Scan the system for installed tools (Strategy 1: PATH scanning)
-    pub fn scan(&mut self) -> Result<()> {
-        self.scan_path()?;
-        Ok(())
+    /// Register a user-defined family. One named like an existing family replaces it;
+    /// otherwise it is consulted before the built-in ones.
+    pub fn register_family(&mut self, family: ToolFamily) {
+        if let Some(existing) = self.families.iter_mut().find(|f| f.name == family.name) {
+            *existing = family;
+        } else {
+            self.families.insert(self.custom_families, family);
+            self.custom_families += 1;
+        }
     }
 
-    /// Strategy 1: Scan PATH for executables
-    fn scan_path(&mut self) -> Result<()> {
-        // Get list of common tools to check
-        let tools_to_check = self.get_common_tools();
+    /// Register `[families]` from a user config or manifest, in name order. Apply the user
+    /// config first so the project's families win.
+    pub fn with_families(mut self, families: &HashMap<String, ToolFamilyConfig>) -> Self {
+        let mut names: Vec<&String> = families.keys().collect();
+        names.sort();
+        for name in names {
+            self.register_family(ToolFamily::from_config(name.clone(), &families[name]));
+        }
+        self
+    }
 
-        for tool in tools_to_check {
-            if let Ok(version) = self.detect_via_path(&tool) {
+    /// Replace the version-manager discovery strategies (the defaults look in the
+    /// standard install locations).
+    pub fn with_strategies(mut self, strategies: Vec<Box<dyn DiscoveryStrategy>>) -> Self {
+        self.strategies = strategies;
+        self
+    }
+
+    /// Cache `--version` results on disk between scans.
+    pub fn with_cache(mut self, cache: ScanCache) -> Self {
+        self.scan_cache = Some(cache);
+        self
+    }
+
+    /// Scan the system for installed tools: every version manager's installs, then
+    /// whatever else is on PATH. Strategies and binaries are probed concurrently.
+    pub fn scan(&mut self) -> Result<()> {
+        let (managed, on_path) = std::thread::scope(|s| {
+            let managers = s.spawn(|| self.scan_managers());
+            let on_path = self.scan_path();
+            (managers.join().unwrap_or_default(), on_path)
+        });
+        self.managers = discovery::detect_managers(&std::env::var_os("PATH").unwrap_or_default());
+
+        for version in managed {
+            self.add_version(version);
+        }
+        let mut probed = Vec::new();
+        for (version, cached) in on_path {
+            if !cached {
+                probed.push(version.clone());
+            }
+            // A manager already listed this install, with a better location.
+            let known = self
+                .get_installed(&version.tool)
+                .iter()
+                .any(|v| v.version == version.version);
+            if !known {
                 self.add_version(version);
             }
         }
 
+        if let Some(cache) = &mut self.scan_cache {
+            for version in &probed {
+                cache.insert(&cache_key(&version.location), &version.version);
+            }
+            if !probed.is_empty() {
+                // A cache we can't write only costs speed.
+                let _ = cache.save();
+            }
+        }
         Ok(())
     }
 
+    /// Strategy 2: List every version each manager has installed
+    fn scan_managers(&self) -> Vec<InstalledVersion> {
+        std::thread::scope(|s| {
+            let handles: Vec<_> = self
+                .strategies
+                .iter()
+                .map(|strategy| s.spawn(move || strategy.discover()))
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap_or_default())
+                .collect()
+        })
+    }
+
+    /// Strategy 1: Scan PATH for executables, with whether each version came from the cache
+    fn scan_path(&self) -> Vec<(InstalledVersion, bool)> {
+        // Get list of common tools to check
+        let tools_to_check = self.get_common_tools();
+        let path = std::env::var_os("PATH").unwrap_or_default();
+
+        std::thread::scope(|s| {
+            let handles: Vec<_> = tools_to_check
+                .iter()
+                .map(|tool| {
+                    let path = &path;
+                    s.spawn(move || self.detect_via_path(tool, path).ok())
+                })
+                .collect();
+            handles
+                .into_iter()
+                .filter_map(|handle| handle.join().ok().flatten())
+                .collect()
+        })
+    }
+
     /// Get list of common tools from all families
     fn get_common_tools(&self) -> Vec<String> {
         let mut tools = Vec::new();
         for family in &self.families {
             tools.extend(family.members.clone());
         }
+        tools.sort();
         tools.dedup();
         tools
     }
 
     /// Detect a tool via PATH
-    fn detect_via_path(&self, tool: &str) -> Result<InstalledVersion> {
-        // Try to find tool in PATH
-        let which_output = Command::new("which")
-            .arg(tool)
-            .output()
-            .context("Failed to run 'which' command")?;
-
-        if !which_output.status.success() {
-            anyhow::bail!("Tool '{}' not found in PATH", tool);
-        }
-
-        let location = String::from_utf8(which_output.stdout)?.trim().to_string();
-
-        // Try to get version
-        let version = self.get_tool_version(tool)?;
+    fn detect_via_path(&self, tool: &str, path: &OsStr) -> Result<(InstalledVersion, bool)> {
+        let location = find_executable(tool, path)
+            .with_context(|| format!("Tool '{}' not found in PATH", tool))?;
+
+        let cached = self
+            .scan_cache
+            .as_ref()
+            .and_then(|cache| cache.get(&cache_key(&location)));
+        let (version, from_cache) = match cached {
+            Some(version) => (version, true),
+            None => (self.get_tool_version(&location)?, false),
+        };
 
-        Ok(InstalledVersion {
-            tool: tool.to_string(),
-            version,
-            location: PathBuf::from(location),
-            managed_by: ToolManager::Unknown, // We don't know yet
-        })
+        Ok((
+            InstalledVersion {
+                tool: tool.to_string(),
+                version,
+                managed_by: ToolManager::from_location(&location),
+                location,
+            },
+            from_cache,
+        ))
     }
 
     /// Get version of a tool by running it with --version
-    fn get_tool_version(&self, tool: &str) -> Result<Version> {
-        let version_output = Command::new(tool)
+    fn get_tool_version(&self, binary: &Path) -> Result<Version> {
+        let version_output = Command::new(binary)
             .arg("--version")
             .output()
-            .context(format!("Failed to get version for '{}'", tool))?;
+            .context(format!("Failed to get version for '{}'", binary.display()))?;
 
         if !version_output.status.success() {
-            anyhow::bail!("Failed to get version for '{}'", tool);
+            anyhow::bail!("Failed to get version for '{}'", binary.display());
         }
 
         let output = String::from_utf8(version_output.stdout)?;
@@ -252,6 +470,15 @@ impl InstalledToolsRegistry {
         self.cache.get(tool).cloned().unwrap_or_default()
     }
 
+    /// All installed versions of a tool under any of its names (`node`/`nodejs`,
+    /// `python`/`python3`, `rust`/`rustc`, `go`/`golang`).
+    pub fn find_installed(&self, tool: &str) -> Vec<InstalledVersion> {
+        tool_names(tool)
+            .into_iter()
+            .flat_map(|name| self.get_installed(name))
+            .collect()
+    }
+
     /// Get tool family by name
     pub fn get_family(&self, family_name: &str) -> Option<&ToolFamily> {
         self.families.iter().find(|f| f.name == family_name)
@@ -280,39 +507,115 @@ impl InstalledToolsRegistry {
         }
     }
 
-    /// Get recommendations for alternatives if a tool is not installed
+    /// Get recommendations for alternatives if a tool is not installed, most preferred first
     pub fn get_recommendations(&self, tool: &str) -> Vec<String> {
         // Find which family this tool belongs to
         for family in &self.families {
             if family.contains(tool) {
                 // Get all installed alternatives
-                let installed = self.get_family_installed(&family.name);
+                let mut installed: Vec<String> = self
+                    .get_family_installed(&family.name)
+                    .into_iter()
+                    .map(|v| v.tool)
+                    .collect();
+                installed.dedup();
                 if !installed.is_empty() {
-                    return installed.iter().map(|v| v.tool.clone()).collect();
+                    installed.sort_by_key(|t| family.rank(t));
+                    return installed;
                 }
 
                 // If nothing installed, suggest other family members
-                return family
+                let mut members: Vec<String> = family
                     .members
                     .iter()
                     .filter(|m| *m != tool)
                     .cloned()
                     .collect();
+                members.sort_by_key(|t| family.rank(t));
+                return members;
             }
         }
 
         Vec::new()
     }
 
-    /// Add a manually detected version
+    /// Package and version managers found by the last scan, version managers first
+    pub fn available_managers(&self) -> &[ToolManager] {
+        &self.managers
+    }
+
+    /// Add a manually detected manager
+    pub fn add_manager(&mut self, manager: ToolManager) {
+        if !self.managers.contains(&manager) {
+            self.managers.push(manager);
+        }
+    }
+
+    /// Add a manually detected version; the same version at the same location is kept once
     pub fn add_version(&mut self, version: InstalledVersion) {
-        self.cache
-            .entry(version.tool.clone())
-            .or_default()
-            .push(version);
+        let versions = self.cache.entry(version.tool.clone()).or_default();
+        if !versions
+            .iter()
+            .any(|v| v.version == version.version && v.location == version.location)
+        {
+            versions.push(version);
+        }
     }
 }
 
+/// Every name a tool goes by: `node`/`nodejs`, `python`/`python3`, `rust`/`rustc`,
+/// `go`/`golang`, or just `tool`.
+pub(crate) fn tool_names(tool: &str) -> Vec<&str> {
+    const ALIASES: &[&[&str]] = &[
+        &["node", "nodejs"],
+        &["python", "python3"],
+        &["rust", "rustc"],
+        &["go", "golang"],
+    ];
+    ALIASES
+        .iter()
+        .find(|names| names.contains(&tool))
+        .map_or_else(|| vec![tool], |names| names.to_vec())
+}
+
+/// Find `tool` in the directories of a `PATH`-style list, like `which` but in-process.
+pub fn find_executable(tool: &str, path: &OsStr) -> Option<PathBuf> {
+    let names: Vec<String> = if cfg!(windows) {
+        vec![
+            format!("{}.exe", tool),
+            format!("{}.cmd", tool),
+            tool.to_string(),
+        ]
+    } else {
+        vec![tool.to_string()]
+    };
+
+    std::env::split_paths(path)
+        .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+        .find(|candidate| is_executable(candidate))
+}
+
+fn is_executable(path: &Path) -> bool {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return false;
+    };
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        metadata.is_file()
+    }
+}
+
+/// Cache entries follow symlinks, so switching `/usr/bin/node` to another target
+/// is a different binary.
+fn cache_key(location: &Path) -> PathBuf {
+    std::fs::canonicalize(location).unwrap_or_else(|_| location.to_path_buf())
+}
+
 impl Default for InstalledToolsRegistry {
     fn default() -> Self {
         Self::new()
@@ -339,6 +642,27 @@ mod tests {
         assert_eq!(js_family.preference_order[1], "pnpm");
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_find_executable() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp = tempfile::tempdir().unwrap();
+        let (first, second) = (temp.path().join("a"), temp.path().join("b"));
+        std::fs::create_dir_all(&first).unwrap();
+        std::fs::create_dir_all(&second).unwrap();
+
+        // Not executable in the first directory, executable in the second.
+        std::fs::write(first.join("node"), "").unwrap();
+        std::fs::write(second.join("node"), "").unwrap();
+        std::fs::set_permissions(second.join("node"), std::fs::Permissions::from_mode(0o755))
+            .unwrap();
+
+        let path = std::env::join_paths([&first, &second]).unwrap();
+        assert_eq!(find_executable("node", &path), Some(second.join("node")));
+        assert_eq!(find_executable("python", &path), None);
+    }
+
     #[test]
     fn test_version_parsing() {
         let registry = InstalledToolsRegistry::new();
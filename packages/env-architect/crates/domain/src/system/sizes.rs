@@ -0,0 +1,57 @@
+use super::registry::tool_names;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Tool sizes learned on this machine, per platform, so estimates the registry index
+/// doesn't cover still work offline.
+pub struct SizeCache {
+    path: PathBuf,
+    /// Bytes, by platform key (`linux-x86_64`) then tool.
+    sizes: HashMap<String, HashMap<String, u64>>,
+}
+
+impl SizeCache {
+    /// `~/.architect/cache/tool-sizes.json`
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::home_dir().map(|home| {
+            home.join(".architect")
+                .join("cache")
+                .join("tool-sizes.json")
+        })
+    }
+
+    /// Load the cache at `path`; a missing or unreadable file starts empty.
+    pub fn open(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let sizes = std::fs::read_to_string(&path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { path, sizes }
+    }
+
+    /// Size of `tool` on `platform` under any of its names.
+    pub fn get(&self, platform: &str, tool: &str) -> Option<u64> {
+        let sizes = self.sizes.get(platform)?;
+        tool_names(tool)
+            .into_iter()
+            .find_map(|name| sizes.get(name))
+            .copied()
+    }
+
+    pub fn insert(&mut self, platform: &str, tool: &str, bytes: u64) {
+        self.sizes
+            .entry(platform.to_string())
+            .or_default()
+            .insert(tool_names(tool)[0].to_string(), bytes);
+    }
+
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, serde_json::to_string_pretty(&self.sizes)?)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+}
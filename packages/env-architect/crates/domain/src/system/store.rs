@@ -1,6 +1,7 @@
 use anyhow::{Context, Result};
 use std::fs;
-use std::path::PathBuf;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 
 /// Manages the immutable Architect Store
 pub struct StoreManager {
@@ -49,6 +50,94 @@ impl StoreManager {
         Ok(path)
     }
 
+    /// Put a verified artifact into the store: a `.tar.gz` is unpacked, anything else is
+    /// copied to `bin/<binary_name>`. Either way `binary_name` must end up a native
+    /// executable or a script; a store entry that fails is removed again.
+    /// Returns the path of the executable.
+    pub fn ingest(
+        &self,
+        tool: &str,
+        version: &str,
+        content_hash: &str,
+        artifact: &Path,
+        binary_name: &str,
+    ) -> Result<PathBuf> {
+        let dir = self.ensure_dir(tool, version, content_hash)?;
+        let result = Self::ingest_into(&dir, artifact, binary_name).and_then(|()| {
+            let executable = self
+                .get_executable_path(tool, version, content_hash, binary_name)
+                .with_context(|| {
+                    format!("{} has no '{}' executable", artifact.display(), binary_name)
+                })?;
+            check_executable(&executable)?;
+            Ok(executable)
+        });
+        let executable = match result {
+            Ok(executable) => executable,
+            Err(e) => {
+                let _ = fs::remove_dir_all(&dir);
+                return Err(e);
+            }
+        };
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&executable, fs::Permissions::from_mode(0o755))?;
+        }
+        Ok(executable)
+    }
+
+    fn ingest_into(dir: &Path, artifact: &Path, binary_name: &str) -> Result<()> {
+        let mut magic = [0u8; 2];
+        fs::File::open(artifact)
+            .and_then(|mut file| file.read(&mut magic))
+            .with_context(|| format!("Failed to read {}", artifact.display()))?;
+        if magic == GZIP_MAGIC {
+            let archive = flate2::read::GzDecoder::new(fs::File::open(artifact)?);
+            // `unpack` keeps entries inside `dir`, skipping any that climb out of it.
+            tar::Archive::new(archive)
+                .unpack(dir)
+                .with_context(|| format!("Failed to unpack {}", artifact.display()))?;
+            return Ok(());
+        }
+
+        let bin_dir = dir.join("bin");
+        fs::create_dir_all(&bin_dir)?;
+        fs::copy(artifact, bin_dir.join(binary_name))
+            .with_context(|| format!("Failed to copy {} into the store", artifact.display()))?;
+        Ok(())
+    }
+
+    /// The executable of the newest stored version of `tool` that `accept` allows.
+    pub fn find_executable(
+        &self,
+        tool: &str,
+        binary_name: &str,
+        accept: impl Fn(&semver::Version) -> bool,
+    ) -> Option<PathBuf> {
+        let prefix = format!("{}-", tool);
+        let mut found: Vec<(semver::Version, PathBuf)> = fs::read_dir(&self.root)
+            .ok()?
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().into_string().ok()?;
+                // <hash>-<tool>-<version>
+                let (hash, rest) = name.split_once('-')?;
+                let version = rest.strip_prefix(&prefix)?;
+                let version = semver::Version::parse(version).ok()?;
+                if !accept(&version) {
+                    return None;
+                }
+                let executable =
+                    self.get_executable_path(tool, &version.to_string(), hash, binary_name)?;
+                Some((version, executable))
+            })
+            .collect();
+        found.sort_by(|a, b| a.0.cmp(&b.0));
+        found.pop().map(|(_, executable)| executable)
+    }
+
     /// List all tools in the store
     pub fn list_tools(&self) -> Result<Vec<String>> {
         let mut tools = Vec::new();
@@ -91,8 +180,48 @@ impl StoreManager {
             return Some(direct_path);
         }
 
-        None
+        // Archives usually wrap everything in one top-level directory (`node-v20.11.0/`).
+        fs::read_dir(&root)
+            .ok()?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .flat_map(|path| [path.join("bin").join(binary_name), path.join(binary_name)])
+            .find(|path| path.is_file())
+    }
+}
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Refuse anything that isn't a native executable (ELF, Mach-O, PE) or a `#!` script,
+/// such as a WebAssembly module or an archive format we don't unpack.
+fn check_executable(path: &Path) -> Result<()> {
+    let mut magic = [0u8; 4];
+    let read = fs::File::open(path)
+        .and_then(|mut file| file.read(&mut magic))
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let magic = &magic[..read];
+
+    let native = [
+        &b"\x7fELF"[..],
+        &[0xfe, 0xed, 0xfa, 0xce],
+        &[0xfe, 0xed, 0xfa, 0xcf],
+        &[0xce, 0xfa, 0xed, 0xfe],
+        &[0xcf, 0xfa, 0xed, 0xfe],
+        &[0xca, 0xfe, 0xba, 0xbe],
+        b"MZ",
+        b"#!",
+    ];
+    if native.iter().any(|prefix| magic.starts_with(prefix)) {
+        return Ok(());
+    }
+    if magic.starts_with(b"\0asm") {
+        anyhow::bail!(
+            "{} is a WebAssembly module, not an executable",
+            path.display()
+        );
     }
+    anyhow::bail!("{} is not an executable", path.display())
 }
 
 #[cfg(test)]
@@ -107,4 +236,98 @@ mod tests {
 
         assert_eq!(path, root.join("abc123456789-node-20.11.0"));
     }
+
+    #[test]
+    fn test_ingest_and_find_executable() {
+        let temp = tempfile::tempdir().unwrap();
+        let manager = StoreManager::new(temp.path().join("store"));
+        let artifact = temp.path().join("node.download");
+        fs::write(&artifact, "#!/bin/sh\n").unwrap();
+
+        assert_eq!(manager.find_executable("node", "node", |_| true), None);
+        let oldest = manager
+            .ingest("node", "18.19.0", "aaaa1111bbbb2222", &artifact, "node")
+            .unwrap();
+        let newest = manager
+            .ingest("node", "20.11.0", "cccc3333dddd4444", &artifact, "node")
+            .unwrap();
+        manager
+            .ingest("node-gyp", "99.0.0", "eeee5555ffff6666", &artifact, "node")
+            .unwrap();
+
+        assert_eq!(
+            manager.find_executable("node", "node", |_| true),
+            Some(newest)
+        );
+        assert_eq!(
+            manager.find_executable("node", "node", |v| v.major == 18),
+            Some(oldest)
+        );
+    }
+
+    fn tar_gz(files: &[(&str, &[u8])]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::default(),
+        ));
+        for (path, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, path, *content).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    #[test]
+    fn test_ingest_unpacks_archives() {
+        let temp = tempfile::tempdir().unwrap();
+        let manager = StoreManager::new(temp.path().join("store"));
+        let artifact = temp.path().join("node.tar.gz");
+        fs::write(
+            &artifact,
+            tar_gz(&[
+                ("node-v20.11.0/bin/node", b"\x7fELF\x02\x01"),
+                ("node-v20.11.0/README.md", b"# Node"),
+            ]),
+        )
+        .unwrap();
+
+        let executable = manager
+            .ingest("node", "20.11.0", "aaaa1111bbbb2222", &artifact, "node")
+            .unwrap();
+        assert!(executable.ends_with("node-v20.11.0/bin/node"));
+        assert_eq!(fs::read(&executable).unwrap(), b"\x7fELF\x02\x01");
+        assert_eq!(
+            manager.find_executable("node", "node", |_| true),
+            Some(executable)
+        );
+    }
+
+    #[test]
+    fn test_ingest_refuses_non_executables() {
+        let temp = tempfile::tempdir().unwrap();
+        let manager = StoreManager::new(temp.path().join("store"));
+        let wasm = temp.path().join("node.wasm");
+        fs::write(&wasm, b"\0asm\x01\0\0\0").unwrap();
+        let err = manager
+            .ingest("node", "20.11.0", "aaaa1111bbbb2222", &wasm, "node")
+            .unwrap_err();
+        assert!(err.to_string().contains("WebAssembly"));
+
+        // An archive without the binary, and one whose binary isn't executable.
+        let archive = temp.path().join("node.tar.gz");
+        fs::write(&archive, tar_gz(&[("docs/node.1", b"manual")])).unwrap();
+        assert!(manager
+            .ingest("node", "20.11.0", "cccc3333dddd4444", &archive, "node")
+            .is_err());
+        fs::write(&archive, tar_gz(&[("bin/node", b"\0asm\x01\0\0\0")])).unwrap();
+        assert!(manager
+            .ingest("node", "20.11.0", "eeee5555ffff6666", &archive, "node")
+            .is_err());
+
+        // Failed ingests leave nothing behind.
+        assert_eq!(manager.list_tools().unwrap(), Vec::<String>::new());
+    }
 }
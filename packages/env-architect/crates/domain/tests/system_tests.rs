@@ -75,3 +75,88 @@ fn test_python_family() {
     assert!(recommendations.contains(&"python3".to_string()));
     println!("✅ Recommendations for 'python': {:?}", recommendations);
 }
+
+#[test]
+fn test_cached_rescan_matches() {
+    use domain::system::{ScanCache, DEFAULT_SCAN_TTL};
+
+    let temp = tempfile::tempdir().unwrap();
+    let cache_path = temp.path().join("tool-scan.json");
+    let scan = || {
+        let mut registry = InstalledToolsRegistry::new()
+            .with_strategies(Vec::new())
+            .with_cache(ScanCache::open(&cache_path, DEFAULT_SCAN_TTL));
+        registry.scan().unwrap();
+        ["git", "make", "cargo", "rustc", "python3", "node"]
+            .iter()
+            .flat_map(|tool| registry.get_installed(tool))
+            .map(|v| (v.tool, v.version, v.location))
+            .collect::<Vec<_>>()
+    };
+
+    let first = scan();
+    if !first.is_empty() {
+        assert!(cache_path.exists());
+    }
+    // The second scan is served from the cache and sees the same tools.
+    assert_eq!(scan(), first);
+}
+
+#[test]
+fn test_user_defined_families() {
+    use env_manifest::UserConfig;
+
+    let user: UserConfig = toml::from_str(
+        r#"
+[families.containers]
+members = ["docker", "podman"]
+prefer = ["podman"]
+
+[families.js-package-manager]
+members = ["npm", "yarn", "pnpm", "bun"]
+prefer = ["npm"]
+"#,
+    )
+    .unwrap();
+    let project: UserConfig = toml::from_str(
+        r#"
+[families.containers]
+members = ["docker", "podman", "nerdctl"]
+prefer = ["nerdctl", "docker"]
+"#,
+    )
+    .unwrap();
+
+    let mut registry = InstalledToolsRegistry::new()
+        .with_families(&user.families)
+        .with_families(&project.families);
+    for (tool, manager) in [
+        ("docker", ToolManager::Homebrew),
+        ("podman", ToolManager::Homebrew),
+        ("npm", ToolManager::System),
+        ("bun", ToolManager::Homebrew),
+    ] {
+        registry.add_version(InstalledVersion {
+            tool: tool.to_string(),
+            version: Version::new(1, 0, 0),
+            location: PathBuf::from("/usr/local/bin").join(tool),
+            managed_by: manager,
+        });
+    }
+
+    // The project's order wins over the user's; nerdctl isn't installed, so docker.
+    let preferred = registry.get_family_preferred("containers").unwrap();
+    assert_eq!(preferred.tool, "docker");
+    assert_eq!(
+        registry.get_recommendations("nerdctl"),
+        vec!["docker".to_string(), "podman".to_string()]
+    );
+
+    // A family named like a built-in one replaces it: npm now beats bun.
+    let preferred = registry.get_family_preferred("js-package-manager").unwrap();
+    assert_eq!(preferred.tool, "npm");
+    assert_eq!(
+        registry.get_recommendations("yarn"),
+        vec!["npm".to_string(), "bun".to_string()]
+    );
+}
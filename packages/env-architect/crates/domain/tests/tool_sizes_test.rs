@@ -0,0 +1,93 @@
+use domain::dependency::ReleaseIndex;
+use domain::intelligence::MetricsDetector;
+use domain::system::{Architecture, OsType, PlatformInfo, SizeCache};
+use semver::Version;
+
+const MB: u64 = 1024 * 1024;
+
+fn platform(os_type: OsType, arch: Architecture) -> PlatformInfo {
+    PlatformInfo {
+        os_type,
+        os_version: "14.2.1".to_string(),
+        arch,
+        distro: None,
+        kernel_version: None,
+        libc: None,
+    }
+}
+
+fn v(s: &str) -> Version {
+    Version::parse(s).unwrap()
+}
+
+fn index() -> ReleaseIndex {
+    ReleaseIndex::from_targets(
+        br#"{
+            "targets": {
+                "node-18.19.0.wasm": {
+                    "length": 41943040,
+                    "hashes": {},
+                    "custom": { "sizes": { "linux-x86_64": 73400320 } }
+                },
+                "node-20.11.0.wasm": {
+                    "length": 44040192,
+                    "hashes": {},
+                    "custom": { "sizes": { "linux-x86_64": 90177536, "macos-aarch64": 85983232 } }
+                },
+                "typescript-5.3.3.tgz": { "length": 23068672, "hashes": {} }
+            }
+        }"#,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_sizes_from_registry_index() {
+    let linux =
+        MetricsDetector::new(platform(OsType::Linux, Architecture::X86_64)).with_index(index());
+    assert_eq!(
+        linux.get_tool_size("nodejs", &v("20.11.0"), None).unwrap(),
+        86
+    );
+    // Sized by the release asked for, not the newest one.
+    assert_eq!(
+        linux.get_tool_size("node", &v("18.19.0"), None).unwrap(),
+        70
+    );
+    // Unpublished versions fall back to the newest release.
+    assert_eq!(linux.get_tool_size("node", &v("21.0.0"), None).unwrap(), 86);
+
+    let mac =
+        MetricsDetector::new(platform(OsType::MacOS, Architecture::Aarch64)).with_index(index());
+    assert_eq!(mac.get_tool_size("node", &v("20.11.0"), None).unwrap(), 82);
+
+    // No per-platform entry: the artifact's own size.
+    assert_eq!(
+        mac.get_tool_size("typescript", &v("5.3.3"), None).unwrap(),
+        22
+    );
+}
+
+#[test]
+fn test_sizes_offline_from_cache() {
+    let temp = tempfile::tempdir().unwrap();
+    let path = temp.path().join("tool-sizes.json");
+    let mut cache = SizeCache::open(&path);
+    cache.insert("linux-x86_64", "deno", 120 * MB);
+    cache.save().unwrap();
+
+    let detector = MetricsDetector::new(platform(OsType::Linux, Architecture::X86_64))
+        .with_cache(SizeCache::open(&path));
+    assert_eq!(
+        detector.get_tool_size("deno", &v("2.0.0"), None).unwrap(),
+        120
+    );
+
+    // Other platforms don't share sizes; without lookups this stays offline.
+    let detector = MetricsDetector::new(platform(OsType::MacOS, Architecture::Aarch64))
+        .with_cache(SizeCache::open(&path));
+    assert_eq!(
+        detector.get_tool_size("deno", &v("2.0.0"), None).unwrap(),
+        100
+    );
+}
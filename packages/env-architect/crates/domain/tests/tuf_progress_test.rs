@@ -0,0 +1,118 @@
+//! Targets fetched through TUF report their progress against the signed length.
+//!
+//! `fixtures/tuf` holds tough's own test root and its key (`snakeoil.pem`).
+
+use chrono::{Duration, Utc};
+use domain::security::tuf::{consistent_snapshot, target_file_name, RepositoryVerifier};
+use std::num::NonZeroU64;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tough::editor::RepositoryEditor;
+use tough::key_source::{KeySource, LocalKeySource};
+use url::Url;
+
+fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/tuf")
+        .join(name)
+}
+
+/// Sign a repository publishing `target` into `dir/metadata`.
+async fn publish(dir: &Path, target: &Path) {
+    let expires = Utc::now() + Duration::days(7);
+    let version = NonZeroU64::new(1).unwrap();
+    let mut editor = RepositoryEditor::new(fixture("root.json")).await.unwrap();
+    editor
+        .targets_expires(expires)
+        .unwrap()
+        .targets_version(version)
+        .unwrap()
+        .snapshot_expires(expires)
+        .snapshot_version(version)
+        .timestamp_expires(expires)
+        .timestamp_version(version)
+        .add_target_paths(vec![target])
+        .await
+        .unwrap();
+    let keys: Vec<Box<dyn KeySource>> = vec![Box::new(LocalKeySource {
+        path: fixture("snakeoil.pem"),
+    })];
+    let signed = editor.sign(&keys).await.unwrap();
+    signed.write(dir.join("metadata")).await.unwrap();
+}
+
+/// Serve `body` to every request, in a few separate writes so it arrives in chunks.
+async fn serve(body: Vec<u8>) -> Url {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = Url::parse(&format!(
+        "http://{}/targets/",
+        listener.local_addr().unwrap()
+    ))
+    .unwrap();
+    tokio::spawn(async move {
+        while let Ok((mut socket, _)) = listener.accept().await {
+            let mut request = [0u8; 1024];
+            let _ = socket.read(&mut request).await;
+            let head = format!(
+                "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+                body.len()
+            );
+            let _ = socket.write_all(head.as_bytes()).await;
+            for chunk in body.chunks(body.len() / 4) {
+                let _ = socket.write_all(chunk).await;
+                let _ = socket.flush().await;
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        }
+    });
+    url
+}
+
+#[tokio::test]
+async fn test_target_download_progress() {
+    let temp = tempfile::tempdir().unwrap();
+    let body: Vec<u8> = (0..256 * 1024).map(|i| (i % 251) as u8).collect();
+    let target = temp.path().join("plugin.wasm");
+    std::fs::write(&target, &body).unwrap();
+    publish(temp.path(), &target).await;
+
+    let metadata = Url::from_directory_path(temp.path().join("metadata")).unwrap();
+    let verifier = RepositoryVerifier::new(
+        &fixture("root.json"),
+        metadata,
+        serve(body.clone()).await,
+        &temp.path().join("cache"),
+    );
+
+    let mut reports = Vec::new();
+    let path = verifier
+        .verify_and_download_with_progress("plugin.wasm", |received, total| {
+            reports.push((received, total))
+        })
+        .await
+        .unwrap();
+    assert_eq!(std::fs::read(path).unwrap(), body);
+
+    // Every report carries the signed length, and bytes only ever go up, to all of it.
+    let length = body.len() as u64;
+    assert!(reports.len() > 1, "{:?}", reports);
+    assert!(reports.iter().all(|(_, total)| *total == Some(length)));
+    assert!(reports.windows(2).all(|pair| pair[0].0 <= pair[1].0));
+    assert_eq!(reports.last().unwrap().0, length);
+}
+
+#[test]
+fn test_consistent_snapshot_names() {
+    // Auto-shim URLs are built from the trusted root the same way downloads are.
+    assert!(consistent_snapshot(&fixture("root.json")));
+    assert!(!consistent_snapshot(&fixture("missing.json")));
+    assert_eq!(
+        target_file_name("plugin.wasm", "ab12", true),
+        "ab12.plugin.wasm"
+    );
+    assert_eq!(
+        target_file_name("plugin.wasm", "ab12", false),
+        "plugin.wasm"
+    );
+}
@@ -1,13 +1,83 @@
+use env_manifest::{ManifestFormat, ManifestSource, SourceSpan, ValidationLevel};
 use tower_lsp::jsonrpc::Result;
 use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer, LspService, Server};
 
 #[derive(Debug)]
 struct Backend {
-    #[allow(dead_code)]
     client: Client,
 }
 
+impl Backend {
+    /// Parse and validate the document, publishing the issues at their source spans.
+    async fn check(&self, uri: Url, text: String, version: Option<i32>) {
+        let Some(format) = uri
+            .to_file_path()
+            .ok()
+            .and_then(|p| ManifestFormat::from_path(&p))
+        else {
+            return;
+        };
+
+        let source = ManifestSource::new(text, format);
+        let diagnostics = match source.validate() {
+            Ok((_, result)) => result
+                .issues
+                .iter()
+                .map(|issue| Diagnostic {
+                    range: to_range(issue.location.as_ref()),
+                    severity: Some(match issue.level {
+                        ValidationLevel::Error => DiagnosticSeverity::ERROR,
+                        ValidationLevel::Warning => DiagnosticSeverity::WARNING,
+                        ValidationLevel::Info => DiagnosticSeverity::INFORMATION,
+                    }),
+                    code: issue.rule.clone().map(NumberOrString::String),
+                    source: Some("env-architect".to_string()),
+                    message: format!("{} ({})", issue.message, issue.field),
+                    ..Default::default()
+                })
+                .collect(),
+            Err(e) => vec![Diagnostic {
+                range: to_range(e.span.as_ref()),
+                severity: Some(DiagnosticSeverity::ERROR),
+                source: Some("env-architect".to_string()),
+                message: e.message,
+                ..Default::default()
+            }],
+        };
+
+        self.client
+            .publish_diagnostics(uri, diagnostics, version)
+            .await;
+    }
+}
+
+/// LSP positions are 0-based; spans are 1-based.
+fn to_range(span: Option<&SourceSpan>) -> Range {
+    let Some(span) = span else {
+        return Range::default();
+    };
+
+    let line = span.line.saturating_sub(1) as u32;
+    let start = span.column.saturating_sub(1) as u32;
+    let start_byte = span
+        .snippet
+        .char_indices()
+        .nth(start as usize)
+        .map_or(span.snippet.len(), |(i, _)| i);
+    let end_byte = (start_byte + span.length).min(span.snippet.len());
+    let width = span
+        .snippet
+        .char_indices()
+        .filter(|(i, _)| *i >= start_byte && *i < end_byte)
+        .count() as u32;
+
+    Range::new(
+        Position::new(line, start),
+        Position::new(line, start + width),
+    )
+}
+
 #[tower_lsp::async_trait]
 impl LanguageServer for Backend {
     async fn initialize(&self, _: InitializeParams) -> Result<InitializeResult> {
@@ -22,6 +92,19 @@ impl LanguageServer for Backend {
         })
     }
 
+    async fn did_open(&self, params: DidOpenTextDocumentParams) {
+        let doc = params.text_document;
+        self.check(doc.uri, doc.text, Some(doc.version)).await;
+    }
+
+    async fn did_change(&self, params: DidChangeTextDocumentParams) {
+        // Full sync: the last change holds the whole document.
+        if let Some(change) = params.content_changes.into_iter().last() {
+            let doc = params.text_document;
+            self.check(doc.uri, change.text, Some(doc.version)).await;
+        }
+    }
+
     async fn shutdown(&self) -> Result<()> {
         Ok(())
     }
@@ -1,7 +1,35 @@
-use env_manifest::EnhancedManifest;
+use env_manifest::{EnhancedManifest, CURRENT_SCHEMA_VERSION};
 use schemars::schema_for;
+use serde::Serialize;
+use std::path::PathBuf;
 
-fn main() {
+/// Print the schema, or with an output directory write `schema.json` (latest) and
+/// `schema/v{N}.json` (pinned to the current schema version).
+fn main() -> anyhow::Result<()> {
     let schema = schema_for!(EnhancedManifest);
-    println!("{}", serde_json::to_string_pretty(&schema).unwrap());
+
+    let mut buf = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(b"\t");
+    schema.serialize(&mut serde_json::Serializer::with_formatter(
+        &mut buf, formatter,
+    ))?;
+    buf.push(b'\n');
+
+    let Some(dir) = std::env::args().nth(1).map(PathBuf::from) else {
+        print!("{}", String::from_utf8(buf)?);
+        return Ok(());
+    };
+
+    let versioned = dir
+        .join("schema")
+        .join(format!("v{}.json", CURRENT_SCHEMA_VERSION));
+    std::fs::create_dir_all(versioned.parent().unwrap())?;
+    std::fs::write(&versioned, &buf)?;
+    std::fs::write(dir.join("schema.json"), &buf)?;
+    println!(
+        "Wrote {} and {}",
+        dir.join("schema.json").display(),
+        versioned.display()
+    );
+    Ok(())
 }
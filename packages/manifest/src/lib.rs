@@ -10,6 +10,19 @@ use std::collections::HashMap;
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(deny_unknown_fields)]
 pub struct EnhancedManifest {
+    /// JSON schema reference for editors (ignored by the tool)
+    #[serde(rename = "$schema", default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+
+    /// Manifest format version; older documents are migrated on load, and documents without
+    /// it are taken to be current
+    #[serde(
+        rename = "schema-version",
+        default = "current_schema_version",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub schema_version: Option<u32>,
+
     /// Project metadata (name, version, etc.)
     #[serde(default, alias = "plugin")]
     pub project: ProjectMetadata,
@@ -50,7 +63,8 @@ pub struct EnhancedManifest {
     #[schemars(schema_with = "dependency_map_schema")]
     pub build_dependencies: HashMap<String, DependencySpec>,
 
-    /// Platform-specific dependencies (à la Cargo)
+    /// Platform-specific dependencies (à la Cargo). When several tables match, triple
+    /// keys apply before `cfg(...)` keys, each in key order, and later tables win.
     #[serde(default)]
     pub target: HashMap<String, TargetDependencies>,
 
@@ -68,7 +82,12 @@ pub struct EnhancedManifest {
 
     /// Environment variables
     #[serde(default)]
-    pub env: HashMap<String, String>,
+    pub env: HashMap<String, EnvValue>,
+
+    /// `.env` files loaded beneath `env` (later files win; missing files are skipped).
+    /// Precedence, lowest first: process env, these files, `env`, profile env, service env.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env_files: Vec<String>,
 
     /// Named scripts
     #[serde(default)]
@@ -93,6 +112,22 @@ pub struct EnhancedManifest {
     #[serde(default)]
     pub conflicts: HashMap<String, String>,
 
+    /// Interchangeable tools and their preference order (e.g. `docker` vs `podman`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub families: HashMap<String, ToolFamilyConfig>,
+
+    /// How conflict resolutions are ranked (`preset = "ci"`, per-objective weights)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scoring: Option<ScoringConfig>,
+
+    /// How `resolve` settles conflicts without prompting (CI, `--non-interactive`)
+    #[serde(
+        rename = "resolution-policy",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub resolution_policy: Option<ResolutionPolicy>,
+
     /// Security capability requests
     #[serde(default, deserialize_with = "types::deserialize_capability_list")]
     pub capabilities: Option<Vec<Capability>>,
@@ -101,6 +136,10 @@ pub struct EnhancedManifest {
     #[serde(default)]
     pub assets: Vec<Asset>,
 
+    /// Validation rules suppressed for this project
+    #[serde(default)]
+    pub lints: Option<LintConfig>,
+
     /// Intelligent environment resolution and conflict data
     #[serde(default)]
     pub intelligence: Option<IntelligenceData>,
@@ -114,6 +153,10 @@ pub struct IntelligenceData {
     pub proposed_actions: Vec<ResolutionAction>,
 }
 
+fn current_schema_version() -> Option<u32> {
+    Some(CURRENT_SCHEMA_VERSION)
+}
+
 fn dependency_map_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
     use schemars::schema::{Schema, SchemaObject};
 
@@ -132,6 +175,8 @@ fn dependency_map_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::
 impl Default for EnhancedManifest {
     fn default() -> Self {
         Self {
+            schema: None,
+            schema_version: current_schema_version(),
             project: ProjectMetadata::default(),
             platform: None,
             dependencies: HashMap::new(),
@@ -143,6 +188,7 @@ impl Default for EnhancedManifest {
             profiles: HashMap::new(),
             hooks: None,
             env: HashMap::new(),
+            env_files: Vec::new(),
             scripts: HashMap::new(),
             extras: HashMap::new(),
             lockfile: None,
@@ -152,8 +198,12 @@ impl Default for EnhancedManifest {
             }),
             services: HashMap::new(),
             conflicts: HashMap::new(),
+            families: HashMap::new(),
+            scoring: None,
+            resolution_policy: None,
             capabilities: None,
             assets: Vec::new(),
+            lints: None,
             intelligence: None,
         }
     }
@@ -0,0 +1,450 @@
+use crate::{CpuArchitecture, OperatingSystem, TargetEnv, TargetPlatform};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// `key = "value"` predicates understood by [`CfgExpr::eval`].
+pub const CFG_KEYS: &[&str] = &[
+    "target_os",
+    "target_arch",
+    "target_family",
+    "target_env",
+    "target_vendor",
+    "target_pointer_width",
+    "libc",
+];
+
+/// Bare-name predicates (`cfg(unix)`).
+pub const CFG_NAMES: &[&str] = &["unix", "windows"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("{message} (at column {column})")]
+pub struct CfgError {
+    pub message: String,
+    /// 1-based column in the target key.
+    pub column: usize,
+}
+
+/// A Cargo-style `cfg(...)` predicate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CfgExpr {
+    All(Vec<CfgExpr>),
+    Any(Vec<CfgExpr>),
+    Not(Box<CfgExpr>),
+    /// `unix`, `windows`
+    Name(String),
+    /// `target_os = "linux"`
+    KeyValue {
+        key: String,
+        value: String,
+    },
+}
+
+impl CfgExpr {
+    /// Parse a full `cfg(...)` key.
+    pub fn parse(key: &str) -> Result<Self, CfgError> {
+        let mut parser = Parser::new(key);
+        parser.expect_ident("cfg")?;
+        parser.expect(Token::Open)?;
+        let expr = parser.expr()?;
+        parser.expect(Token::Close)?;
+        parser.end()?;
+        Ok(expr)
+    }
+
+    /// Evaluate against a platform. Unknown keys and names are false, as in Cargo.
+    pub fn eval(&self, platform: &TargetPlatform) -> bool {
+        match self {
+            CfgExpr::All(exprs) => exprs.iter().all(|e| e.eval(platform)),
+            CfgExpr::Any(exprs) => exprs.iter().any(|e| e.eval(platform)),
+            CfgExpr::Not(expr) => !expr.eval(platform),
+            CfgExpr::Name(name) => {
+                CFG_NAMES.contains(&name.as_str())
+                    && platform_value(platform, "target_family").as_deref() == Some(name)
+            }
+            CfgExpr::KeyValue { key, value } => {
+                platform_value(platform, key).as_deref() == Some(value)
+            }
+        }
+    }
+
+    /// Keys, names and values this release cannot evaluate.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        self.collect_problems(&mut problems);
+        problems
+    }
+
+    fn collect_problems(&self, problems: &mut Vec<String>) {
+        match self {
+            CfgExpr::All(exprs) | CfgExpr::Any(exprs) => {
+                exprs.iter().for_each(|e| e.collect_problems(problems))
+            }
+            CfgExpr::Not(expr) => expr.collect_problems(problems),
+            CfgExpr::Name(name) => {
+                if !CFG_NAMES.contains(&name.as_str()) {
+                    problems.push(format!(
+                        "Unknown cfg name `{}` (expected one of: {})",
+                        name,
+                        CFG_NAMES.join(", ")
+                    ));
+                }
+            }
+            CfgExpr::KeyValue { key, value } => {
+                if !CFG_KEYS.contains(&key.as_str()) {
+                    problems.push(format!(
+                        "Unknown cfg key `{}` (expected one of: {})",
+                        key,
+                        CFG_KEYS.join(", ")
+                    ));
+                } else if !known_value(key, value) {
+                    problems.push(format!("Unknown value \"{}\" for `{}`", value, key));
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Display for CfgExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let list = |f: &mut fmt::Formatter<'_>, name: &str, exprs: &[CfgExpr]| {
+            write!(f, "{}(", name)?;
+            for (i, expr) in exprs.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", expr)?;
+            }
+            write!(f, ")")
+        };
+
+        match self {
+            CfgExpr::All(exprs) => list(f, "all", exprs),
+            CfgExpr::Any(exprs) => list(f, "any", exprs),
+            CfgExpr::Not(expr) => write!(f, "not({})", expr),
+            CfgExpr::Name(name) => write!(f, "{}", name),
+            CfgExpr::KeyValue { key, value } => write!(f, "{} = \"{}\"", key, value),
+        }
+    }
+}
+
+/// How a `[target.<key>]` table selects platforms.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TargetSelector {
+    /// `cfg(...)` expression.
+    Cfg(CfgExpr),
+    /// Triple-like key (`linux`, `x86_64-apple-darwin`), see [`TargetPlatform::matches_target_key`].
+    Triple(String),
+}
+
+impl TargetSelector {
+    pub fn parse(key: &str) -> Result<Self, CfgError> {
+        let is_cfg = key
+            .trim_start()
+            .strip_prefix("cfg")
+            .is_some_and(|rest| rest.trim_start().starts_with('('));
+        if is_cfg {
+            CfgExpr::parse(key).map(Self::Cfg)
+        } else {
+            Ok(Self::Triple(key.to_string()))
+        }
+    }
+
+    pub fn matches(&self, platform: &TargetPlatform) -> bool {
+        match self {
+            TargetSelector::Cfg(expr) => expr.eval(platform),
+            TargetSelector::Triple(key) => platform.matches_target_key(key),
+        }
+    }
+}
+
+impl FromStr for TargetSelector {
+    type Err = CfgError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// The platform's value for a cfg key, spelled the way Rust spells it.
+fn platform_value(platform: &TargetPlatform, key: &str) -> Option<String> {
+    let arch = match platform.arch {
+        CpuArchitecture::Any => None,
+        arch => Some(arch.as_str()),
+    };
+
+    let value = match key {
+        "target_os" => match platform.os {
+            OperatingSystem::Any => return None,
+            os => os.as_str().to_string(),
+        },
+        "target_arch" => arch?.to_string(),
+        "target_family" => match (platform.os, platform.arch) {
+            (_, CpuArchitecture::Wasm32) => "wasm".to_string(),
+            (OperatingSystem::Windows, _) => "windows".to_string(),
+            (OperatingSystem::Any, _) => return None,
+            _ => "unix".to_string(),
+        },
+        "target_env" | "libc" => platform.env?.as_str().to_string(),
+        "target_vendor" => match platform.os {
+            OperatingSystem::Macos | OperatingSystem::Ios => "apple",
+            OperatingSystem::Windows => "pc",
+            _ => "unknown",
+        }
+        .to_string(),
+        "target_pointer_width" => match arch? {
+            "arm" | "x86" | "wasm32" => "32",
+            _ => "64",
+        }
+        .to_string(),
+        _ => return None,
+    };
+    Some(value)
+}
+
+fn known_value(key: &str, value: &str) -> bool {
+    let json = serde_json::Value::String(value.to_string());
+    match key {
+        "target_os" => serde_json::from_value::<OperatingSystem>(json)
+            .is_ok_and(|os| os != OperatingSystem::Any),
+        "target_arch" => serde_json::from_value::<CpuArchitecture>(json)
+            .is_ok_and(|arch| arch != CpuArchitecture::Any),
+        "target_family" => matches!(value, "unix" | "windows" | "wasm"),
+        "target_env" | "libc" => TargetEnv::from_str(value).is_ok(),
+        "target_vendor" => matches!(value, "apple" | "pc" | "unknown"),
+        "target_pointer_width" => matches!(value, "32" | "64"),
+        _ => false,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Open,
+    Close,
+    Comma,
+    Eq,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Ident(ident) => write!(f, "`{}`", ident),
+            Token::Str(s) => write!(f, "\"{}\"", s),
+            Token::Open => write!(f, "`(`"),
+            Token::Close => write!(f, "`)`"),
+            Token::Comma => write!(f, "`,`"),
+            Token::Eq => write!(f, "`=`"),
+        }
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    fn error(&self, at: usize, message: impl Into<String>) -> CfgError {
+        CfgError {
+            message: message.into(),
+            column: self.input[..at].chars().count() + 1,
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.input[self.pos..];
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    /// The next token and the offset it starts at, without consuming it.
+    fn peek(&mut self) -> Result<Option<(Token, usize, usize)>, CfgError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        let rest = &self.input[start..];
+        let Some(c) = rest.chars().next() else {
+            return Ok(None);
+        };
+
+        let (token, len) = match c {
+            '(' => (Token::Open, 1),
+            ')' => (Token::Close, 1),
+            ',' => (Token::Comma, 1),
+            '=' => (Token::Eq, 1),
+            '"' => {
+                let end = rest[1..]
+                    .find('"')
+                    .ok_or_else(|| self.error(start, "Unterminated string"))?;
+                (Token::Str(rest[1..=end].to_string()), end + 2)
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let len = rest
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(rest.len());
+                (Token::Ident(rest[..len].to_string()), len)
+            }
+            c => return Err(self.error(start, format!("Unexpected character `{}`", c))),
+        };
+        Ok(Some((token, start, len)))
+    }
+
+    fn next(&mut self) -> Result<(Token, usize), CfgError> {
+        match self.peek()? {
+            Some((token, start, len)) => {
+                self.pos = start + len;
+                Ok((token, start))
+            }
+            None => Err(self.error(self.input.len(), "Unexpected end of expression")),
+        }
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), CfgError> {
+        let (token, at) = self.next()?;
+        if token == expected {
+            Ok(())
+        } else {
+            Err(self.error(at, format!("Expected {}, found {}", expected, token)))
+        }
+    }
+
+    fn expect_ident(&mut self, expected: &str) -> Result<(), CfgError> {
+        self.expect(Token::Ident(expected.to_string()))
+    }
+
+    fn end(&mut self) -> Result<(), CfgError> {
+        match self.peek()? {
+            None => Ok(()),
+            Some((token, at, _)) => Err(self.error(at, format!("Unexpected {}", token))),
+        }
+    }
+
+    fn expr(&mut self) -> Result<CfgExpr, CfgError> {
+        let (token, at) = self.next()?;
+        let Token::Ident(ident) = token else {
+            return Err(self.error(at, format!("Expected a cfg predicate, found {}", token)));
+        };
+
+        match self.peek()? {
+            Some((Token::Open, _, _)) => {
+                self.next()?;
+                let args = self.list()?;
+                match ident.as_str() {
+                    "all" => Ok(CfgExpr::All(args)),
+                    "any" => Ok(CfgExpr::Any(args)),
+                    "not" if args.len() == 1 => {
+                        Ok(CfgExpr::Not(Box::new(args.into_iter().next().unwrap())))
+                    }
+                    "not" => Err(self.error(at, "`not` takes exactly one predicate")),
+                    _ => Err(self.error(
+                        at,
+                        format!("Unknown operator `{}` (expected all, any or not)", ident),
+                    )),
+                }
+            }
+            Some((Token::Eq, _, _)) => {
+                self.next()?;
+                match self.next()? {
+                    (Token::Str(value), _) => Ok(CfgExpr::KeyValue { key: ident, value }),
+                    (token, at) => {
+                        Err(self.error(at, format!("Expected a quoted value, found {}", token)))
+                    }
+                }
+            }
+            _ => Ok(CfgExpr::Name(ident)),
+        }
+    }
+
+    /// Comma-separated predicates up to the closing parenthesis (trailing comma allowed).
+    fn list(&mut self) -> Result<Vec<CfgExpr>, CfgError> {
+        let mut exprs = Vec::new();
+        loop {
+            if let Some((Token::Close, _, _)) = self.peek()? {
+                self.next()?;
+                return Ok(exprs);
+            }
+            exprs.push(self.expr()?);
+            match self.next()? {
+                (Token::Comma, _) => {}
+                (Token::Close, _) => return Ok(exprs),
+                (token, at) => {
+                    return Err(self.error(at, format!("Expected `,` or `)`, found {}", token)))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn linux_arm_musl() -> TargetPlatform {
+        TargetPlatform::new(OperatingSystem::Linux, CpuArchitecture::Aarch64)
+            .with_env(TargetEnv::Musl)
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let expr =
+            CfgExpr::parse(r#"cfg(all(target_os = "linux", not(target_arch="x86_64"),))"#).unwrap();
+        assert_eq!(
+            expr.to_string(),
+            r#"all(target_os = "linux", not(target_arch = "x86_64"))"#
+        );
+        assert_eq!(
+            CfgExpr::parse("cfg(unix)").unwrap(),
+            CfgExpr::Name("unix".into())
+        );
+    }
+
+    #[test]
+    fn test_eval() {
+        let platform = linux_arm_musl();
+        let eval = |key: &str| CfgExpr::parse(key).unwrap().eval(&platform);
+
+        assert!(eval(
+            r#"cfg(all(target_os = "linux", target_arch = "aarch64"))"#
+        ));
+        assert!(eval(r#"cfg(libc = "musl")"#));
+        assert!(eval("cfg(unix)"));
+        assert!(!eval("cfg(windows)"));
+        assert!(eval(
+            r#"cfg(any(target_os = "macos", target_pointer_width = "64"))"#
+        ));
+        assert!(!eval(r#"cfg(not(target_env = "musl"))"#));
+
+        // ARM64 aliases evaluate like their canonical names.
+        let arm64 = TargetPlatform::new(OperatingSystem::Macos, CpuArchitecture::Arm64);
+        assert!(
+            CfgExpr::parse(r#"cfg(all(target_arch = "aarch64", target_vendor = "apple"))"#)
+                .unwrap()
+                .eval(&arm64)
+        );
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        let err = CfgExpr::parse(r#"cfg(target_os = linux)"#).unwrap_err();
+        assert_eq!(err.column, 17);
+        assert!(CfgExpr::parse("cfg(all(unix)").is_err());
+        assert!(CfgExpr::parse("cfg(not(unix, windows))").is_err());
+        assert!(CfgExpr::parse("cfg(unix) extra").is_err());
+    }
+
+    #[test]
+    fn test_unknown_keys() {
+        let expr = CfgExpr::parse(
+            r#"cfg(any(target_os = "plan9", target_cpu = "x", feature = "y", bsd))"#,
+        )
+        .unwrap();
+        let problems = expr.problems();
+        assert_eq!(problems.len(), 4);
+        assert!(problems[1].contains("target_cpu"));
+        assert!(!expr.eval(&linux_arm_musl()));
+    }
+}
@@ -0,0 +1,107 @@
+use std::fmt;
+use thiserror::Error;
+
+/// Digest algorithms an asset or artifact checksum can use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChecksumAlgorithm {
+    Sha256,
+    Sha384,
+    Sha512,
+}
+
+impl ChecksumAlgorithm {
+    pub const ALL: &'static [ChecksumAlgorithm] = &[Self::Sha256, Self::Sha384, Self::Sha512];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Sha256 => "sha256",
+            Self::Sha384 => "sha384",
+            Self::Sha512 => "sha512",
+        }
+    }
+
+    /// Length of a digest in hex characters.
+    pub fn hex_len(&self) -> usize {
+        match self {
+            Self::Sha256 => 64,
+            Self::Sha384 => 96,
+            Self::Sha512 => 128,
+        }
+    }
+
+    fn names() -> String {
+        Self::ALL
+            .iter()
+            .map(Self::name)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl fmt::Display for ChecksumAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Why a checksum isn't `<algorithm>:<hex digest>`, worded to follow "Checksum '…'".
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ChecksumError {
+    #[error("must be written as '<algorithm>:<hex digest>' (e.g. 'sha256:…')")]
+    Format,
+
+    #[error("uses unsupported algorithm '{0}' (expected one of: {names})", names = ChecksumAlgorithm::names())]
+    Algorithm(String),
+
+    #[error("must be {} hex characters for {algorithm}, got '{digest}'", algorithm.hex_len())]
+    Digest {
+        algorithm: ChecksumAlgorithm,
+        digest: String,
+    },
+}
+
+/// Split a checksum as manifests write it (`sha256:…`) into its algorithm and its
+/// lowercased digest. Installs and `check` both go through here, so a checksum the lint
+/// accepts is one the downloader can verify.
+pub fn parse_checksum(raw: &str) -> Result<(ChecksumAlgorithm, String), ChecksumError> {
+    let (name, digest) = raw.split_once(':').ok_or(ChecksumError::Format)?;
+    let algorithm = ChecksumAlgorithm::ALL
+        .iter()
+        .copied()
+        .find(|algorithm| algorithm.name() == name)
+        .ok_or_else(|| ChecksumError::Algorithm(name.to_string()))?;
+    if digest.len() != algorithm.hex_len() || !digest.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(ChecksumError::Digest {
+            algorithm,
+            digest: digest.to_string(),
+        });
+    }
+    Ok((algorithm, digest.to_ascii_lowercase()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_checksum() {
+        let digest = "AB".repeat(48);
+        assert_eq!(
+            parse_checksum(&format!("sha384:{}", digest)),
+            Ok((ChecksumAlgorithm::Sha384, digest.to_ascii_lowercase()))
+        );
+
+        assert_eq!(parse_checksum(&"ab".repeat(32)), Err(ChecksumError::Format));
+        // blake3 digests are 64 hex characters too, but nothing can verify them.
+        let err = parse_checksum(&format!("blake3:{}", "ab".repeat(32))).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "uses unsupported algorithm 'blake3' (expected one of: sha256, sha384, sha512)"
+        );
+        assert_eq!(
+            parse_checksum("sha256:abc123").unwrap_err().to_string(),
+            "must be 64 hex characters for sha256, got 'abc123'"
+        );
+        assert!(parse_checksum(&format!("sha256:{}", "zz".repeat(32))).is_err());
+    }
+}
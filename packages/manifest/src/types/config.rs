@@ -1,7 +1,12 @@
+use crate::{
+    validate_families, validate_scoring, EnvValue, ScoringConfig, ToolFamilyConfig,
+    ValidationResult,
+};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::Duration;
+use url::Url;
 
 /// Lockfile generation settings.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
@@ -36,7 +41,7 @@ pub struct Profile {
     #[serde(default)]
     pub dependencies: Vec<String>,
     #[serde(default)]
-    pub env: HashMap<String, String>,
+    pub env: HashMap<String, EnvValue>,
     #[serde(default)]
     pub exclude_groups: Vec<String>,
 }
@@ -48,3 +53,90 @@ fn default_true() -> bool {
 fn default_cache_ttl() -> Duration {
     Duration::from_secs(24 * 60 * 60) // 24 hours
 }
+
+/// User-wide settings (`~/.env-architect/config.toml`), shared by every project.
+/// A project's own `[families]` and `[scoring]` take precedence over these.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct UserConfig {
+    /// Tool families and their preference order.
+    #[serde(default)]
+    pub families: HashMap<String, ToolFamilyConfig>,
+
+    /// How conflict resolutions are ranked.
+    #[serde(default)]
+    pub scoring: Option<ScoringConfig>,
+
+    /// Ask npm, PyPI, crates.io and the OS package manager for sizes the registry
+    /// index doesn't publish. Off by default: lookups then stay offline.
+    #[serde(default, rename = "ecosystem-sizes")]
+    pub ecosystem_sizes: bool,
+
+    /// Other servers carrying the registry's targets, tried in order when it fails.
+    /// Downloads are still checked against the registry's signed metadata.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mirrors: Vec<Url>,
+}
+
+impl UserConfig {
+    pub fn validate(&self) -> ValidationResult {
+        let mut result = ValidationResult::new();
+        validate_families(&self.families, &mut result);
+        if let Some(scoring) = &self.scoring {
+            validate_scoring(scoring, &mut result);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ValidationLevel;
+
+    #[test]
+    fn test_validate_user_config() {
+        let config: UserConfig = toml::from_str(
+            r#"
+[families.containers]
+members = ["docker", "podman"]
+prefer = ["podman"]
+
+[families.python]
+members = ["python3", "uv", "python3"]
+prefer = ["conda"]
+
+[families.empty]
+members = []
+
+[families.runtimes]
+members = ["podman"]
+"#,
+        )
+        .unwrap();
+
+        let result = config.validate();
+        let fields = |level: ValidationLevel| -> Vec<&str> {
+            result
+                .issues
+                .iter()
+                .filter(|issue| issue.level == level)
+                .map(|issue| issue.field.as_str())
+                .collect()
+        };
+        assert_eq!(
+            fields(ValidationLevel::Error),
+            [
+                "families.empty.members",
+                "families.python.members[2]",
+                "families.python.prefer[0]"
+            ]
+        );
+        assert_eq!(
+            fields(ValidationLevel::Warning),
+            ["families.runtimes.members[0]"]
+        );
+
+        assert!(toml::from_str::<UserConfig>("[families.x]\nmembers = []\norder = []\n").is_err());
+    }
+}
@@ -0,0 +1,557 @@
+use crate::PackageManager;
+use semver::{Comparator, Op, Version, VersionReq};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Release channels accepted without a registry lookup at parse time.
+const CHANNEL_KEYWORDS: &[&str] = &[
+    "lts", "latest", "stable", "current", "node", "beta", "nightly",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ConstraintError {
+    #[error("Invalid version constraint '{raw}': {reason}")]
+    Invalid { raw: String, reason: String },
+
+    #[error("Unknown release channel '{channel}' for {tool}")]
+    UnknownChannel { tool: String, channel: String },
+}
+
+/// Whose version syntax a dependency follows. Decides what a bare `3.11` means
+/// and which registry channels apply.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Ecosystem {
+    /// Cargo-style SemVer: a bare version is a caret range.
+    Semver,
+    Node,
+    Python,
+    Go,
+    Ruby,
+}
+
+impl Ecosystem {
+    /// Pick the ecosystem from an explicit manager, falling back to the tool name.
+    pub fn for_dependency(name: &str, manager: Option<PackageManager>) -> Self {
+        match manager {
+            Some(PackageManager::Npm) => return Self::Node,
+            Some(PackageManager::Pip | PackageManager::Pip3) => return Self::Python,
+            Some(PackageManager::Go) => return Self::Go,
+            Some(PackageManager::Gem) => return Self::Ruby,
+            _ => {}
+        }
+
+        match name {
+            "node" | "nodejs" | "npm" | "yarn" | "pnpm" | "bun" => Self::Node,
+            "python" | "python3" | "pip" | "pip3" | "pypy" | "poetry" | "uv" => Self::Python,
+            "go" | "golang" => Self::Go,
+            "ruby" | "gem" | "bundler" => Self::Ruby,
+            _ => Self::Semver,
+        }
+    }
+}
+
+/// A normalized, comparable range: the versions matching `req` minus any `excluded`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionRange {
+    pub req: VersionReq,
+    pub excluded: Vec<VersionReq>,
+}
+
+impl VersionRange {
+    pub fn matches(&self, version: &Version) -> bool {
+        self.req.matches(version) && !self.excluded.iter().any(|e| e.matches(version))
+    }
+
+    /// Whether some version satisfies both ranges.
+    ///
+    /// An overlap, if there is one, starts at one of the two ranges' lower bounds or just
+    /// past one of their exclusions, so only those (and `0.0.0`) need checking.
+    pub fn intersects(&self, other: &VersionRange) -> bool {
+        std::iter::once(Version::new(0, 0, 0))
+            .chain(self.lower_bounds())
+            .chain(other.lower_bounds())
+            .chain(self.past_exclusions())
+            .chain(other.past_exclusions())
+            .any(|v| self.matches(&v) && other.matches(&v))
+    }
+
+    fn lower_bounds(&self) -> impl Iterator<Item = Version> + '_ {
+        self.req.comparators.iter().filter_map(|c| {
+            let (minor, patch) = (c.minor.unwrap_or(0), c.patch.unwrap_or(0));
+            let mut version = match c.op {
+                Op::Less | Op::LessEq => return None,
+                // `>1.2` starts at 1.3.0, `>1.2.3` at 1.2.4.
+                Op::Greater => past_exact(c),
+                _ => Version::new(c.major, minor, patch),
+            };
+            version.pre = c.pre.clone();
+            Some(version)
+        })
+    }
+
+    /// The first release above each excluded range: `!=3.9.*` ends at 3.10.0.
+    fn past_exclusions(&self) -> impl Iterator<Item = Version> + '_ {
+        self.excluded
+            .iter()
+            .flat_map(|req| &req.comparators)
+            .filter_map(|c| match c.op {
+                Op::Exact | Op::Wildcard | Op::LessEq => Some(past_exact(c)),
+                Op::Less => Some(Version::new(
+                    c.major,
+                    c.minor.unwrap_or(0),
+                    c.patch.unwrap_or(0),
+                )),
+                Op::Tilde => Some(match c.minor {
+                    None => Version::new(c.major + 1, 0, 0),
+                    Some(minor) => Version::new(c.major, minor + 1, 0),
+                }),
+                Op::Caret => Some(match (c.major, c.minor, c.patch) {
+                    (0, None, _) => Version::new(1, 0, 0),
+                    (0, Some(0), Some(patch)) => Version::new(0, 0, patch + 1),
+                    (0, Some(minor), _) => Version::new(0, minor + 1, 0),
+                    (major, _, _) => Version::new(major + 1, 0, 0),
+                }),
+                _ => None,
+            })
+    }
+}
+
+/// The first release above everything `=c` matches: 1.3.0 for `=1.2`, 1.2.4 for `=1.2.3`.
+fn past_exact(c: &Comparator) -> Version {
+    match (c.minor, c.patch) {
+        (None, _) => Version::new(c.major + 1, 0, 0),
+        (Some(minor), None) => Version::new(c.major, minor + 1, 0),
+        (Some(minor), Some(patch)) => Version::new(c.major, minor, patch + 1),
+    }
+}
+
+impl From<VersionReq> for VersionRange {
+    fn from(req: VersionReq) -> Self {
+        Self {
+            req,
+            excluded: Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for VersionRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.req)?;
+        for excluded in &self.excluded {
+            write!(f, " except {}", excluded)?;
+        }
+        Ok(())
+    }
+}
+
+/// How a constraint was written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConstraintKind {
+    /// SemVer range (`^1.2`, `>=3, <4`, `3.11.*`).
+    Semver(VersionReq),
+    /// Bare version (`3.11`, `v20`); caret in SemVer, a prefix match elsewhere. Manifests
+    /// below schema version 2 meant caret everywhere and are migrated on load.
+    Bare {
+        major: u64,
+        minor: Option<u64>,
+        patch: Option<u64>,
+    },
+    /// PEP 440 specifiers (`~=3.11`, `==3.11.*`, `>=3.9,!=3.10.*`).
+    Pep440(VersionRange),
+    /// RubyGems requirements (`~> 3.2`, `~> 3.2, >= 3.2.2`).
+    RubyGems(VersionRange),
+    /// Named release channel (`lts/*`, `lts/iron`, `stable`), resolved through a registry.
+    Channel(String),
+}
+
+/// A version constraint that keeps its original syntax.
+///
+/// Serializes back to exactly what the user wrote; [`VersionConstraint::range`]
+/// normalizes it for comparison.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionConstraint {
+    raw: String,
+    kind: ConstraintKind,
+}
+
+impl VersionConstraint {
+    /// Parse any supported syntax. An empty string means "any version".
+    pub fn parse(raw: &str) -> Result<Self, ConstraintError> {
+        let trimmed = raw.trim();
+        if trimmed.is_empty() {
+            return Ok(Self::any());
+        }
+
+        let invalid = |reason: String| ConstraintError::Invalid {
+            raw: raw.to_string(),
+            reason,
+        };
+
+        let kind = if is_channel(trimmed) {
+            ConstraintKind::Channel(trimmed.to_string())
+        } else if trimmed.contains("~>") {
+            ConstraintKind::RubyGems(parse_clauses(trimmed).map_err(invalid)?)
+        } else if ["~=", "==", "!="].iter().any(|op| trimmed.contains(op)) {
+            ConstraintKind::Pep440(parse_clauses(trimmed).map_err(invalid)?)
+        } else if let Some((major, minor, patch)) = parse_partial(trimmed.trim_start_matches('v')) {
+            ConstraintKind::Bare {
+                major,
+                minor,
+                patch,
+            }
+        } else {
+            ConstraintKind::Semver(VersionReq::parse(trimmed).map_err(|e| invalid(e.to_string()))?)
+        };
+
+        Ok(Self {
+            raw: trimmed.to_string(),
+            kind,
+        })
+    }
+
+    /// Matches every version (`*`).
+    pub fn any() -> Self {
+        Self {
+            raw: "*".to_string(),
+            kind: ConstraintKind::Semver(VersionReq::STAR),
+        }
+    }
+
+    /// The constraint exactly as written.
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+
+    pub fn kind(&self) -> &ConstraintKind {
+        &self.kind
+    }
+
+    pub fn is_any(&self) -> bool {
+        matches!(&self.kind, ConstraintKind::Semver(req) if *req == VersionReq::STAR)
+    }
+
+    /// The release channel, if this constraint names one.
+    pub fn channel(&self) -> Option<&str> {
+        match &self.kind {
+            ConstraintKind::Channel(channel) => Some(channel),
+            _ => None,
+        }
+    }
+
+    /// Normalize to a comparable range. Channels need a registry; see [`Self::resolve`].
+    pub fn range(&self, ecosystem: Ecosystem) -> Option<VersionRange> {
+        match &self.kind {
+            ConstraintKind::Semver(req) => Some(req.clone().into()),
+            ConstraintKind::Pep440(range) | ConstraintKind::RubyGems(range) => Some(range.clone()),
+            ConstraintKind::Bare {
+                major,
+                minor,
+                patch,
+            } => {
+                let version = [Some(*major), *minor, *patch]
+                    .iter()
+                    .flatten()
+                    .map(u64::to_string)
+                    .collect::<Vec<_>>()
+                    .join(".");
+                let op = if ecosystem == Ecosystem::Semver {
+                    "^"
+                } else {
+                    "="
+                };
+                VersionReq::parse(&format!("{}{}", op, version))
+                    .ok()
+                    .map(Into::into)
+            }
+            ConstraintKind::Channel(_) => None,
+        }
+    }
+
+    /// Normalize to a comparable range, resolving channels through `channels`.
+    pub fn resolve(
+        &self,
+        tool: &str,
+        ecosystem: Ecosystem,
+        channels: &dyn ChannelRegistry,
+    ) -> Result<VersionRange, ConstraintError> {
+        if let Some(range) = self.range(ecosystem) {
+            return Ok(range);
+        }
+
+        let channel = self.channel().unwrap_or(&self.raw);
+        channels
+            .resolve_channel(tool, ecosystem, channel)
+            .map(Into::into)
+            .ok_or_else(|| ConstraintError::UnknownChannel {
+                tool: tool.to_string(),
+                channel: channel.to_string(),
+            })
+    }
+}
+
+/// Maps release channels (`lts/iron`, `stable`) to version ranges.
+pub trait ChannelRegistry {
+    fn resolve_channel(
+        &self,
+        tool: &str,
+        ecosystem: Ecosystem,
+        channel: &str,
+    ) -> Option<VersionReq>;
+}
+
+impl Default for VersionConstraint {
+    fn default() -> Self {
+        Self::any()
+    }
+}
+
+impl FromStr for VersionConstraint {
+    type Err = ConstraintError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl From<VersionReq> for VersionConstraint {
+    fn from(req: VersionReq) -> Self {
+        Self {
+            raw: req.to_string(),
+            kind: ConstraintKind::Semver(req),
+        }
+    }
+}
+
+impl fmt::Display for VersionConstraint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+impl Serialize for VersionConstraint {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.raw)
+    }
+}
+
+impl<'de> Deserialize<'de> for VersionConstraint {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+        Self::parse(&raw).map_err(serde::de::Error::custom)
+    }
+}
+
+fn is_channel(s: &str) -> bool {
+    CHANNEL_KEYWORDS.contains(&s)
+        || s.strip_prefix("lts/").is_some_and(|name| {
+            name == "*" || (!name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric()))
+        })
+}
+
+/// `3`, `3.11` or `3.11.4`.
+fn parse_partial(s: &str) -> Option<(u64, Option<u64>, Option<u64>)> {
+    let mut parts = s.split('.');
+    let major = parts.next()?.parse().ok()?;
+    let minor = parts.next().map(str::parse).transpose().ok()?;
+    let patch = parts.next().map(str::parse).transpose().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((major, minor, patch))
+}
+
+/// Comma-separated PEP 440 / RubyGems clauses.
+fn parse_clauses(s: &str) -> Result<VersionRange, String> {
+    let mut comparators = Vec::new();
+    let mut excluded = Vec::new();
+
+    for clause in s.split(',').map(str::trim) {
+        let (op, version) = split_operator(clause);
+        let version = version.trim();
+
+        let req = match op {
+            // Compatible release: `~=3.11` is `>=3.11, <4`, `~> 3.2.1` is `>=3.2.1, <3.3`.
+            "~=" | "~>" => {
+                let (major, minor, patch) = parse_partial(version)
+                    .ok_or_else(|| format!("'{}' is not a release version", version))?;
+                let upper = match (minor, patch) {
+                    (Some(minor), Some(_)) => format!("{}.{}.0", major, minor + 1),
+                    _ if op == "~=" && minor.is_none() => {
+                        return Err("`~=` needs at least two version components".to_string())
+                    }
+                    _ => format!("{}.0.0", major + 1),
+                };
+                format!(">={}, <{}", version, upper)
+            }
+            // `==3.11.*` is a prefix match; `==3.11` is exactly 3.11.0.
+            "==" | "===" => format!("={}", exact_or_prefix(version)),
+            "=" | "" => format!("={}", version.trim_end_matches(".*")),
+            "!=" => {
+                excluded.push(parse_req(&format!("={}", exact_or_prefix(version)))?);
+                continue;
+            }
+            _ => format!("{}{}", op, version),
+        };
+        comparators.extend(parse_req(&req)?.comparators);
+    }
+
+    Ok(VersionRange {
+        req: VersionReq { comparators },
+        excluded,
+    })
+}
+
+/// A PEP 440 `==`/`!=` operand as a SemVer `=` operand: `3.11.*` keeps matching the
+/// whole release line, `3.11` is padded with zeros to name a single release.
+fn exact_or_prefix(version: &str) -> String {
+    if let Some(prefix) = version.strip_suffix(".*") {
+        return prefix.to_string();
+    }
+    match parse_partial(version) {
+        Some((major, minor, patch)) => {
+            format!("{}.{}.{}", major, minor.unwrap_or(0), patch.unwrap_or(0))
+        }
+        None => version.to_string(),
+    }
+}
+
+fn split_operator(clause: &str) -> (&str, &str) {
+    for op in ["===", "~=", "~>", "==", "!=", ">=", "<=", ">", "<", "="] {
+        if let Some(rest) = clause.strip_prefix(op) {
+            return (op, rest);
+        }
+    }
+    ("", clause)
+}
+
+fn parse_req(s: &str) -> Result<VersionReq, String> {
+    VersionReq::parse(s).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(raw: &str, ecosystem: Ecosystem) -> VersionRange {
+        VersionConstraint::parse(raw)
+            .unwrap()
+            .range(ecosystem)
+            .unwrap()
+    }
+
+    fn v(s: &str) -> Version {
+        Version::parse(s).unwrap()
+    }
+
+    #[test]
+    fn test_keeps_original_syntax() {
+        for raw in ["~=3.11", "3.11.*", "lts/iron", "~> 3.2", "1.22", "^20"] {
+            let constraint = VersionConstraint::parse(raw).unwrap();
+            assert_eq!(constraint.to_string(), raw);
+            assert_eq!(
+                serde_json::to_string(&constraint).unwrap(),
+                format!("\"{}\"", raw)
+            );
+        }
+        assert!(VersionConstraint::parse("").unwrap().is_any());
+    }
+
+    #[test]
+    fn test_python_specifiers() {
+        let compatible = range("~=3.11", Ecosystem::Python);
+        assert!(compatible.matches(&v("3.12.1")));
+        assert!(!compatible.matches(&v("4.0.0")));
+
+        let prefix = range("==3.11.*", Ecosystem::Python);
+        assert!(prefix.matches(&v("3.11.9")));
+        assert!(!prefix.matches(&v("3.12.0")));
+
+        let excluded = range(">=3.9, !=3.10.*, <3.13", Ecosystem::Python);
+        assert!(excluded.matches(&v("3.9.1")));
+        assert!(!excluded.matches(&v("3.10.4")));
+        assert!(!excluded.matches(&v("3.13.0")));
+
+        assert!(range("3.11.*", Ecosystem::Python).matches(&v("3.11.2")));
+
+        // Without `.*`, `==` names one release.
+        let exact = range("==3.11", Ecosystem::Python);
+        assert!(exact.matches(&v("3.11.0")));
+        assert!(!exact.matches(&v("3.11.4")));
+        let not_exact = range(">=3.10, !=3.11", Ecosystem::Python);
+        assert!(not_exact.matches(&v("3.11.4")));
+        assert!(!not_exact.matches(&v("3.11.0")));
+    }
+
+    #[test]
+    fn test_bare_versions_depend_on_ecosystem() {
+        // Go's `1.22` is the 1.22 release line, SemVer's is `^1.22`.
+        assert!(!range("1.22", Ecosystem::Go).matches(&v("1.23.0")));
+        assert!(range("1.22", Ecosystem::Go).matches(&v("1.22.5")));
+        assert!(range("1.22", Ecosystem::Semver).matches(&v("1.23.0")));
+        assert!(range("v20", Ecosystem::Node).matches(&v("20.11.1")));
+    }
+
+    #[test]
+    fn test_ruby_pessimistic() {
+        let minor = range("~> 3.2", Ecosystem::Ruby);
+        assert!(minor.matches(&v("3.9.0")));
+        assert!(!minor.matches(&v("4.0.0")));
+
+        let patch = range("~> 3.2.1, >= 3.2.2", Ecosystem::Ruby);
+        assert!(patch.matches(&v("3.2.5")));
+        assert!(!patch.matches(&v("3.2.1")));
+        assert!(!patch.matches(&v("3.3.0")));
+    }
+
+    #[test]
+    fn test_range_intersection() {
+        let caret = range("^18", Ecosystem::Semver);
+        assert!(caret.intersects(&range(">=18.5, <19", Ecosystem::Semver)));
+        assert!(caret.intersects(&range("*", Ecosystem::Semver)));
+        assert!(!caret.intersects(&range("^20", Ecosystem::Semver)));
+        assert!(!caret.intersects(&range(">18", Ecosystem::Semver)));
+
+        let py = range("~=3.11", Ecosystem::Python);
+        assert!(py.intersects(&range(">=3.12", Ecosystem::Python)));
+        assert!(!py.intersects(&range("3.10", Ecosystem::Python)));
+
+        // The overlap starts past an exclusion rather than at a lower bound.
+        let not_39 = range(">=3.9, !=3.9.*", Ecosystem::Python);
+        assert!(not_39.intersects(&range(">=3.9, <4", Ecosystem::Python)));
+        assert!(range(">=3.9, <4", Ecosystem::Python).intersects(&not_39));
+        assert!(!not_39.intersects(&range("3.9", Ecosystem::Python)));
+        let gaps = range(">=3.9, !=3.9.*, !=3.10.*, !=3.11", Ecosystem::Python);
+        assert!(gaps.intersects(&range("~=3.11", Ecosystem::Python)));
+        assert!(!gaps.intersects(&range("==3.11", Ecosystem::Python)));
+    }
+
+    #[test]
+    fn test_channels_resolve_through_registry() {
+        struct Lts;
+        impl ChannelRegistry for Lts {
+            fn resolve_channel(&self, _: &str, _: Ecosystem, channel: &str) -> Option<VersionReq> {
+                (channel == "lts/iron").then(|| VersionReq::parse("^20").unwrap())
+            }
+        }
+
+        let iron = VersionConstraint::parse("lts/iron").unwrap();
+        assert_eq!(iron.channel(), Some("lts/iron"));
+        assert!(iron.range(Ecosystem::Node).is_none());
+        let resolved = iron.resolve("node", Ecosystem::Node, &Lts).unwrap();
+        assert!(resolved.matches(&v("20.11.0")));
+
+        let err = VersionConstraint::parse("lts/*")
+            .unwrap()
+            .resolve("node", Ecosystem::Node, &Lts)
+            .unwrap_err();
+        assert!(matches!(err, ConstraintError::UnknownChannel { .. }));
+    }
+
+    #[test]
+    fn test_rejects_garbage() {
+        assert!(VersionConstraint::parse("not-a-version").is_err());
+        assert!(VersionConstraint::parse("~=3").is_err());
+    }
+}
@@ -1,5 +1,5 @@
+use crate::{Ecosystem, OperatingSystem, VersionConstraint};
 use schemars::JsonSchema;
-use semver::VersionReq;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use url::Url;
@@ -8,12 +8,8 @@ use url::Url;
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(untagged)]
 pub enum DependencySpec {
-    /// Simple version string (e.g., `^1.0.0`).
-    Simple(
-        #[serde(deserialize_with = "deserialize_version_req")]
-        #[schemars(with = "String")]
-        VersionReq,
-    ),
+    /// Simple version string (e.g., `^1.0.0`, `~=3.11`, `lts/iron`).
+    Simple(#[schemars(with = "String")] VersionConstraint),
 
     /// Detailed configuration object.
     Detailed(DependencyDetails),
@@ -22,13 +18,10 @@ pub enum DependencySpec {
 /// Detailed configuration for a dependency.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct DependencyDetails {
-    /// Version requirement (SemVer range).
-    #[serde(
-        default = "default_version_req",
-        deserialize_with = "deserialize_version_req"
-    )]
+    /// Version constraint, in SemVer or the tool's native syntax.
+    #[serde(default)]
     #[schemars(with = "String")]
-    pub version: VersionReq,
+    pub version: VersionConstraint,
 
     /// Explicit package manager to use.
     pub manager: Option<PackageManager>,
@@ -42,6 +35,28 @@ pub struct DependencyDetails {
     pub optional: bool,
 }
 
+impl DependencySpec {
+    /// The version constraint, whichever form the spec takes.
+    pub fn constraint(&self) -> &VersionConstraint {
+        match self {
+            DependencySpec::Simple(constraint) => constraint,
+            DependencySpec::Detailed(details) => &details.version,
+        }
+    }
+
+    pub fn manager(&self) -> Option<PackageManager> {
+        match self {
+            DependencySpec::Simple(_) => None,
+            DependencySpec::Detailed(details) => details.manager,
+        }
+    }
+
+    /// The ecosystem whose syntax the constraint for `name` follows.
+    pub fn ecosystem(&self, name: &str) -> Ecosystem {
+        Ecosystem::for_dependency(name, self.manager())
+    }
+}
+
 /// Supported package managers.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
 #[serde(rename_all = "lowercase")]
@@ -64,6 +79,41 @@ pub enum PackageManager {
     Docker,
 }
 
+impl PackageManager {
+    /// Operating systems the manager exists on; `None` means everywhere.
+    pub fn platforms(&self) -> Option<&'static [OperatingSystem]> {
+        match self {
+            PackageManager::Apt | PackageManager::Yum | PackageManager::Pacman => {
+                Some(&[OperatingSystem::Linux])
+            }
+            PackageManager::Brew => Some(&[OperatingSystem::Macos, OperatingSystem::Linux]),
+            PackageManager::Chocolatey => Some(&[OperatingSystem::Windows]),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PackageManager::Npm => "npm",
+            PackageManager::Pip => "pip",
+            PackageManager::Pip3 => "pip3",
+            PackageManager::Cargo => "cargo",
+            PackageManager::Gem => "gem",
+            PackageManager::Go => "go",
+            PackageManager::Maven => "maven",
+            PackageManager::Gradle => "gradle",
+            PackageManager::Composer => "composer",
+            PackageManager::Nuget => "nuget",
+            PackageManager::Chocolatey => "chocolatey",
+            PackageManager::Brew => "brew",
+            PackageManager::Apt => "apt",
+            PackageManager::Yum => "yum",
+            PackageManager::Pacman => "pacman",
+            PackageManager::Docker => "docker",
+        }
+    }
+}
+
 /// Target-specific dependencies.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 pub struct TargetDependencies {
@@ -109,29 +159,16 @@ where
         DepMapOrSeq::Seq(s) => {
             let mut map = HashMap::new();
             for name in s {
-                map.insert(name.clone(), DependencySpec::Simple(default_version_req()));
+                map.insert(
+                    name.clone(),
+                    DependencySpec::Simple(VersionConstraint::any()),
+                );
             }
             Ok(map)
         }
     }
 }
 
-pub fn default_version_req() -> VersionReq {
-    VersionReq::parse("*").unwrap()
-}
-
-pub fn deserialize_version_req<'de, D>(deserializer: D) -> Result<VersionReq, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    let s = String::deserialize(deserializer)?;
-    if s.is_empty() {
-        Ok(VersionReq::parse("*").unwrap())
-    } else {
-        VersionReq::parse(&s).map_err(serde::de::Error::custom)
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -141,7 +178,7 @@ mod tests {
         let json = r#""^1.2.3""#;
         let spec: DependencySpec = serde_json::from_str(json).unwrap();
         match spec {
-            DependencySpec::Simple(v) => assert_eq!(v, VersionReq::parse("^1.2.3").unwrap()),
+            DependencySpec::Simple(v) => assert_eq!(v, VersionConstraint::parse("^1.2.3").unwrap()),
             _ => panic!("Expected Simple"),
         }
     }
@@ -151,17 +188,26 @@ mod tests {
         let json = r#""""#;
         let spec: DependencySpec = serde_json::from_str(json).unwrap();
         match spec {
-            DependencySpec::Simple(v) => assert_eq!(v, VersionReq::parse("*").unwrap()),
+            DependencySpec::Simple(v) => assert!(v.is_any()),
             _ => panic!("Expected Simple"),
         }
     }
 
+    #[test]
+    fn test_dependency_spec_native_syntax() {
+        let json = r#"{ "version": "~=3.11", "manager": "pip" }"#;
+        let spec: DependencySpec = serde_json::from_str(json).unwrap();
+        assert_eq!(spec.constraint().as_str(), "~=3.11");
+        assert_eq!(spec.ecosystem("python"), Ecosystem::Python);
+        assert!(serde_json::from_str::<DependencySpec>(r#""^^1""#).is_err());
+    }
+
     #[test]
     fn test_dependency_spec_empty() {
         let json = r#""""#;
         let spec: DependencySpec = serde_json::from_str(json).unwrap();
         match spec {
-            DependencySpec::Simple(v) => assert_eq!(v, VersionReq::parse("*").unwrap()),
+            DependencySpec::Simple(v) => assert!(v.is_any()),
             _ => panic!("Expected Simple"),
         }
     }
@@ -0,0 +1,289 @@
+use crate::EnvValue;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// A `.env` error, with the 1-based line it starts on (0 when the file couldn't be read).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DotenvError {
+    pub path: Option<PathBuf>,
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for DotenvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.path, self.line) {
+            (Some(path), 0) => write!(f, "{}: ", path.display())?,
+            (Some(path), line) => write!(f, "{}:{}: ", path.display(), line)?,
+            (None, 0) => {}
+            (None, line) => write!(f, "line {}: ", line)?,
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for DotenvError {}
+
+/// Parse `.env` content into `(key, value)` pairs in file order.
+///
+/// Supports `export KEY=value`, `#` comments (a `#` after whitespace ends an unquoted
+/// value), single-quoted literals, and double-quoted values with `\n`, `\t`, `\"`, `\\`
+/// escapes. Both quote styles may span lines. Values are taken literally: `${VAR}` is not
+/// expanded.
+pub fn parse_dotenv(content: &str) -> Result<Vec<(String, String)>, DotenvError> {
+    let mut vars = Vec::new();
+    let mut lines = content.lines().enumerate();
+
+    while let Some((index, raw)) = lines.next() {
+        let line_number = index + 1;
+        let error = |message: String| DotenvError {
+            path: None,
+            line: line_number,
+            message,
+        };
+
+        let line = raw.trim_start();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line).trim_start();
+
+        let (key, rest) = line
+            .split_once('=')
+            .ok_or_else(|| error(format!("expected KEY=value, got '{}'", raw.trim())))?;
+        let key = key.trim();
+        if key.is_empty()
+            || !key
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '.' || c == '-')
+            || key.starts_with(|c: char| c.is_ascii_digit())
+        {
+            return Err(error(format!("invalid variable name '{}'", key)));
+        }
+
+        let rest = rest.trim_start();
+        let value = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                // Collect lines until the closing quote.
+                let mut body = rest[1..].to_string();
+                let (value, trailing) = loop {
+                    if let Some((value, trailing)) = close_quote(&body, quote) {
+                        break (value, trailing);
+                    }
+                    match lines.next() {
+                        Some((_, next)) => {
+                            body.push('\n');
+                            body.push_str(next);
+                        }
+                        None => return Err(error(format!("unterminated {} quote", quote))),
+                    }
+                };
+
+                let trailing = trailing.trim_start();
+                if !trailing.is_empty() && !trailing.starts_with('#') {
+                    return Err(error(format!(
+                        "unexpected '{}' after closing quote",
+                        trailing
+                    )));
+                }
+                if quote == '"' {
+                    unescape(&value)
+                } else {
+                    value
+                }
+            }
+            _ => {
+                let end = rest
+                    .char_indices()
+                    .find(|&(i, c)| c == '#' && (i == 0 || rest[..i].ends_with([' ', '\t'])))
+                    .map_or(rest.len(), |(i, _)| i);
+                rest[..end].trim_end().to_string()
+            }
+        };
+
+        vars.push((key.to_string(), value));
+    }
+
+    Ok(vars)
+}
+
+/// Split a quoted body at its closing quote (skipping `\"` in double quotes).
+fn close_quote(body: &str, quote: char) -> Option<(String, &str)> {
+    let mut escaped = false;
+    for (i, c) in body.char_indices() {
+        if quote == '"' && escaped {
+            escaped = false;
+        } else if quote == '"' && c == '\\' {
+            escaped = true;
+        } else if c == quote {
+            return Some((body[..i].to_string(), &body[i + 1..]));
+        }
+    }
+    None
+}
+
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('t') => out.push('\t'),
+            Some(other @ ('"' | '\\' | '$')) => out.push(other),
+            Some(other) => {
+                out.push('\\');
+                out.push(other);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// Render variables as `.env` lines, quoting values that need it so [`parse_dotenv`]
+/// reads them back unchanged.
+pub fn to_dotenv<'a>(vars: impl IntoIterator<Item = (&'a str, &'a str)>) -> String {
+    let mut out = String::new();
+    for (key, value) in vars {
+        let plain = value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-./:@%+,=".contains(c));
+        if plain {
+            out.push_str(&format!("{}={}\n", key, value));
+        } else if !value.contains(['\'', '\n', '\r']) {
+            // Single quotes keep `$` literal for tools that expand variables.
+            out.push_str(&format!("{}='{}'\n", key, value));
+        } else {
+            let escaped = value
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('$', "\\$")
+                .replace('\n', "\\n")
+                .replace('\r', "\\r")
+                .replace('\t', "\\t");
+            out.push_str(&format!("{}=\"{}\"\n", key, escaped));
+        }
+    }
+    out
+}
+
+/// Read `files` (relative to `root`) into one env layer; later files win. Missing files
+/// are skipped, so optional overrides such as `.env.local` need not exist.
+pub fn read_env_files(
+    root: &Path,
+    files: &[String],
+) -> Result<HashMap<String, EnvValue>, DotenvError> {
+    let mut env = HashMap::new();
+    for file in files {
+        let path = root.join(file);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => {
+                return Err(DotenvError {
+                    path: Some(path),
+                    line: 0,
+                    message: e.to_string(),
+                })
+            }
+        };
+
+        let vars = parse_dotenv(&content).map_err(|e| DotenvError {
+            path: Some(path.clone()),
+            ..e
+        })?;
+        for (key, value) in vars {
+            // Literal: keep `${` from being read as a manifest reference.
+            env.insert(key, EnvValue::Literal(value.replace("${", "$${")));
+        }
+    }
+    Ok(env)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> Vec<(String, String)> {
+        parse_dotenv(content).unwrap()
+    }
+
+    fn pair(key: &str, value: &str) -> (String, String) {
+        (key.to_string(), value.to_string())
+    }
+
+    #[test]
+    fn test_parse_forms() {
+        let vars = parse(
+            r#"
+# comment
+export NODE_ENV=production
+PLAIN = value with spaces   # trailing comment
+URL=https://example.com/#anchor
+EMPTY=
+SINGLE='literal \n ${HOME}'
+DOUBLE="line\nnext \"quoted\""
+"#,
+        );
+        assert_eq!(
+            vars,
+            vec![
+                pair("NODE_ENV", "production"),
+                pair("PLAIN", "value with spaces"),
+                pair("URL", "https://example.com/#anchor"),
+                pair("EMPTY", ""),
+                pair("SINGLE", "literal \\n ${HOME}"),
+                pair("DOUBLE", "line\nnext \"quoted\""),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_multiline_values() {
+        let vars =
+            parse("KEY=\"-----BEGIN KEY-----\nabc\n-----END KEY-----\"\nNEXT='a\nb' # note\n");
+        assert_eq!(
+            vars,
+            vec![
+                pair("KEY", "-----BEGIN KEY-----\nabc\n-----END KEY-----"),
+                pair("NEXT", "a\nb"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_errors_report_lines() {
+        let err = parse_dotenv("A=1\nB=\"open\n\nC=3").unwrap_err();
+        assert_eq!(err.line, 2);
+        assert!(err.message.contains("unterminated"));
+
+        assert_eq!(parse_dotenv("A=1\nnot a pair").unwrap_err().line, 2);
+        assert!(parse_dotenv("1A=x").is_err());
+        assert!(parse_dotenv("A=\"x\" y").is_err());
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let vars = [
+            ("PLAIN", "abc-1.2/x:y"),
+            ("SPACED", "a b ${HOME}"),
+            ("QUOTE", "it's $5"),
+            ("TRICKY", "say \"hi\"\n\\path\t#"),
+            ("EMPTY", ""),
+        ];
+        let rendered = to_dotenv(vars);
+        assert!(
+            rendered.starts_with("PLAIN=abc-1.2/x:y\nSPACED='a b ${HOME}'\nQUOTE=\"it's \\$5\"\n")
+        );
+
+        let parsed = parse(&rendered);
+        let expected: Vec<_> = vars.iter().map(|(k, v)| pair(k, v)).collect();
+        assert_eq!(parsed, expected);
+    }
+}
@@ -0,0 +1,848 @@
+use crate::source::{yaml_key, JsonNode};
+use crate::{ManifestFormat, ManifestSource};
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::fmt;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// Format-preserving manifest editor.
+///
+/// Edits touch only the keys they change: comments, ordering and whitespace elsewhere in
+/// the file are kept. TOML goes through `toml_edit`; JSON and YAML are edited by splicing
+/// the affected lines, since their serde parsers can't round-trip formatting.
+///
+/// Paths are key segments, e.g. `["dev-dependencies", "node"]` or `["env", "FOO"]`.
+#[derive(Debug, Clone)]
+pub struct ManifestEditor {
+    pub format: ManifestFormat,
+    pub path: Option<PathBuf>,
+    document: Document,
+}
+
+#[derive(Debug, Clone)]
+enum Document {
+    Toml(toml_edit::DocumentMut),
+    Text(String),
+}
+
+impl ManifestEditor {
+    pub fn new(content: &str, format: ManifestFormat) -> Result<Self> {
+        let document = match format {
+            ManifestFormat::Toml => Document::Toml(
+                content
+                    .parse::<toml_edit::DocumentMut>()
+                    .context("Failed to parse TOML manifest")?,
+            ),
+            ManifestFormat::Json => {
+                JsonNode::parse(content).context("Failed to parse JSON manifest")?;
+                Document::Text(content.to_string())
+            }
+            ManifestFormat::Yaml => Document::Text(content.to_string()),
+        };
+
+        Ok(Self {
+            format,
+            path: None,
+            document,
+        })
+    }
+
+    /// Open a manifest file for editing.
+    pub fn open(path: &Path) -> Result<Self> {
+        let format = ManifestFormat::from_path(path)
+            .with_context(|| format!("Unsupported manifest format: {}", path.display()))?;
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read manifest file: {}", path.display()))?;
+
+        let mut editor = Self::new(&content, format)?;
+        editor.path = Some(path.to_path_buf());
+        Ok(editor)
+    }
+
+    /// Whether a key exists at `path`.
+    pub fn contains(&self, path: &[&str]) -> bool {
+        match &self.document {
+            Document::Toml(doc) => {
+                let mut item = doc.as_item();
+                for key in path {
+                    match item.as_table_like().and_then(|t| t.get(key)) {
+                        Some(child) => item = child,
+                        None => return false,
+                    }
+                }
+                true
+            }
+            Document::Text(text) => match self.format {
+                ManifestFormat::Json => {
+                    JsonNode::parse(text).is_some_and(|root| json_lookup(&root, path).is_some())
+                }
+                _ => yaml_lookup(&yaml_lines(text), path).is_some(),
+            },
+        }
+    }
+
+    /// Set the value at `path`, creating missing parent tables.
+    pub fn set(&mut self, path: &[&str], value: impl Into<Value>) -> Result<()> {
+        let value = value.into();
+        anyhow::ensure!(!path.is_empty(), "Cannot set an empty path");
+
+        match &mut self.document {
+            Document::Toml(doc) => toml_set(doc, path, &value),
+            Document::Text(text) => {
+                *text = match self.format {
+                    ManifestFormat::Json => json_set(text, path, &value)?,
+                    _ => yaml_set(text, path, &value)?,
+                };
+                Ok(())
+            }
+        }
+    }
+
+    /// Remove the key at `path`. Returns whether it existed.
+    pub fn remove(&mut self, path: &[&str]) -> Result<bool> {
+        if path.is_empty() || !self.contains(path) {
+            return Ok(false);
+        }
+
+        match &mut self.document {
+            Document::Toml(doc) => {
+                let (last, parents) = path.split_last().expect("non-empty path");
+                let mut item = doc.as_item_mut();
+                for key in parents {
+                    item = item
+                        .as_table_like_mut()
+                        .and_then(|t| t.get_mut(key))
+                        .expect("path exists");
+                }
+                let table = item.as_table_like_mut().expect("path exists");
+                Ok(table.remove(last).is_some())
+            }
+            Document::Text(text) => {
+                *text = match self.format {
+                    ManifestFormat::Json => json_remove(text, path)?,
+                    _ => yaml_remove(text, path),
+                };
+                Ok(true)
+            }
+        }
+    }
+
+    /// Read the value at `path`.
+    pub fn get(&self, path: &[&str]) -> Option<Value> {
+        match &self.document {
+            Document::Toml(doc) => {
+                let mut item = doc.as_item();
+                for key in path {
+                    item = item.as_table_like()?.get(key)?;
+                }
+                toml_item_to_json(item)
+            }
+            Document::Text(text) => match self.format {
+                ManifestFormat::Json => {
+                    let root = JsonNode::parse(text)?;
+                    let node = json_lookup(&root, path)?;
+                    serde_json::from_str(&text[node.range()]).ok()
+                }
+                _ => {
+                    let lines = yaml_lines(text);
+                    if path.is_empty() {
+                        return serde_yaml::from_str(text).ok();
+                    }
+                    let entry = yaml_lookup(&lines, path)?;
+                    yaml_entry_value(&lines, &entry)
+                }
+            },
+        }
+    }
+
+    /// Rename the last key of `path` in place, keeping its value and position.
+    /// Returns whether the key existed.
+    pub fn rename(&mut self, path: &[&str], new_key: &str) -> Result<bool> {
+        if path.is_empty() || !self.contains(path) {
+            return Ok(false);
+        }
+        let (last, parents) = path.split_last().expect("non-empty path");
+        let mut target = parents.to_vec();
+        target.push(new_key);
+        anyhow::ensure!(
+            !self.contains(&target),
+            "Cannot rename '{}': '{}' already exists",
+            path.join("."),
+            target.join(".")
+        );
+
+        match &mut self.document {
+            Document::Toml(doc) => {
+                let mut item = doc.as_item_mut();
+                for key in parents {
+                    item = item
+                        .as_table_like_mut()
+                        .and_then(|t| t.get_mut(key))
+                        .expect("path exists");
+                }
+                let table = item.as_table_like_mut().expect("path exists");
+                let value = table.remove(last).expect("path exists");
+                table.insert(new_key, value);
+            }
+            Document::Text(text) => match self.format {
+                ManifestFormat::Json => {
+                    let root = JsonNode::parse(text).context("Failed to parse JSON manifest")?;
+                    let Some(JsonNode::Object { members, .. }) = json_lookup(&root, parents) else {
+                        anyhow::bail!("'{}' is not an object", parents.join("."));
+                    };
+                    let member = members
+                        .iter()
+                        .find(|m| m.key == *last)
+                        .expect("path exists");
+                    let key = Value::String(new_key.to_string()).to_string();
+                    *text = splice(text, member.key_range.clone(), &key);
+                }
+                _ => {
+                    let lines = yaml_lines(text);
+                    let entry = yaml_lookup(&lines, path).expect("path exists");
+                    let line = lines[entry.line];
+                    let key_end = line.len() - entry.inline_value.len();
+                    let colon = line[..key_end].rfind(':').expect("key line has a colon");
+                    let renamed = format!(
+                        "{}{}{}",
+                        &line[..entry.indent],
+                        yaml_key_text(new_key),
+                        &line[colon..]
+                    );
+                    let mut out: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+                    out[entry.line] = renamed;
+                    *text = yaml_join(out, text);
+                }
+            },
+        }
+        Ok(true)
+    }
+
+    /// Set a dependency's version requirement in `section` (e.g. `["dev-dependencies"]`).
+    /// A detailed spec keeps its other fields and only has `version` updated.
+    pub fn set_dependency(&mut self, section: &[&str], name: &str, version: &str) -> Result<()> {
+        let mut path = section.to_vec();
+        path.push(name);
+
+        let mut detailed = path.clone();
+        detailed.push("version");
+        if self.contains(&detailed) {
+            self.set(&detailed, version)
+        } else {
+            self.set(&path, version)
+        }
+    }
+
+    /// The edited document as a parseable source.
+    pub fn source(&self) -> ManifestSource {
+        let source = ManifestSource::new(self.to_string(), self.format);
+        match &self.path {
+            Some(path) => source.with_path(path),
+            None => source,
+        }
+    }
+
+    /// Write the document back to the file it was opened from.
+    pub fn save(&self) -> Result<()> {
+        let path = self
+            .path
+            .as_ref()
+            .context("Manifest was not opened from a file")?;
+        std::fs::write(path, self.to_string())
+            .with_context(|| format!("Failed to write manifest file: {}", path.display()))
+    }
+}
+
+impl fmt::Display for ManifestEditor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.document {
+            Document::Toml(doc) => write!(f, "{}", doc),
+            Document::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+fn toml_set(doc: &mut toml_edit::DocumentMut, path: &[&str], value: &Value) -> Result<()> {
+    let (last, parents) = path.split_last().expect("non-empty path");
+    let value = to_toml(value)?;
+
+    let mut item = doc.as_item_mut();
+    for (depth, key) in parents.iter().enumerate() {
+        let inline = item.is_value();
+        let table = item
+            .as_table_like_mut()
+            .with_context(|| format!("'{}' is not a table", parents[..depth].join(".")))?;
+
+        if table.get(key).is_none() {
+            let child = if inline {
+                toml_edit::value(toml_edit::InlineTable::new())
+            } else {
+                // Only the table that directly holds the value gets a header.
+                let mut child = toml_edit::Table::new();
+                child.set_implicit(depth + 1 < parents.len());
+                toml_edit::Item::Table(child)
+            };
+            table.insert(key, child);
+        }
+        item = table.get_mut(key).expect("inserted above");
+    }
+
+    let table = item
+        .as_table_like_mut()
+        .with_context(|| format!("'{}' is not a table", parents.join(".")))?;
+
+    match table.get_mut(last) {
+        Some(existing) => {
+            let mut value = value;
+            if let Some(old) = existing.as_value() {
+                *value.decor_mut() = old.decor().clone();
+            }
+            *existing = toml_edit::Item::Value(value);
+        }
+        None => {
+            table.insert(last, toml_edit::Item::Value(value));
+        }
+    }
+    Ok(())
+}
+
+fn toml_item_to_json(item: &toml_edit::Item) -> Option<Value> {
+    match item {
+        toml_edit::Item::Value(value) => Some(toml_value_to_json(value)),
+        toml_edit::Item::Table(table) => Some(Value::Object(
+            table
+                .iter()
+                .filter_map(|(k, v)| Some((k.to_string(), toml_item_to_json(v)?)))
+                .collect(),
+        )),
+        toml_edit::Item::ArrayOfTables(tables) => Some(Value::Array(
+            tables
+                .iter()
+                .filter_map(|t| toml_item_to_json(&toml_edit::Item::Table(t.clone())))
+                .collect(),
+        )),
+        toml_edit::Item::None => None,
+    }
+}
+
+fn toml_value_to_json(value: &toml_edit::Value) -> Value {
+    match value {
+        toml_edit::Value::String(s) => Value::String(s.value().clone()),
+        toml_edit::Value::Integer(i) => Value::from(*i.value()),
+        toml_edit::Value::Float(f) => Value::from(*f.value()),
+        toml_edit::Value::Boolean(b) => Value::Bool(*b.value()),
+        toml_edit::Value::Datetime(d) => Value::String(d.value().to_string()),
+        toml_edit::Value::Array(items) => {
+            Value::Array(items.iter().map(toml_value_to_json).collect())
+        }
+        toml_edit::Value::InlineTable(table) => Value::Object(
+            table
+                .iter()
+                .map(|(k, v)| (k.to_string(), toml_value_to_json(v)))
+                .collect(),
+        ),
+    }
+}
+
+fn to_toml(value: &Value) -> Result<toml_edit::Value> {
+    Ok(match value {
+        Value::String(s) => s.as_str().into(),
+        Value::Bool(b) => (*b).into(),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => i.into(),
+            None => n.as_f64().context("Unsupported number")?.into(),
+        },
+        Value::Array(items) => items
+            .iter()
+            .map(to_toml)
+            .collect::<Result<toml_edit::Array>>()?
+            .into(),
+        Value::Object(map) => {
+            let mut table = toml_edit::InlineTable::new();
+            for (k, v) in map {
+                table.insert(k, to_toml(v)?);
+            }
+            table.into()
+        }
+        Value::Null => anyhow::bail!("TOML has no null value"),
+    })
+}
+
+fn json_lookup<'a>(root: &'a JsonNode, path: &[&str]) -> Option<&'a JsonNode> {
+    let mut node = root;
+    for key in path {
+        match node {
+            JsonNode::Object { members, .. } => {
+                node = &members.iter().find(|m| m.key == *key)?.value;
+            }
+            _ => return None,
+        }
+    }
+    Some(node)
+}
+
+fn json_set(text: &str, path: &[&str], value: &Value) -> Result<String> {
+    let root = JsonNode::parse(text).context("Failed to parse JSON manifest")?;
+    let unit = json_indent_unit(text);
+
+    let mut node = &root;
+    for (depth, key) in path.iter().enumerate() {
+        let JsonNode::Object { range, members } = node else {
+            anyhow::bail!("'{}' is not an object", path[..depth].join("."));
+        };
+
+        let Some(member) = members.iter().find(|m| m.key == *key) else {
+            // Build the missing tail as nested objects and add it as a new member.
+            let nested = path[depth + 1..]
+                .iter()
+                .rev()
+                .fold(value.clone(), |acc, key| {
+                    Value::Object([(key.to_string(), acc)].into_iter().collect())
+                });
+            return Ok(json_insert_member(
+                text, range, members, key, &nested, &unit,
+            ));
+        };
+        node = &member.value;
+    }
+
+    let range = node.range();
+    let indent = line_indent(text, range.start);
+    Ok(splice(text, range, &json_render(value, indent, &unit)))
+}
+
+fn json_insert_member(
+    text: &str,
+    object: &Range<usize>,
+    members: &[crate::source::JsonMember],
+    key: &str,
+    value: &Value,
+    unit: &str,
+) -> String {
+    let key = Value::String(key.to_string()).to_string();
+
+    match members.last() {
+        None => {
+            let indent = line_indent(text, object.start);
+            let inner = format!("{}{}", indent, unit);
+            let rendered = json_render(value, &inner, unit);
+            splice(
+                text,
+                object.clone(),
+                &format!("{{\n{}{}: {}\n{}}}", inner, key, rendered, indent),
+            )
+        }
+        Some(last) => {
+            let end = last.value.range().end;
+            let on_one_line = !text[object.start..end].contains('\n');
+            let insertion = if on_one_line {
+                format!(", {}: {}", key, json_render(value, "", unit))
+            } else {
+                let indent = line_indent(text, last.key_range.start);
+                format!(",\n{}{}: {}", indent, key, json_render(value, indent, unit))
+            };
+            splice(text, end..end, &insertion)
+        }
+    }
+}
+
+fn json_remove(text: &str, path: &[&str]) -> Result<String> {
+    let root = JsonNode::parse(text).context("Failed to parse JSON manifest")?;
+    let (last, parents) = path.split_last().expect("non-empty path");
+
+    let Some(JsonNode::Object { range, members }) = json_lookup(&root, parents) else {
+        anyhow::bail!("'{}' is not an object", parents.join("."));
+    };
+    let index = members
+        .iter()
+        .position(|m| m.key == *last)
+        .context("Key not found")?;
+
+    let removed = if members.len() == 1 {
+        range.start + 1..range.end - 1
+    } else if index == 0 {
+        members[0].key_range.start..members[1].key_range.start
+    } else {
+        members[index - 1].value.range().end..members[index].value.range().end
+    };
+    Ok(splice(text, removed, ""))
+}
+
+/// Pretty-print `value`, continuing lines at `indent`.
+fn json_render(value: &Value, indent: &str, unit: &str) -> String {
+    let mut out = Vec::new();
+    let formatter = serde_json::ser::PrettyFormatter::with_indent(unit.as_bytes());
+    let mut serializer = serde_json::Serializer::with_formatter(&mut out, formatter);
+    serde::Serialize::serialize(value, &mut serializer).expect("JSON values always serialize");
+
+    String::from_utf8(out)
+        .expect("serde_json emits UTF-8")
+        .replace('\n', &format!("\n{}", indent))
+}
+
+/// The indentation step used by the document (tab or spaces), defaulting to two spaces.
+fn json_indent_unit(text: &str) -> String {
+    text.lines()
+        .map(|line| &line[..line.len() - line.trim_start().len()])
+        .find(|indent| !indent.is_empty())
+        .unwrap_or("  ")
+        .to_string()
+}
+
+fn line_indent(text: &str, pos: usize) -> &str {
+    let start = text[..pos].rfind('\n').map_or(0, |i| i + 1);
+    let line = &text[start..pos];
+    &line[..line.len() - line.trim_start().len()]
+}
+
+fn splice(text: &str, range: Range<usize>, replacement: &str) -> String {
+    format!(
+        "{}{}{}",
+        &text[..range.start],
+        replacement,
+        &text[range.end..]
+    )
+}
+
+/// A block-style mapping entry: its key line and the lines of its nested block.
+#[derive(Debug, Clone)]
+struct YamlEntry {
+    line: usize,
+    indent: usize,
+    /// One past the last significant (non-blank, non-comment) line of the entry.
+    end: usize,
+    /// Whatever follows the colon on the key line.
+    inline_value: String,
+}
+
+impl YamlEntry {
+    /// The inline value without any trailing comment.
+    fn value(&self) -> &str {
+        let value = self.inline_value.as_str();
+        if value.starts_with('#') {
+            return "";
+        }
+        value.find(" #").map_or(value, |i| value[..i].trim_end())
+    }
+}
+
+fn yaml_lines(text: &str) -> Vec<&str> {
+    text.lines().collect()
+}
+
+fn yaml_significant(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    !trimmed.is_empty() && !trimmed.starts_with('#') && !trimmed.starts_with("---")
+}
+
+fn yaml_indent(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// The mapping entries directly inside `range`.
+fn yaml_children(lines: &[&str], range: Range<usize>) -> Vec<(String, YamlEntry)> {
+    let Some(indent) = lines[range.clone()]
+        .iter()
+        .find(|l| yaml_significant(l))
+        .map(|l| yaml_indent(l))
+    else {
+        return Vec::new();
+    };
+
+    let mut entries: Vec<(String, YamlEntry)> = Vec::new();
+    for i in range {
+        let line = lines[i];
+        if !yaml_significant(line) {
+            continue;
+        }
+        let line_indent = yaml_indent(line);
+        if line_indent == indent {
+            if let Some((key, rest)) = yaml_key(&line[line_indent..]) {
+                entries.push((
+                    key,
+                    YamlEntry {
+                        line: i,
+                        indent,
+                        end: i + 1,
+                        inline_value: rest[1..].trim().to_string(),
+                    },
+                ));
+                continue;
+            }
+        }
+        if line_indent >= indent {
+            if let Some((_, entry)) = entries.last_mut() {
+                entry.end = i + 1;
+            }
+        }
+    }
+    entries
+}
+
+/// Parse the value of a mapping entry, whether written inline or as a nested block.
+fn yaml_entry_value(lines: &[&str], entry: &YamlEntry) -> Option<Value> {
+    let inline = entry.value();
+    if !inline.is_empty() && !inline.starts_with('|') && !inline.starts_with('>') {
+        return serde_yaml::from_str(inline).ok();
+    }
+
+    // Re-parse the entry as a one-key document so block scalars keep their meaning.
+    let block: Vec<&str> = lines[entry.line..entry.end]
+        .iter()
+        .map(|l| l.get(entry.indent..).unwrap_or(""))
+        .collect();
+    let document: serde_yaml::Mapping = serde_yaml::from_str(&block.join("\n")).ok()?;
+    let (_, value) = document.into_iter().next()?;
+    serde_yaml::from_value(value).ok()
+}
+
+fn yaml_lookup(lines: &[&str], path: &[&str]) -> Option<YamlEntry> {
+    let mut range = 0..lines.len();
+    let mut found = None;
+    for key in path {
+        let (_, entry) = yaml_children(lines, range)
+            .into_iter()
+            .find(|(k, _)| k == key)?;
+        range = entry.line + 1..entry.end;
+        found = Some(entry);
+    }
+    found
+}
+
+fn yaml_scalar(value: &Value) -> String {
+    match value {
+        Value::Array(_) | Value::Object(_) => value.to_string(),
+        _ => serde_yaml::to_string(value)
+            .map(|s| s.trim_end().to_string())
+            .unwrap_or_else(|_| value.to_string()),
+    }
+}
+
+fn yaml_key_text(key: &str) -> String {
+    yaml_scalar(&Value::String(key.to_string()))
+}
+
+fn yaml_join(lines: Vec<String>, original: &str) -> String {
+    let mut out = lines.join("\n");
+    if original.ends_with('\n') {
+        out.push('\n');
+    }
+    out
+}
+
+fn yaml_set(text: &str, path: &[&str], value: &Value) -> Result<String> {
+    let lines = yaml_lines(text);
+    let mut out: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+
+    // Walk down as far as the document goes.
+    let mut range = 0..lines.len();
+    let mut parent: Option<YamlEntry> = None;
+    let mut depth = 0;
+    while depth < path.len() {
+        match yaml_children(&lines, range.clone())
+            .into_iter()
+            .find(|(k, _)| k == path[depth])
+        {
+            Some((_, entry)) => {
+                range = entry.line + 1..entry.end;
+                parent = Some(entry);
+                depth += 1;
+            }
+            None => break,
+        }
+    }
+
+    if depth == path.len() {
+        let entry = parent.expect("path is non-empty");
+        let key_line = lines[entry.line];
+        let key_end = key_line.len() - entry.inline_value.len();
+        let key_part = key_line[..key_end].trim_end();
+        let comment = entry
+            .inline_value
+            .find(" #")
+            .map_or("", |i| &entry.inline_value[i..]);
+        out.splice(
+            entry.line..entry.end,
+            [format!("{} {}{}", key_part, yaml_scalar(value), comment)],
+        );
+        return Ok(yaml_join(out, text));
+    }
+
+    let (insert_at, indent) = match &parent {
+        Some(entry) => {
+            if !entry.value().is_empty() {
+                anyhow::ensure!(
+                    matches!(entry.value(), "{}" | "~" | "null"),
+                    "'{}' uses flow style and can't be edited in place",
+                    path[..depth].join(".")
+                );
+                let key_line = lines[entry.line];
+                let key_end = key_line.len() - entry.inline_value.len();
+                out[entry.line] = key_line[..key_end].trim_end().to_string();
+            }
+            let indent = yaml_children(&lines, range.clone())
+                .first()
+                .map_or(entry.indent + 2, |(_, child)| child.indent);
+            (entry.end, indent)
+        }
+        None => {
+            let end = lines
+                .iter()
+                .rposition(|l| yaml_significant(l))
+                .map_or(0, |i| i + 1);
+            (end, 0)
+        }
+    };
+
+    let mut new_lines = Vec::new();
+    for (i, key) in path[depth..].iter().enumerate() {
+        let pad = " ".repeat(indent + i * 2);
+        if depth + i + 1 == path.len() {
+            new_lines.push(format!(
+                "{}{}: {}",
+                pad,
+                yaml_key_text(key),
+                yaml_scalar(value)
+            ));
+        } else {
+            new_lines.push(format!("{}{}:", pad, yaml_key_text(key)));
+        }
+    }
+    out.splice(insert_at..insert_at, new_lines);
+    Ok(yaml_join(out, text))
+}
+
+fn yaml_remove(text: &str, path: &[&str]) -> String {
+    let lines = yaml_lines(text);
+    let mut out: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+    let entry = yaml_lookup(&lines, path).expect("caller checked the path exists");
+    out.drain(entry.line..entry.end);
+
+    // An emptied mapping would read back as null; keep it an empty map instead.
+    let (_, parents) = path.split_last().expect("non-empty path");
+    if let Some(parent) = yaml_lookup(&lines, parents).filter(|_| !parents.is_empty()) {
+        if yaml_children(&lines, parent.line + 1..parent.end).len() == 1 {
+            out[parent.line].push_str(" {}");
+        }
+    }
+    yaml_join(out, text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_toml_edits_keep_comments() {
+        let mut editor = ManifestEditor::new(
+            "# My project\n[dependencies]\nnode = \"^18\" # LTS\npython = { version = \"^3.11\", manager = \"pyenv\" }\n",
+            ManifestFormat::Toml,
+        )
+        .unwrap();
+
+        editor
+            .set_dependency(&["dependencies"], "node", "^20")
+            .unwrap();
+        editor
+            .set_dependency(&["dependencies"], "python", "^3.12")
+            .unwrap();
+        editor
+            .set_dependency(&["dev-dependencies"], "eslint", "^8")
+            .unwrap();
+        editor.set(&["env", "FOO"], "bar").unwrap();
+
+        assert_eq!(
+            editor.to_string(),
+            "# My project\n[dependencies]\nnode = \"^20\" # LTS\npython = { version = \"^3.12\", manager = \"pyenv\" }\n\n[dev-dependencies]\neslint = \"^8\"\n\n[env]\nFOO = \"bar\"\n"
+        );
+
+        assert!(editor.remove(&["dependencies", "python"]).unwrap());
+        assert!(!editor.remove(&["dependencies", "missing"]).unwrap());
+        assert!(!editor.contains(&["dependencies", "python"]));
+        editor.source().parse().unwrap();
+    }
+
+    #[test]
+    fn test_json_edits() {
+        let mut editor = ManifestEditor::new(
+            "{\n  \"dependencies\": {\n    \"node\": \"^18\"\n  }\n}\n",
+            ManifestFormat::Json,
+        )
+        .unwrap();
+
+        editor
+            .set_dependency(&["dependencies"], "node", "^20")
+            .unwrap();
+        editor.set(&["dependencies", "python"], "^3.11").unwrap();
+        editor.set(&["env", "FOO"], "bar").unwrap();
+        assert_eq!(
+            editor.to_string(),
+            "{\n  \"dependencies\": {\n    \"node\": \"^20\",\n    \"python\": \"^3.11\"\n  },\n  \"env\": {\n    \"FOO\": \"bar\"\n  }\n}\n"
+        );
+
+        editor.remove(&["dependencies", "node"]).unwrap();
+        editor.remove(&["env", "FOO"]).unwrap();
+        assert_eq!(
+            editor.to_string(),
+            "{\n  \"dependencies\": {\n    \"python\": \"^3.11\"\n  },\n  \"env\": {}\n}\n"
+        );
+        editor.source().parse().unwrap();
+    }
+
+    #[test]
+    fn test_yaml_edits_keep_comments() {
+        let mut editor = ManifestEditor::new(
+            "# My project\ndependencies:\n  node: ^18 # LTS\n\nscripts:\n  build: |\n    cargo build\n",
+            ManifestFormat::Yaml,
+        )
+        .unwrap();
+
+        editor
+            .set_dependency(&["dependencies"], "node", "^20")
+            .unwrap();
+        editor.set(&["dev-dependencies", "eslint"], "^8").unwrap();
+        editor.set(&["scripts", "test"], "cargo test").unwrap();
+        assert_eq!(
+            editor.to_string(),
+            "# My project\ndependencies:\n  node: ^20 # LTS\n\nscripts:\n  build: |\n    cargo build\n  test: cargo test\ndev-dependencies:\n  eslint: ^8\n"
+        );
+
+        editor.remove(&["dev-dependencies", "eslint"]).unwrap();
+        assert!(editor.to_string().ends_with("dev-dependencies: {}\n"));
+        editor.source().parse().unwrap();
+    }
+
+    #[test]
+    fn test_get_and_rename() {
+        let cases = [
+            (
+                "# legacy\n[plugin]\nname = \"demo\" # keep\n",
+                ManifestFormat::Toml,
+                "# legacy\n[project]\nname = \"demo\" # keep\n",
+            ),
+            (
+                "{\n  \"plugin\": { \"name\": \"demo\" }\n}",
+                ManifestFormat::Json,
+                "{\n  \"project\": { \"name\": \"demo\" }\n}",
+            ),
+            (
+                "plugin: # legacy\n  name: demo\n",
+                ManifestFormat::Yaml,
+                "project: # legacy\n  name: demo\n",
+            ),
+        ];
+
+        for (input, format, expected) in cases {
+            let mut editor = ManifestEditor::new(input, format).unwrap();
+            assert_eq!(editor.get(&["plugin", "name"]), Some(Value::from("demo")));
+            assert!(editor.rename(&["plugin"], "project").unwrap());
+            assert_eq!(editor.to_string(), expected);
+            assert_eq!(
+                editor.get(&["project"]),
+                Some(serde_json::json!({ "name": "demo" }))
+            );
+        }
+    }
+}
@@ -0,0 +1,334 @@
+use crate::{DependencySpec, EnhancedManifest, EnvValue, TargetPlatform, TargetSelector};
+use anyhow::Result;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Dependency sections that profiles can reference by name.
+const SECTION_DEPENDENCIES: &str = "dependencies";
+const SECTION_DEV: &str = "dev-dependencies";
+const SECTION_TEST: &str = "test-dependencies";
+const SECTION_BUILD: &str = "build-dependencies";
+
+/// The concrete dependency set and environment for one profile/extras/platform selection.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema, Default)]
+pub struct EffectiveManifest {
+    /// The profile that was applied, if any.
+    pub profile: Option<String>,
+
+    /// The extras that were activated.
+    pub extras: Vec<String>,
+
+    /// Every dependency that should be installed.
+    pub dependencies: HashMap<String, DependencySpec>,
+
+    /// Environment variables after profile overrides.
+    pub env: HashMap<String, EnvValue>,
+}
+
+impl EnhancedManifest {
+    /// Flatten profiles, groups, extras and target tables into a concrete selection.
+    ///
+    /// Production `dependencies` are always included. Without a profile (or with a profile
+    /// that lists no dependency sections) every dependency section and every non-optional
+    /// group is selected; otherwise only the sections and groups named by the profile are.
+    /// Groups in the profile's `exclude_groups` are dropped, optional dependencies are only
+    /// kept when an activated extra names them, and matching `[target.*]` tables are merged
+    /// last so platform-specific specs win.
+    pub fn effective(
+        &self,
+        profile: Option<&str>,
+        extras: &[String],
+        platform: &TargetPlatform,
+    ) -> Result<EffectiveManifest> {
+        let profile_def = match profile {
+            Some(name) => Some(
+                self.profiles
+                    .get(name)
+                    .ok_or_else(|| anyhow::anyhow!("Profile '{}' is not defined", name))?,
+            ),
+            None => None,
+        };
+
+        // Extras name either optional dependencies or optional groups.
+        let mut activated: Vec<&str> = Vec::new();
+        for extra in extras {
+            let members = self
+                .extras
+                .get(extra)
+                .ok_or_else(|| anyhow::anyhow!("Extra '{}' is not defined", extra))?;
+            activated.extend(members.iter().map(String::as_str));
+        }
+
+        let sections: Vec<&str> = match profile_def {
+            Some(p) if !p.dependencies.is_empty() => {
+                p.dependencies.iter().map(String::as_str).collect()
+            }
+            _ => {
+                let mut default = vec![SECTION_DEV, SECTION_TEST, SECTION_BUILD];
+                default.extend(
+                    self.group
+                        .iter()
+                        .filter(|(_, g)| !g.optional)
+                        .map(|(name, _)| name.as_str()),
+                );
+                default
+            }
+        };
+
+        let excluded: &[String] = profile_def
+            .map(|p| p.exclude_groups.as_slice())
+            .unwrap_or(&[]);
+
+        let mut dependencies = HashMap::new();
+        self.merge_dependencies(&mut dependencies, &self.dependencies, &activated);
+
+        for section in sections {
+            match section {
+                SECTION_DEPENDENCIES => {}
+                SECTION_DEV => {
+                    self.merge_dependencies(&mut dependencies, &self.dev_dependencies, &activated)
+                }
+                SECTION_TEST => {
+                    self.merge_dependencies(&mut dependencies, &self.test_dependencies, &activated)
+                }
+                SECTION_BUILD => {
+                    self.merge_dependencies(&mut dependencies, &self.build_dependencies, &activated)
+                }
+                group_name => {
+                    if excluded.iter().any(|e| e == group_name) {
+                        continue;
+                    }
+                    let group = self.group.get(group_name).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "Profile '{}' references non-existent dependency group '{}'",
+                            profile.unwrap_or_default(),
+                            group_name
+                        )
+                    })?;
+                    self.merge_dependencies(&mut dependencies, &group.dependencies, &activated);
+                }
+            }
+        }
+
+        // Optional groups pulled in by an extra.
+        for name in &activated {
+            if let Some(group) = self.group.get(*name) {
+                if !excluded.iter().any(|e| e == name) {
+                    self.merge_dependencies(&mut dependencies, &group.dependencies, &activated);
+                }
+            }
+        }
+
+        // Triple keys, then `cfg(...)` keys, each in key order: later tables win.
+        let mut targets = Vec::with_capacity(self.target.len());
+        for (key, target) in &self.target {
+            let selector = TargetSelector::parse(key)
+                .map_err(|e| anyhow::anyhow!("Invalid target '{}': {}", key, e))?;
+            targets.push((
+                matches!(selector, TargetSelector::Cfg(_)),
+                key,
+                selector,
+                target,
+            ));
+        }
+        targets.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+        for (_, _, selector, target) in targets {
+            if selector.matches(platform) {
+                self.merge_dependencies(&mut dependencies, &target.dependencies, &activated);
+            }
+        }
+
+        let mut env = self.env.clone();
+        if let Some(p) = profile_def {
+            env.extend(p.env.iter().map(|(k, v)| (k.clone(), v.clone())));
+        }
+
+        Ok(EffectiveManifest {
+            profile: profile.map(str::to_string),
+            extras: extras.to_vec(),
+            dependencies,
+            env,
+        })
+    }
+
+    fn merge_dependencies(
+        &self,
+        into: &mut HashMap<String, DependencySpec>,
+        from: &HashMap<String, DependencySpec>,
+        activated: &[&str],
+    ) {
+        for (name, spec) in from {
+            let optional = matches!(spec, DependencySpec::Detailed(d) if d.optional);
+            if optional && !activated.contains(&name.as_str()) {
+                continue;
+            }
+            into.insert(name.clone(), spec.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CpuArchitecture, OperatingSystem, TargetEnv};
+
+    fn manifest() -> EnhancedManifest {
+        toml::from_str(
+            r#"
+[dependencies]
+node = "^20.0.0"
+cuda = { version = "^12.0.0", optional = true }
+
+[dev-dependencies]
+eslint = "^8.0.0"
+
+[test-dependencies]
+jest = "^29.0.0"
+
+[group.docs]
+dependencies = { mkdocs = "^1.5.0" }
+
+[group.ml]
+optional = true
+dependencies = { torch = "^2.0.0" }
+
+[target.linux.dependencies]
+node = "^18.0.0"
+
+[target.x86_64-apple-darwin.dependencies]
+xcode = "*"
+
+[target.'cfg(all(target_os = "linux", target_arch = "aarch64"))'.dependencies]
+gcc-aarch64 = "*"
+
+[target.'cfg(libc = "musl")'.dependencies]
+musl-tools = "*"
+
+[profiles.ci]
+description = "CI"
+dependencies = ["test-dependencies", "docs"]
+exclude_groups = ["docs"]
+
+[profiles.ci.env]
+CI = "true"
+
+[env]
+CI = "false"
+LOG = "info"
+
+[extras]
+gpu = ["cuda", "ml"]
+"#,
+        )
+        .unwrap()
+    }
+
+    fn linux() -> TargetPlatform {
+        TargetPlatform::new(OperatingSystem::Linux, CpuArchitecture::X86_64)
+    }
+
+    fn macos() -> TargetPlatform {
+        TargetPlatform::new(OperatingSystem::Macos, CpuArchitecture::X86_64)
+    }
+
+    #[test]
+    fn test_default_selection() {
+        let effective = manifest().effective(None, &[], &macos()).unwrap();
+        let mut names: Vec<_> = effective.dependencies.keys().cloned().collect();
+        names.sort();
+
+        assert_eq!(names, vec!["eslint", "jest", "mkdocs", "node", "xcode"]);
+        assert_eq!(effective.env.get("CI"), Some(&EnvValue::from("false")));
+    }
+
+    #[test]
+    fn test_profile_and_extras() {
+        let effective = manifest()
+            .effective(Some("ci"), &["gpu".to_string()], &macos())
+            .unwrap();
+        let mut names: Vec<_> = effective.dependencies.keys().cloned().collect();
+        names.sort();
+
+        assert_eq!(names, vec!["cuda", "jest", "node", "torch", "xcode"]);
+        assert_eq!(effective.env.get("CI"), Some(&EnvValue::from("true")));
+        assert_eq!(effective.env.get("LOG"), Some(&EnvValue::from("info")));
+    }
+
+    #[test]
+    fn test_target_overrides() {
+        let effective = manifest().effective(None, &[], &linux()).unwrap();
+
+        assert!(!effective.dependencies.contains_key("xcode"));
+        match &effective.dependencies["node"] {
+            DependencySpec::Simple(req) => assert_eq!(req.to_string(), "^18.0.0"),
+            _ => panic!("Expected Simple"),
+        }
+    }
+
+    #[test]
+    fn test_cfg_targets() {
+        let arm_musl = TargetPlatform::new(OperatingSystem::Linux, CpuArchitecture::Aarch64)
+            .with_env(TargetEnv::Musl);
+        let effective = manifest().effective(None, &[], &arm_musl).unwrap();
+        assert!(effective.dependencies.contains_key("gcc-aarch64"));
+        assert!(effective.dependencies.contains_key("musl-tools"));
+
+        let effective = manifest().effective(None, &[], &linux()).unwrap();
+        assert!(!effective.dependencies.contains_key("gcc-aarch64"));
+        assert!(!effective.dependencies.contains_key("musl-tools"));
+
+        let mut broken = manifest();
+        broken
+            .target
+            .insert("cfg(all(unix)".to_string(), broken.target["linux"].clone());
+        assert!(broken.effective(None, &[], &linux()).is_err());
+    }
+
+    #[test]
+    fn test_overlapping_targets() {
+        let mut manifest = manifest();
+        for (key, node) in [
+            ("cfg(unix)", "^22.0.0"),
+            ("x86_64-unknown-linux-gnu", "^19.0.0"),
+            ("cfg(target_os = \"linux\")", "^21.0.0"),
+        ] {
+            let mut target = manifest.target["linux"].clone();
+            target.dependencies.insert(
+                "node".to_string(),
+                DependencySpec::Simple(node.parse().unwrap()),
+            );
+            manifest.target.insert(key.to_string(), target);
+        }
+
+        // `cfg(unix)` sorts last among the cfg keys, and cfg keys apply after triples.
+        for _ in 0..10 {
+            let effective = manifest.effective(None, &[], &linux()).unwrap();
+            assert_eq!(
+                effective.dependencies["node"].constraint().as_str(),
+                "^22.0.0"
+            );
+        }
+        manifest.target.remove("cfg(unix)");
+        let effective = manifest.effective(None, &[], &linux()).unwrap();
+        assert_eq!(
+            effective.dependencies["node"].constraint().as_str(),
+            "^21.0.0"
+        );
+        manifest.target.remove("cfg(target_os = \"linux\")");
+        let effective = manifest.effective(None, &[], &linux()).unwrap();
+        assert_eq!(
+            effective.dependencies["node"].constraint().as_str(),
+            "^19.0.0"
+        );
+    }
+
+    #[test]
+    fn test_unknown_selection() {
+        let m = manifest();
+        assert!(m.effective(Some("missing"), &[], &linux()).is_err());
+        assert!(m
+            .effective(None, &["missing".to_string()], &linux())
+            .is_err());
+    }
+}
@@ -0,0 +1,553 @@
+use crate::{InterpolationContext, InterpolationError, Interpolator};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// An environment variable value.
+///
+/// A plain string replaces the variable. The detailed form edits the inherited value
+/// instead, which is what list variables such as `PATH` or `PYTHONPATH` need:
+///
+/// ```toml
+/// [env]
+/// PATH = { prepend = ["${project.root}/bin"] }
+/// PYTHONPATH = { append = ["src"] }
+/// RUST_LOG = { default = "info" }
+/// NODE_OPTIONS = { unset = true }
+/// NPM_TOKEN = { secret = "npm-token" }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
+#[serde(untagged)]
+pub enum EnvValue {
+    /// Literal value (replaces any inherited value).
+    Literal(String),
+
+    /// Edit of the inherited value.
+    Detailed(EnvValueDetails),
+
+    /// Value read from a secret store when the environment is built.
+    Secret(SecretRef),
+}
+
+/// Where a secret value comes from.
+///
+/// `secret` names an entry in the OS keyring or the project's encrypted secrets file
+/// (managed with `env-architect secrets`); `secret-file` reads a file instead.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default, JsonSchema)]
+#[serde(deny_unknown_fields, rename_all = "kebab-case")]
+pub struct SecretRef {
+    /// Secret name in the keyring or project secrets file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+
+    /// Path of a file holding the value (relative to the project root).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret_file: Option<String>,
+}
+
+impl SecretRef {
+    /// Problems with this reference, if any.
+    pub fn conflicts(&self) -> Option<&'static str> {
+        match (&self.secret, &self.secret_file) {
+            (Some(_), Some(_)) => Some("'secret' cannot be combined with 'secret-file'"),
+            (None, None) => Some("expected one of secret or secret-file"),
+            (Some(name), None) if name.trim().is_empty() => Some("secret name cannot be empty"),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SecretRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.secret, &self.secret_file) {
+            (Some(name), _) => write!(f, "<secret {}>", name),
+            (None, Some(path)) => write!(f, "<secret file {}>", path),
+            (None, None) => write!(f, "<secret>"),
+        }
+    }
+}
+
+/// Looks up the values of `{ secret = ... }` entries.
+pub trait SecretResolver {
+    fn resolve_secret(&self, secret: &SecretRef) -> Result<String, String>;
+}
+
+/// Resolver for contexts without secret access; every lookup fails.
+pub struct NoSecrets;
+
+impl SecretResolver for NoSecrets {
+    fn resolve_secret(&self, _secret: &SecretRef) -> Result<String, String> {
+        Err("secrets are not available in this context".to_string())
+    }
+}
+
+/// What unresolved secrets, and secret values in masked output, read as.
+pub const SECRET_MASK: &str = "********";
+
+/// Values shorter than this are not masked; masking every `1` or `on` in the output
+/// would hide more than it protects.
+const MIN_MASKED_LEN: usize = 4;
+
+/// Replace every occurrence of the given secret values in `text` with [`SECRET_MASK`].
+pub fn mask_secrets(text: &str, secrets: &[String]) -> String {
+    let mut secrets: Vec<&String> = secrets
+        .iter()
+        .filter(|s| s.len() >= MIN_MASKED_LEN)
+        .collect();
+    // Longest first, so a secret containing another is masked whole.
+    secrets.sort_by_key(|s| std::cmp::Reverse(s.len()));
+
+    secrets.into_iter().fold(text.to_string(), |out, secret| {
+        out.replace(secret.as_str(), SECRET_MASK)
+    })
+}
+
+/// Detailed environment variable operations.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EnvValueDetails {
+    /// Entries to add in front of the inherited value.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prepend: Vec<String>,
+
+    /// Entries to add after the inherited value.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub append: Vec<String>,
+
+    /// Remove the variable from the environment.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub unset: bool,
+
+    /// Value to use only when the variable is not already set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<String>,
+
+    /// List separator (defaults to the platform path separator).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub separator: Option<String>,
+}
+
+impl EnvValue {
+    /// Apply this value to the inherited value of the variable.
+    /// Returns `None` when the variable should be removed.
+    ///
+    /// Secrets read as [`SECRET_MASK`] here; [`EnvironmentBuilder`] resolves them first.
+    pub fn apply(&self, inherited: Option<&str>) -> Option<String> {
+        match self {
+            EnvValue::Literal(value) => Some(value.clone()),
+            EnvValue::Detailed(details) => details.apply(inherited),
+            EnvValue::Secret(_) => Some(SECRET_MASK.to_string()),
+        }
+    }
+}
+
+impl EnvValueDetails {
+    pub fn apply(&self, inherited: Option<&str>) -> Option<String> {
+        if self.unset {
+            return None;
+        }
+
+        let base = match inherited.filter(|v| !v.is_empty()) {
+            Some(value) => Some(value.to_string()),
+            None => self.default.clone(),
+        };
+
+        if self.prepend.is_empty() && self.append.is_empty() {
+            return base;
+        }
+
+        let separator = self.separator.as_deref().unwrap_or(PATH_SEPARATOR);
+        let added: Vec<&str> = self
+            .prepend
+            .iter()
+            .chain(self.append.iter())
+            .map(String::as_str)
+            .collect();
+
+        let mut parts: Vec<&str> = self.prepend.iter().map(String::as_str).collect();
+        if let Some(base) = base.as_deref() {
+            // Drop entries we are about to add so re-activation doesn't grow the list.
+            parts.extend(
+                base.split(separator)
+                    .filter(|p| !p.is_empty() && !added.contains(p)),
+            );
+        }
+        parts.extend(self.append.iter().map(String::as_str));
+
+        Some(parts.join(separator))
+    }
+
+    /// Problems with this combination of operations, if any.
+    pub fn conflicts(&self) -> Option<&'static str> {
+        if self.unset
+            && (!self.prepend.is_empty() || !self.append.is_empty() || self.default.is_some())
+        {
+            return Some("'unset' cannot be combined with prepend, append or default");
+        }
+        if !self.unset
+            && self.prepend.is_empty()
+            && self.append.is_empty()
+            && self.default.is_none()
+        {
+            return Some("expected one of prepend, append, unset or default");
+        }
+        None
+    }
+}
+
+impl From<String> for EnvValue {
+    fn from(value: String) -> Self {
+        EnvValue::Literal(value)
+    }
+}
+
+impl From<&str> for EnvValue {
+    fn from(value: &str) -> Self {
+        EnvValue::Literal(value.to_string())
+    }
+}
+
+impl fmt::Display for EnvValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvValue::Literal(value) => write!(f, "{}", value),
+            EnvValue::Detailed(details) => {
+                let mut ops = Vec::new();
+                if details.unset {
+                    ops.push("unset".to_string());
+                }
+                if let Some(default) = &details.default {
+                    ops.push(format!("default {}", default));
+                }
+                if !details.prepend.is_empty() {
+                    ops.push(format!("prepend {}", details.prepend.join(", ")));
+                }
+                if !details.append.is_empty() {
+                    ops.push(format!("append {}", details.append.join(", ")));
+                }
+                write!(f, "<{}>", ops.join("; "))
+            }
+            EnvValue::Secret(secret) => write!(f, "{}", secret),
+        }
+    }
+}
+
+#[cfg(windows)]
+const PATH_SEPARATOR: &str = ";";
+#[cfg(not(windows))]
+const PATH_SEPARATOR: &str = ":";
+
+/// The final environment for a child process.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResolvedEnv {
+    /// Every variable the child should see.
+    pub vars: HashMap<String, String>,
+
+    /// Variables removed by `unset`.
+    pub removed: Vec<String>,
+
+    /// Resolved secret values, for masking output.
+    pub secrets: Vec<String>,
+}
+
+impl ResolvedEnv {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.vars.get(key).map(String::as_str)
+    }
+
+    /// Mask any secret value appearing in `text`.
+    pub fn mask(&self, text: &str) -> String {
+        mask_secrets(text, &self.secrets)
+    }
+
+    /// Apply to a command about to be spawned.
+    pub fn apply(&self, command: &mut std::process::Command) {
+        command.envs(&self.vars);
+        for key in &self.removed {
+            command.env_remove(key);
+        }
+    }
+}
+
+/// Layers env maps on top of an inherited environment.
+///
+/// Each layer is interpolated against the result of the layers below it, so a later
+/// `PATH = { prepend = [...] }` sees the `PATH` produced by earlier layers. This is the one
+/// place that turns manifest `env` tables into a process environment; `shell`, `run`, shims
+/// and services all go through it.
+#[derive(Debug, Clone)]
+pub struct EnvironmentBuilder {
+    base: HashMap<String, String>,
+    layers: Vec<HashMap<String, EnvValue>>,
+}
+
+impl EnvironmentBuilder {
+    /// Start from an explicit environment.
+    pub fn new(base: HashMap<String, String>) -> Self {
+        Self {
+            base,
+            layers: Vec::new(),
+        }
+    }
+
+    /// Start from the current process environment.
+    pub fn from_process() -> Self {
+        Self::new(std::env::vars().collect())
+    }
+
+    /// Add a layer of env values.
+    pub fn layer(mut self, env: &HashMap<String, EnvValue>) -> Self {
+        if !env.is_empty() {
+            self.layers.push(env.clone());
+        }
+        self
+    }
+
+    /// Add a literal variable in its own layer.
+    pub fn set(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.layers.push(HashMap::from([(
+            key.into(),
+            EnvValue::Literal(value.into()),
+        )]));
+        self
+    }
+
+    /// Prepend a single entry to a list variable in its own layer.
+    pub fn prepend(mut self, key: impl Into<String>, entry: impl Into<String>) -> Self {
+        let value = EnvValue::Detailed(EnvValueDetails {
+            prepend: vec![entry.into()],
+            ..Default::default()
+        });
+        self.layers.push(HashMap::from([(key.into(), value)]));
+        self
+    }
+
+    /// Interpolate and apply every layer. Fails on any secret entry; see
+    /// [`Self::build_with_secrets`].
+    pub fn build(&self, ctx: &InterpolationContext) -> Result<ResolvedEnv, InterpolationError> {
+        self.build_with_secrets(ctx, &NoSecrets)
+    }
+
+    /// Interpolate and apply every layer, reading secret entries through `secrets`.
+    ///
+    /// Secret values are taken verbatim (a `${` inside one is not a reference) and are
+    /// listed in [`ResolvedEnv::secrets`] so callers can mask them.
+    pub fn build_with_secrets(
+        &self,
+        ctx: &InterpolationContext,
+        secrets: &dyn SecretResolver,
+    ) -> Result<ResolvedEnv, InterpolationError> {
+        let mut vars = self.base.clone();
+        let mut removed: Vec<String> = Vec::new();
+        let mut resolved_secrets: Vec<String> = Vec::new();
+
+        for layer in &self.layers {
+            let layer_ctx = ctx.clone().with_process_env(vars.clone());
+            let layer = Self::resolve_secrets(layer, &layer_ctx, secrets, &mut resolved_secrets)?;
+            let mut interpolator = Interpolator::new(&layer_ctx, &layer);
+
+            for (key, value) in interpolator.resolve_env()? {
+                match value {
+                    Some(value) => {
+                        removed.retain(|k| k != &key);
+                        vars.insert(key, value);
+                    }
+                    None => {
+                        vars.remove(&key);
+                        if !removed.contains(&key) {
+                            removed.push(key);
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(ResolvedEnv {
+            vars,
+            removed,
+            secrets: resolved_secrets,
+        })
+    }
+
+    /// Replace the layer's secret entries with their (escaped) literal values.
+    fn resolve_secrets(
+        layer: &HashMap<String, EnvValue>,
+        ctx: &InterpolationContext,
+        secrets: &dyn SecretResolver,
+        resolved: &mut Vec<String>,
+    ) -> Result<HashMap<String, EnvValue>, InterpolationError> {
+        let mut out = layer.clone();
+        for (key, value) in layer {
+            let EnvValue::Secret(secret) = value else {
+                continue;
+            };
+            let field = format!("env.{}", key);
+            let mut secret = secret.clone();
+            if let Some(path) = &secret.secret_file {
+                let path = Interpolator::new(ctx, layer).resolve_str(path, &field)?;
+                secret.secret_file = Some(path);
+            }
+
+            let value =
+                secrets
+                    .resolve_secret(&secret)
+                    .map_err(|message| InterpolationError::Secret {
+                        field: field.clone(),
+                        message,
+                    })?;
+            out.insert(key.clone(), EnvValue::Literal(value.replace("${", "$${")));
+            resolved.push(value);
+        }
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProjectMetadata;
+    use std::path::Path;
+
+    fn base() -> HashMap<String, String> {
+        [("PATH", "/usr/bin:/bin"), ("NODE_OPTIONS", "--inspect")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    fn ctx() -> InterpolationContext {
+        InterpolationContext::new(&ProjectMetadata::default(), Path::new("/work"))
+            .with_process_env(base())
+    }
+
+    #[test]
+    fn test_parse_detailed_forms() {
+        let env: HashMap<String, EnvValue> = toml::from_str(
+            r#"
+PATH = { prepend = ["bin"], separator = ":" }
+LOG = { default = "info" }
+GONE = { unset = true }
+PLAIN = "value"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(env["PLAIN"], EnvValue::from("value"));
+        assert!(matches!(&env["GONE"], EnvValue::Detailed(d) if d.unset));
+        assert!(matches!(&env["LOG"], EnvValue::Detailed(d) if d.conflicts().is_none()));
+        assert!(toml::from_str::<HashMap<String, EnvValue>>("X = { prepnd = [\"a\"] }").is_err());
+    }
+
+    #[test]
+    fn test_apply_operations() {
+        let prepend = EnvValueDetails {
+            prepend: vec!["/opt/bin".to_string()],
+            append: vec!["/usr/bin".to_string()],
+            separator: Some(":".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            prepend.apply(Some("/usr/bin:/bin")).as_deref(),
+            Some("/opt/bin:/bin:/usr/bin")
+        );
+        assert_eq!(prepend.apply(None).as_deref(), Some("/opt/bin:/usr/bin"));
+
+        let default = EnvValueDetails {
+            default: Some("info".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(default.apply(Some("debug")).as_deref(), Some("debug"));
+        assert_eq!(default.apply(None).as_deref(), Some("info"));
+    }
+
+    #[test]
+    fn test_builder_layers() {
+        let manifest_env: HashMap<String, EnvValue> = toml::from_str(
+            r#"
+PATH = { prepend = ["${project.root}/bin"] }
+NODE_OPTIONS = { unset = true }
+TOOLS = "${PATH}"
+"#,
+        )
+        .unwrap();
+
+        let mut inherited = base();
+        inherited.insert(
+            "PATH".to_string(),
+            ["/usr/bin", "/bin"].join(PATH_SEPARATOR),
+        );
+
+        let resolved = EnvironmentBuilder::new(inherited.clone())
+            .layer(&manifest_env)
+            .prepend("PATH", "/work/.architect/shims")
+            .build(&ctx().with_process_env(inherited))
+            .unwrap();
+
+        let expected = ["/work/.architect/shims", "/work/bin", "/usr/bin", "/bin"];
+        assert_eq!(
+            resolved.get("PATH"),
+            Some(expected.join(PATH_SEPARATOR).as_str())
+        );
+        assert_eq!(
+            resolved.get("TOOLS"),
+            Some(expected[1..].join(PATH_SEPARATOR).as_str())
+        );
+        assert_eq!(resolved.get("NODE_OPTIONS"), None);
+        assert_eq!(resolved.removed, vec!["NODE_OPTIONS".to_string()]);
+    }
+
+    struct Vault;
+
+    impl SecretResolver for Vault {
+        fn resolve_secret(&self, secret: &SecretRef) -> Result<String, String> {
+            match (secret.secret.as_deref(), secret.secret_file.as_deref()) {
+                (Some("npm-token"), _) => Ok("npm_s3cr3t${HOME}".to_string()),
+                (_, Some("/work/.token")) => Ok("file-token".to_string()),
+                _ => Err("not found".to_string()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_secrets() {
+        let env: HashMap<String, EnvValue> = toml::from_str(
+            r#"
+NPM_TOKEN = { secret = "npm-token" }
+GH_TOKEN = { secret-file = "${project.root}/.token" }
+NPMRC = "//registry.npmjs.org/:_authToken=${NPM_TOKEN}"
+"#,
+        )
+        .unwrap();
+        assert_eq!(env["NPM_TOKEN"].to_string(), "<secret npm-token>");
+        assert!(toml::from_str::<HashMap<String, EnvValue>>(
+            "X = { secret = \"a\", secret-file = \"b\" }"
+        )
+        .map(|env| matches!(&env["X"], EnvValue::Secret(s) if s.conflicts().is_some()))
+        .unwrap());
+
+        let builder = EnvironmentBuilder::new(base()).layer(&env);
+        assert!(matches!(
+            builder.build(&ctx()),
+            Err(InterpolationError::Secret { .. })
+        ));
+
+        let resolved = builder.build_with_secrets(&ctx(), &Vault).unwrap();
+        // Secret values are never interpolated.
+        assert_eq!(resolved.get("NPM_TOKEN"), Some("npm_s3cr3t${HOME}"));
+        assert_eq!(resolved.get("GH_TOKEN"), Some("file-token"));
+        assert_eq!(
+            resolved.mask(resolved.get("NPMRC").unwrap()),
+            format!("//registry.npmjs.org/:_authToken={}", SECRET_MASK)
+        );
+
+        // Without a store, references read as the mask.
+        let manifest = crate::EnhancedManifest {
+            env,
+            ..Default::default()
+        };
+        let shown = manifest.interpolate(&ctx()).unwrap();
+        assert_eq!(shown.env["NPM_TOKEN"].to_string(), "<secret npm-token>");
+        assert!(shown.env["NPMRC"].to_string().ends_with(SECRET_MASK));
+    }
+}
@@ -0,0 +1,107 @@
+use crate::ValidationResult;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Interchangeable tools (`[families.containers] members = ["docker", "podman"]`).
+///
+/// A family named like a built-in one (`js-package-manager`, `python`, `nodejs`,
+/// `rust-compiler`, `git`, `make`) replaces it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ToolFamilyConfig {
+    /// Tools that can stand in for each other.
+    pub members: Vec<String>,
+
+    /// Most preferred first. Members left out follow in `members` order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prefer: Vec<String>,
+}
+
+impl ToolFamilyConfig {
+    /// Every member, most preferred first.
+    pub fn preference_order(&self) -> Vec<String> {
+        let mut order: Vec<String> = Vec::with_capacity(self.members.len());
+        for tool in self.prefer.iter().chain(&self.members) {
+            if !order.contains(tool) {
+                order.push(tool.clone());
+            }
+        }
+        order
+    }
+}
+
+/// Check `[families]`: members must be non-empty and unique, `prefer` may only name
+/// members, and a tool in two families gets a warning since their preferences can disagree.
+pub fn validate_families(
+    families: &HashMap<String, ToolFamilyConfig>,
+    result: &mut ValidationResult,
+) {
+    let families: BTreeMap<_, _> = families.iter().collect();
+    let mut owner: BTreeMap<&str, &str> = BTreeMap::new();
+
+    for (name, family) in &families {
+        let field = format!("families.{}", name);
+        if family.members.is_empty() {
+            result.add_error(
+                format!("{}.members", field),
+                format!("Family '{}' has no members", name),
+            );
+        }
+
+        for (i, tool) in family.members.iter().enumerate() {
+            if family.members[..i].contains(tool) {
+                result.add_error(
+                    format!("{}.members[{}]", field, i),
+                    format!("'{}' is listed twice in family '{}'", tool, name),
+                );
+                continue;
+            }
+            if let Some(other) = owner.insert(tool, name) {
+                result.add_warning(
+                    format!("{}.members[{}]", field, i),
+                    format!(
+                        "'{}' is also in family '{}'; their preferences may disagree",
+                        tool, other
+                    ),
+                );
+            }
+        }
+
+        for (i, tool) in family.prefer.iter().enumerate() {
+            if !family.members.contains(tool) {
+                result.add_error(
+                    format!("{}.prefer[{}]", field, i),
+                    format!("'{}' is not a member of family '{}'", tool, name),
+                );
+            } else if family.prefer[..i].contains(tool) {
+                result.add_error(
+                    format!("{}.prefer[{}]", field, i),
+                    format!("'{}' is listed twice in the preference order", tool),
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn family(members: &[&str], prefer: &[&str]) -> ToolFamilyConfig {
+        ToolFamilyConfig {
+            members: members.iter().map(|s| s.to_string()).collect(),
+            prefer: prefer.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_preference_order() {
+        let runtimes = family(&["docker", "podman", "nerdctl"], &["podman"]);
+        assert_eq!(runtimes.preference_order(), ["podman", "docker", "nerdctl"]);
+        assert_eq!(
+            family(&["npm", "yarn"], &[]).preference_order(),
+            ["npm", "yarn"]
+        );
+    }
+}
@@ -11,7 +11,7 @@ pub struct GlobalManifest {
     #[serde(default)]
     pub tools: HashMap<String, GlobalTool>,
 
-    /// Future: Track managed projects?
+    /// Project directories installed on this machine, for impact analysis.
     #[serde(default)]
     pub projects: Vec<String>,
 }
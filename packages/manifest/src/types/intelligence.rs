@@ -7,9 +7,17 @@ use serde::{Deserialize, Serialize};
 pub enum ResolutionAction {
     /// Execute a command via a known manager (e.g., nvm, brew)
     ManagedInstall { manager: String, command: String },
-    /// Download and shim a binary automatically
-    AutoShim { url: String, binary_name: String },
-    /// Modify project configuration
+    /// Download a binary into the store and shim it into the project. The host of `url`
+    /// must be allowed by the manifest's `network` capability.
+    AutoShim {
+        url: String,
+        binary_name: String,
+        version: String,
+        /// Hex SHA-256 of the artifact; downloads that don't match are discarded.
+        sha256: String,
+    },
+    /// Modify a project file: `path` is relative to the project root and `patch` is a
+    /// JSON Patch or a JSON/TOML/YAML merge patch (see [`ConfigPatch`](crate::ConfigPatch))
     ConfigUpdate { path: String, patch: String },
     /// Ask user to manually resolve
     ManualPrompt {
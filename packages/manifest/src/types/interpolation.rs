@@ -0,0 +1,503 @@
+use crate::{
+    EffectiveManifest, EnhancedManifest, EnvValue, EnvValueDetails, ProjectMetadata, ScriptCommand,
+};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Errors raised while resolving `${...}` references.
+///
+/// `field` is the dotted manifest path of the value being resolved (e.g. `env.PATH`,
+/// `scripts.build[1]`, `services.db.command`).
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum InterpolationError {
+    #[error("{field}: variable '{name}' is not defined (use '${{{name}:-default}}' to provide a fallback)")]
+    Undefined { field: String, name: String },
+
+    #[error("{field}: unknown project property 'project.{name}' (expected root, name or version)")]
+    UnknownProperty { field: String, name: String },
+
+    #[error("{field}: unterminated '${{' in \"{value}\"")]
+    Unterminated { field: String, value: String },
+
+    #[error("{field}: empty variable reference '${{}}'")]
+    EmptyName { field: String },
+
+    #[error("{field}: cyclic env reference {}", chain.join(" -> "))]
+    Cycle { field: String, chain: Vec<String> },
+
+    #[error("{field}: {message}")]
+    Secret { field: String, message: String },
+}
+
+/// Values available to `${...}` references.
+#[derive(Debug, Clone)]
+pub struct InterpolationContext {
+    pub project_root: PathBuf,
+    pub project_name: String,
+    pub project_version: String,
+    /// The inherited process environment, consulted after manifest `env` entries.
+    pub process_env: HashMap<String, String>,
+}
+
+impl InterpolationContext {
+    /// Build a context from the project metadata and the current process environment.
+    pub fn new(project: &ProjectMetadata, project_root: &Path) -> Self {
+        Self {
+            project_root: project_root.to_path_buf(),
+            project_name: project.name.clone(),
+            project_version: project.version.to_string(),
+            process_env: std::env::vars().collect(),
+        }
+    }
+
+    /// Replace the inherited environment (useful for tests and sandboxed runs).
+    pub fn with_process_env(mut self, env: HashMap<String, String>) -> Self {
+        self.process_env = env;
+        self
+    }
+}
+
+/// Resolves `${VAR}`, `${VAR:-default}`, `${env.VAR}` and `${project.*}` references.
+///
+/// Manifest `env` entries take precedence over the process environment and may refer to each
+/// other; a reference yields the entry's final value (after prepend/append/default/unset).
+/// An entry referring to itself (e.g. `PATH = "/opt/bin:${PATH}"`) reads the inherited
+/// value. `$${` produces a literal `${`; a bare `$VAR` is left untouched for the shell.
+///
+/// Shell commands (scripts, services, hooks) go through [`Interpolator::resolve_command`],
+/// which only resolves the `project.*` and `env.*` namespaces so shell syntax such as
+/// `${f}` or `${1}` survives.
+pub struct Interpolator<'a> {
+    ctx: &'a InterpolationContext,
+    env: &'a HashMap<String, EnvValue>,
+    resolved: HashMap<String, Option<String>>,
+    stack: Vec<String>,
+}
+
+impl<'a> Interpolator<'a> {
+    pub fn new(ctx: &'a InterpolationContext, env: &'a HashMap<String, EnvValue>) -> Self {
+        Self {
+            ctx,
+            env,
+            resolved: HashMap::new(),
+            stack: Vec::new(),
+        }
+    }
+
+    /// Resolve every entry of the env map to its final value (`None` when unset).
+    pub fn resolve_env(&mut self) -> Result<HashMap<String, Option<String>>, InterpolationError> {
+        let mut names: Vec<&String> = self.env.keys().collect();
+        names.sort();
+
+        let mut out = HashMap::new();
+        for name in names {
+            let value = self.resolve_entry(name)?;
+            out.insert(name.clone(), value);
+        }
+        Ok(out)
+    }
+
+    /// Interpolate the strings inside one env entry, keeping its prepend/append/default form.
+    pub fn interpolate_value(&mut self, name: &str) -> Result<EnvValue, InterpolationError> {
+        self.stack.push(name.to_string());
+        let value = self.interpolate_components(name);
+        self.stack.pop();
+        value
+    }
+
+    fn interpolate_components(&mut self, name: &str) -> Result<EnvValue, InterpolationError> {
+        let field = format!("env.{}", name);
+
+        match &self.env[name] {
+            EnvValue::Literal(value) => Ok(EnvValue::Literal(self.resolve_str(value, &field)?)),
+            EnvValue::Detailed(details) => {
+                let mut out = EnvValueDetails {
+                    unset: details.unset,
+                    separator: details.separator.clone(),
+                    ..Default::default()
+                };
+                for (i, entry) in details.prepend.iter().enumerate() {
+                    out.prepend
+                        .push(self.resolve_str(entry, &format!("{}.prepend[{}]", field, i))?);
+                }
+                for (i, entry) in details.append.iter().enumerate() {
+                    out.append
+                        .push(self.resolve_str(entry, &format!("{}.append[{}]", field, i))?);
+                }
+                if let Some(default) = &details.default {
+                    out.default = Some(self.resolve_str(default, &format!("{}.default", field))?);
+                }
+                Ok(EnvValue::Detailed(out))
+            }
+            EnvValue::Secret(secret) => Ok(EnvValue::Secret(secret.clone())),
+        }
+    }
+
+    /// Resolve an arbitrary string found at `field`.
+    pub fn resolve_str(&mut self, value: &str, field: &str) -> Result<String, InterpolationError> {
+        self.resolve(value, field, false)
+    }
+
+    /// Resolve a shell command found at `field`, leaving `${…}` outside the `project.*`
+    /// and `env.*` namespaces for the shell.
+    pub fn resolve_command(
+        &mut self,
+        value: &str,
+        field: &str,
+    ) -> Result<String, InterpolationError> {
+        self.resolve(value, field, true)
+    }
+
+    fn resolve(
+        &mut self,
+        value: &str,
+        field: &str,
+        command: bool,
+    ) -> Result<String, InterpolationError> {
+        let mut out = String::with_capacity(value.len());
+        let mut rest = value;
+
+        while let Some(pos) = rest.find('$') {
+            out.push_str(&rest[..pos]);
+            let after = &rest[pos + 1..];
+
+            if let Some(escaped) = after.strip_prefix("${") {
+                out.push_str("${");
+                rest = escaped;
+            } else if let Some(body) = after.strip_prefix('{') {
+                let end = matching_brace(body).ok_or_else(|| InterpolationError::Unterminated {
+                    field: field.to_string(),
+                    value: value.to_string(),
+                })?;
+                out.push_str(&self.evaluate(&body[..end], field, command)?);
+                rest = &body[end + 1..];
+            } else {
+                out.push('$');
+                rest = after;
+            }
+        }
+
+        out.push_str(rest);
+        Ok(out)
+    }
+
+    fn evaluate(
+        &mut self,
+        expr: &str,
+        field: &str,
+        command: bool,
+    ) -> Result<String, InterpolationError> {
+        let (name, default) = match expr.split_once(":-") {
+            Some((name, default)) => (name.trim(), Some(default)),
+            None => (expr.trim(), None),
+        };
+
+        if command && !name.starts_with("project.") && !name.starts_with("env.") {
+            return Ok(format!("${{{}}}", expr));
+        }
+
+        if name.is_empty() {
+            return Err(InterpolationError::EmptyName {
+                field: field.to_string(),
+            });
+        }
+
+        if let Some(property) = name.strip_prefix("project.") {
+            return match property {
+                "root" => Ok(self.ctx.project_root.to_string_lossy().to_string()),
+                "name" => Ok(self.ctx.project_name.clone()),
+                "version" => Ok(self.ctx.project_version.clone()),
+                _ => Err(InterpolationError::UnknownProperty {
+                    field: field.to_string(),
+                    name: property.to_string(),
+                }),
+            };
+        }
+
+        let name = name.strip_prefix("env.").unwrap_or(name);
+        match (self.lookup(name)?, default) {
+            (Some(value), Some(_)) if !value.is_empty() => Ok(value),
+            (Some(value), None) => Ok(value),
+            (_, Some(default)) => self.resolve(default, field, command),
+            (None, None) => Err(InterpolationError::Undefined {
+                field: field.to_string(),
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    fn lookup(&mut self, name: &str) -> Result<Option<String>, InterpolationError> {
+        // Self-reference reads the inherited value rather than recursing.
+        if self.stack.last().map(String::as_str) == Some(name) {
+            return Ok(self.ctx.process_env.get(name).cloned());
+        }
+
+        if self.env.contains_key(name) {
+            return self.resolve_entry(name);
+        }
+
+        Ok(self.ctx.process_env.get(name).cloned())
+    }
+
+    fn resolve_entry(&mut self, name: &str) -> Result<Option<String>, InterpolationError> {
+        if let Some(value) = self.resolved.get(name) {
+            return Ok(value.clone());
+        }
+
+        if let Some(start) = self.stack.iter().position(|n| n == name) {
+            let mut chain: Vec<String> = self.stack[start..].to_vec();
+            chain.push(name.to_string());
+            return Err(InterpolationError::Cycle {
+                field: format!("env.{}", self.stack[start]),
+                chain,
+            });
+        }
+
+        let value = self.interpolate_value(name)?;
+        let inherited = self.ctx.process_env.get(name).map(String::as_str);
+        let value = value.apply(inherited);
+
+        self.resolved.insert(name.to_string(), value.clone());
+        Ok(value)
+    }
+}
+
+/// Index of the `}` closing a `${` whose body starts at `body[0]`.
+fn matching_brace(body: &str) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in body.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(i),
+            '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    None
+}
+
+impl EnhancedManifest {
+    /// Return a copy with `env`, `scripts`, `services.*.command` and `hooks` interpolated.
+    pub fn interpolate(
+        &self,
+        ctx: &InterpolationContext,
+    ) -> Result<EnhancedManifest, InterpolationError> {
+        let mut interpolator = Interpolator::new(ctx, &self.env);
+        let mut manifest = self.clone();
+
+        for name in self.env.keys() {
+            let value = interpolator.interpolate_value(name)?;
+            manifest.env.insert(name.clone(), value);
+        }
+
+        for (name, script) in manifest.scripts.iter_mut() {
+            match script {
+                ScriptCommand::Single(cmd) => {
+                    *cmd = interpolator.resolve_command(cmd, &format!("scripts.{}", name))?;
+                }
+                ScriptCommand::Multiple(cmds) => {
+                    for (i, cmd) in cmds.iter_mut().enumerate() {
+                        *cmd = interpolator
+                            .resolve_command(cmd, &format!("scripts.{}[{}]", name, i))?;
+                    }
+                }
+            }
+        }
+
+        for (name, service) in manifest.services.iter_mut() {
+            service.command = interpolator
+                .resolve_command(&service.command, &format!("services.{}.command", name))?;
+        }
+
+        if let Some(hooks) = manifest.hooks.as_mut() {
+            let slots = [
+                ("pre_install", &mut hooks.pre_install),
+                ("post_install", &mut hooks.post_install),
+                ("pre_activate", &mut hooks.pre_activate),
+                ("post_activate", &mut hooks.post_activate),
+                ("pre_deactivate", &mut hooks.pre_deactivate),
+                ("post_deactivate", &mut hooks.post_deactivate),
+            ];
+            for (name, slot) in slots {
+                if let Some(cmd) = slot.as_mut() {
+                    *cmd = interpolator.resolve_command(cmd, &format!("hooks.{}", name))?;
+                }
+            }
+        }
+
+        Ok(manifest)
+    }
+}
+
+impl EffectiveManifest {
+    /// Interpolate the effective env (manifest env with profile overrides applied).
+    pub fn interpolate(
+        mut self,
+        ctx: &InterpolationContext,
+    ) -> Result<EffectiveManifest, InterpolationError> {
+        let mut interpolator = Interpolator::new(ctx, &self.env);
+        let mut env = HashMap::new();
+        for name in self.env.keys() {
+            env.insert(name.clone(), interpolator.interpolate_value(name)?);
+        }
+        self.env = env;
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx() -> InterpolationContext {
+        let project = ProjectMetadata {
+            name: "demo".to_string(),
+            ..Default::default()
+        };
+
+        InterpolationContext::new(&project, Path::new("/work/demo")).with_process_env(
+            [("HOME", "/home/dev"), ("PATH", "/usr/bin")]
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        )
+    }
+
+    fn env(entries: &[(&str, &str)]) -> HashMap<String, EnvValue> {
+        entries
+            .iter()
+            .map(|(k, v)| (k.to_string(), EnvValue::from(*v)))
+            .collect()
+    }
+
+    #[test]
+    fn test_references_and_defaults() {
+        let ctx = ctx();
+        let env = env(&[
+            ("DATA", "${project.root}/data"),
+            ("CACHE", "${DATA}/cache"),
+            ("LEVEL", "${LOG_LEVEL:-info}"),
+            ("PATH", "${project.root}/bin:${PATH}"),
+            ("LITERAL", "$${HOME} and $HOME"),
+        ]);
+
+        let resolved = Interpolator::new(&ctx, &env).resolve_env().unwrap();
+
+        assert_eq!(resolved["CACHE"].as_deref(), Some("/work/demo/data/cache"));
+        assert_eq!(resolved["LEVEL"].as_deref(), Some("info"));
+        assert_eq!(resolved["PATH"].as_deref(), Some("/work/demo/bin:/usr/bin"));
+        assert_eq!(resolved["LITERAL"].as_deref(), Some("${HOME} and $HOME"));
+    }
+
+    #[test]
+    fn test_cycle_detection() {
+        let ctx = ctx();
+        let env = env(&[("A", "${B}"), ("B", "${C}"), ("C", "${A}")]);
+
+        let err = Interpolator::new(&ctx, &env).resolve_env().unwrap_err();
+        assert_eq!(
+            err,
+            InterpolationError::Cycle {
+                field: "env.A".to_string(),
+                chain: vec!["A", "B", "C", "A"]
+                    .into_iter()
+                    .map(String::from)
+                    .collect(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_errors_point_at_field() {
+        let ctx = ctx();
+        let mut manifest = EnhancedManifest::default();
+        manifest.scripts.insert(
+            "build".to_string(),
+            ScriptCommand::Multiple(vec!["echo ok".to_string(), "${env.MISSING}".to_string()]),
+        );
+
+        let err = manifest.interpolate(&ctx).unwrap_err();
+        assert_eq!(
+            err,
+            InterpolationError::Undefined {
+                field: "scripts.build[1]".to_string(),
+                name: "MISSING".to_string(),
+            }
+        );
+
+        let env = env(&[("X", "${project.owner}"), ("Y", "${oops")]);
+        assert!(matches!(
+            Interpolator::new(&ctx, &env).resolve_entry("X"),
+            Err(InterpolationError::UnknownProperty { .. })
+        ));
+        assert!(matches!(
+            Interpolator::new(&ctx, &env).resolve_entry("Y"),
+            Err(InterpolationError::Unterminated { .. })
+        ));
+    }
+
+    #[test]
+    fn test_manifest_interpolation() {
+        let ctx = ctx();
+        let manifest: EnhancedManifest = toml::from_str(
+            r#"
+[env]
+DB_DIR = "${project.root}/.db"
+
+[scripts]
+start = "serve --data ${env.DB_DIR:-/tmp}"
+
+[services.db]
+command = "postgres -D ${env.DB_DIR}"
+
+[hooks]
+post_install = "echo installed ${project.name}"
+"#,
+        )
+        .unwrap();
+
+        let resolved = manifest.interpolate(&ctx).unwrap();
+
+        assert_eq!(
+            resolved.scripts["start"],
+            ScriptCommand::Single("serve --data /work/demo/.db".to_string())
+        );
+        assert_eq!(
+            resolved.services["db"].command,
+            "postgres -D /work/demo/.db"
+        );
+        assert_eq!(
+            resolved.hooks.unwrap().post_install.as_deref(),
+            Some("echo installed demo")
+        );
+    }
+
+    #[test]
+    fn test_shell_syntax_left_alone() {
+        let ctx = ctx();
+        let manifest: EnhancedManifest = toml::from_str(
+            r#"
+[scripts]
+lint = "for f in ${project.root}/*.sh; do shellcheck ${f} ${1:-}; done"
+
+[hooks]
+post_install = "echo ${HOME} ${env.HOME}"
+"#,
+        )
+        .unwrap();
+
+        let resolved = manifest.interpolate(&ctx).unwrap();
+
+        assert_eq!(
+            resolved.scripts["lint"],
+            ScriptCommand::Single(
+                "for f in /work/demo/*.sh; do shellcheck ${f} ${1:-}; done".to_string()
+            )
+        );
+        assert_eq!(
+            resolved.hooks.unwrap().post_install.as_deref(),
+            Some("echo ${HOME} /home/dev")
+        );
+    }
+}
@@ -0,0 +1,584 @@
+use crate::{
+    Capability, DependencySpec, EnhancedManifest, ScriptCommand, ValidationLevel, ValidationResult,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Per-project rule settings (`[lints]`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default, JsonSchema)]
+pub struct LintConfig {
+    /// Rule IDs to suppress for this project (e.g. `undeclared-tool`).
+    #[serde(default)]
+    pub allow: Vec<String>,
+}
+
+/// A semantic manifest check. IDs are stable and used in `[lints] allow`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rule {
+    /// A package listed in `conflicts` is also a dependency.
+    ConflictingDependency,
+    /// A package's production range doesn't overlap its dev/test/build range.
+    IncompatibleRanges,
+    /// A `manager` that exists on none of the allowed platforms.
+    UnavailableManager,
+    /// A script runs a tool no dependency provides.
+    UndeclaredTool,
+    /// A hook runs a program its capabilities don't allow.
+    MissingCapability,
+    /// An asset checksum that isn't `<algorithm>:<hex>` of the right length.
+    MalformedChecksum,
+}
+
+impl Rule {
+    pub const ALL: &'static [Rule] = &[
+        Rule::ConflictingDependency,
+        Rule::IncompatibleRanges,
+        Rule::UnavailableManager,
+        Rule::UndeclaredTool,
+        Rule::MissingCapability,
+        Rule::MalformedChecksum,
+    ];
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            Rule::ConflictingDependency => "conflicting-dependency",
+            Rule::IncompatibleRanges => "incompatible-ranges",
+            Rule::UnavailableManager => "unavailable-manager",
+            Rule::UndeclaredTool => "undeclared-tool",
+            Rule::MissingCapability => "missing-capability",
+            Rule::MalformedChecksum => "malformed-checksum",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Rule> {
+        Rule::ALL.iter().copied().find(|rule| rule.id() == id)
+    }
+
+    pub fn level(&self) -> ValidationLevel {
+        match self {
+            // Installs refuse a malformed checksum anyway, once they get to the asset.
+            Rule::UndeclaredTool | Rule::MissingCapability | Rule::MalformedChecksum => {
+                ValidationLevel::Warning
+            }
+            _ => ValidationLevel::Error,
+        }
+    }
+}
+
+/// Executables provided by a dependency under a different name.
+const TOOL_PROVIDERS: &[(&str, &[&str])] = &[
+    ("node", &["node", "nodejs"]),
+    ("npm", &["node", "nodejs", "npm"]),
+    ("npx", &["node", "nodejs", "npm"]),
+    ("yarn", &["yarn"]),
+    ("pnpm", &["pnpm"]),
+    ("bun", &["bun"]),
+    ("deno", &["deno"]),
+    ("python", &["python", "python3"]),
+    ("python3", &["python", "python3"]),
+    ("pip", &["python", "python3", "pip"]),
+    ("pip3", &["python", "python3", "pip3"]),
+    ("poetry", &["poetry"]),
+    ("uv", &["uv"]),
+    ("cargo", &["rust", "rustup", "cargo"]),
+    ("rustc", &["rust", "rustup", "rustc"]),
+    ("go", &["go", "golang"]),
+    ("ruby", &["ruby"]),
+    ("gem", &["ruby", "gem"]),
+    ("bundle", &["ruby", "bundler"]),
+    ("java", &["java", "jdk", "openjdk"]),
+    ("mvn", &["maven"]),
+    ("gradle", &["gradle"]),
+    ("php", &["php"]),
+    ("composer", &["composer"]),
+    ("dotnet", &["dotnet"]),
+    ("docker", &["docker"]),
+    ("terraform", &["terraform"]),
+    ("kubectl", &["kubectl"]),
+];
+
+/// Programs that reach the network and also need a `network` capability.
+const NETWORK_TOOLS: &[&str] = &["curl", "wget"];
+
+/// Runs the [`Rule`]s, dropping any the project allows.
+pub(crate) struct RuleChecker<'a> {
+    manifest: &'a EnhancedManifest,
+    allowed: Vec<Rule>,
+}
+
+impl<'a> RuleChecker<'a> {
+    pub(crate) fn new(manifest: &'a EnhancedManifest, result: &mut ValidationResult) -> Self {
+        let mut allowed = Vec::new();
+        let ids = manifest.lints.iter().flat_map(|lints| &lints.allow);
+        for (i, id) in ids.enumerate() {
+            match Rule::from_id(id) {
+                Some(rule) => allowed.push(rule),
+                None => result.add_warning(
+                    format!("lints.allow[{}]", i),
+                    format!(
+                        "Unknown rule '{}'. Known rules: {}",
+                        id,
+                        Rule::ALL
+                            .iter()
+                            .map(|r| r.id())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                ),
+            }
+        }
+        Self { manifest, allowed }
+    }
+
+    pub(crate) fn check(&self, result: &mut ValidationResult) {
+        self.conflicting_dependencies(result);
+        self.incompatible_ranges(result);
+        self.unavailable_managers(result);
+        self.undeclared_tools(result);
+        self.missing_capabilities(result);
+        self.malformed_checksums(result);
+    }
+
+    fn report(&self, result: &mut ValidationResult, rule: Rule, field: String, message: String) {
+        if !self.allowed.contains(&rule) {
+            result.add_rule(rule, field, message);
+        }
+    }
+
+    /// Every declared dependency with its field path, in a stable order.
+    fn dependencies(&self) -> Vec<(String, &'a str, &'a DependencySpec)> {
+        let m = self.manifest;
+        let mut sections: Vec<(String, &HashMap<String, DependencySpec>)> = vec![
+            ("dependencies".to_string(), &m.dependencies),
+            ("dev-dependencies".to_string(), &m.dev_dependencies),
+            ("test-dependencies".to_string(), &m.test_dependencies),
+            ("build-dependencies".to_string(), &m.build_dependencies),
+        ];
+        for (name, group) in &m.group {
+            sections.push((format!("group.{}.dependencies", name), &group.dependencies));
+        }
+        for (key, target) in &m.target {
+            sections.push((format!("target.{}.dependencies", key), &target.dependencies));
+        }
+
+        let mut deps: Vec<_> = sections
+            .into_iter()
+            .flat_map(|(section, map)| {
+                map.iter()
+                    .map(move |(name, spec)| (format!("{}.{}", section, name), name.as_str(), spec))
+            })
+            .collect();
+        deps.sort_by(|a, b| a.0.cmp(&b.0));
+        deps
+    }
+
+    fn is_declared(&self, name: &str) -> bool {
+        self.dependencies().iter().any(|(_, dep, _)| *dep == name)
+    }
+
+    fn conflicting_dependencies(&self, result: &mut ValidationResult) {
+        let conflicts: BTreeMap<_, _> = self.manifest.conflicts.iter().collect();
+        for (package, reason) in conflicts {
+            if let Some((field, _, _)) = self
+                .dependencies()
+                .into_iter()
+                .find(|(_, dep, _)| *dep == package.as_str())
+            {
+                self.report(
+                    result,
+                    Rule::ConflictingDependency,
+                    format!("conflicts.{}", package),
+                    format!(
+                        "'{}' is declared as a conflict ({}) but is required by {}",
+                        package, reason, field
+                    ),
+                );
+            }
+        }
+    }
+
+    fn incompatible_ranges(&self, result: &mut ValidationResult) {
+        let m = self.manifest;
+        let others = [
+            ("dev-dependencies", &m.dev_dependencies),
+            ("test-dependencies", &m.test_dependencies),
+            ("build-dependencies", &m.build_dependencies),
+        ];
+        let prod: BTreeMap<_, _> = m.dependencies.iter().collect();
+
+        for (name, spec) in prod {
+            let ecosystem = spec.ecosystem(name);
+            let Some(required) = spec.constraint().range(ecosystem) else {
+                continue;
+            };
+            for (section, deps) in others {
+                let Some(other) = deps.get(name) else {
+                    continue;
+                };
+                let Some(range) = other.constraint().range(ecosystem) else {
+                    continue;
+                };
+                if !required.intersects(&range) {
+                    self.report(
+                        result,
+                        Rule::IncompatibleRanges,
+                        format!("{}.{}", section, name),
+                        format!(
+                            "'{}' requires '{}' in {} but '{}' in dependencies; no version satisfies both",
+                            name,
+                            other.constraint(),
+                            section,
+                            spec.constraint()
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn unavailable_managers(&self, result: &mut ValidationResult) {
+        let Some(platform) = &self.manifest.platform else {
+            return;
+        };
+
+        for (field, name, spec) in self.dependencies() {
+            let Some(manager) = spec.manager() else {
+                continue;
+            };
+            let Some(supported) = manager.platforms() else {
+                continue;
+            };
+            let available = platform
+                .platforms
+                .iter()
+                .any(|allowed| supported.iter().any(|os| allowed.admits(*os)));
+
+            if !available {
+                self.report(
+                    result,
+                    Rule::UnavailableManager,
+                    format!("{}.manager", field),
+                    format!(
+                        "'{}' is installed with {}, which isn't available on {}",
+                        name,
+                        manager.as_str(),
+                        platform
+                            .platforms
+                            .iter()
+                            .map(|os| os.as_str())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                );
+            }
+        }
+    }
+
+    fn undeclared_tools(&self, result: &mut ValidationResult) {
+        let scripts: BTreeMap<_, _> = self.manifest.scripts.iter().collect();
+        for (name, script) in scripts {
+            let lines: Vec<(String, &str)> = match script {
+                ScriptCommand::Single(command) => vec![(format!("scripts.{}", name), command)],
+                ScriptCommand::Multiple(commands) => commands
+                    .iter()
+                    .enumerate()
+                    .map(|(i, c)| (format!("scripts.{}[{}]", name, i), c.as_str()))
+                    .collect(),
+            };
+
+            for (field, command) in lines {
+                for program in programs(command) {
+                    let Some((_, providers)) =
+                        TOOL_PROVIDERS.iter().find(|(tool, _)| *tool == program)
+                    else {
+                        continue;
+                    };
+                    if !providers.iter().any(|p| self.is_declared(p)) {
+                        self.report(
+                            result,
+                            Rule::UndeclaredTool,
+                            field.clone(),
+                            format!(
+                                "Script '{}' runs '{}' but no dependency provides it (add one of: {})",
+                                name,
+                                program,
+                                providers.join(", ")
+                            ),
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    fn missing_capabilities(&self, result: &mut ValidationResult) {
+        let Some(hooks) = &self.manifest.hooks else {
+            return;
+        };
+        // Without a `capabilities` list the project hasn't opted into sandboxing hooks.
+        let Some(capabilities) = self.manifest.capabilities.as_deref() else {
+            return;
+        };
+        let may_exec = |program: &str| {
+            capabilities.iter().any(|cap| {
+                matches!(cap, Capability::SysExec(allowed)
+                    if allowed.iter().any(|a| a == "*" || a == program))
+            })
+        };
+        let has_network = capabilities
+            .iter()
+            .any(|cap| matches!(cap, Capability::Network(_)));
+
+        let stages = [
+            ("pre_install", &hooks.pre_install),
+            ("post_install", &hooks.post_install),
+            ("pre_activate", &hooks.pre_activate),
+            ("post_activate", &hooks.post_activate),
+            ("pre_deactivate", &hooks.pre_deactivate),
+            ("post_deactivate", &hooks.post_deactivate),
+        ];
+        for (stage, command) in stages {
+            let Some(command) = command else {
+                continue;
+            };
+            let field = format!("hooks.{}", stage);
+            for program in programs(command) {
+                if !may_exec(program) {
+                    self.report(
+                        result,
+                        Rule::MissingCapability,
+                        field.clone(),
+                        format!(
+                            "Hook '{}' runs '{}' but no `sys-exec` capability allows it",
+                            stage, program
+                        ),
+                    );
+                }
+                if NETWORK_TOOLS.contains(&program) && !has_network {
+                    self.report(
+                        result,
+                        Rule::MissingCapability,
+                        field.clone(),
+                        format!(
+                            "Hook '{}' uses '{}' but no `network` capability was requested",
+                            stage, program
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    fn malformed_checksums(&self, result: &mut ValidationResult) {
+        for (i, asset) in self.manifest.assets.iter().enumerate() {
+            if let Err(problem) = crate::parse_checksum(&asset.checksum) {
+                self.report(
+                    result,
+                    Rule::MalformedChecksum,
+                    format!("assets[{}].checksum", i),
+                    format!("Checksum for asset '{}' {}", asset.name, problem),
+                );
+            }
+        }
+    }
+}
+
+/// The programs a shell command line runs: the first word of each `&&`, `||`, `;` or `|`
+/// separated command, skipping `VAR=value` assignments and taking the basename.
+/// Separators inside quotes (`echo "a && b"`) don't start a command.
+fn programs(command: &str) -> Vec<&str> {
+    commands(command)
+        .into_iter()
+        .filter_map(|part| {
+            part.split_whitespace()
+                .find(|word| !word.contains('='))
+                .map(|word| word.rsplit('/').next().unwrap_or(word))
+        })
+        .collect()
+}
+
+/// Split a command line at unquoted `|`, `;`, newlines and `&&`.
+fn commands(line: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut quote = None;
+    let mut escaped = false;
+    let mut start = 0;
+    let mut chars = line.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match (quote, c) {
+            (Some('\''), '\'') | (Some('"'), '"') => quote = None,
+            (Some('\''), _) => {}
+            (_, '\\') => escaped = true,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '|' | ';' | '\n') => {
+                parts.push(&line[start..i]);
+                start = i + 1;
+            }
+            (None, '&') if chars.peek().is_some_and(|(_, next)| *next == '&') => {
+                parts.push(&line[start..i]);
+                chars.next();
+                start = i + 2;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&line[start..]);
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ManifestValidator, ValidationResult};
+
+    fn rules(toml: &str) -> Vec<(String, String)> {
+        let manifest = toml::from_str(toml).unwrap();
+        let result: ValidationResult = ManifestValidator::validate(&manifest);
+        result
+            .issues
+            .into_iter()
+            .filter_map(|i| Some((i.rule?, i.field)))
+            .collect()
+    }
+
+    fn pair(rule: &str, field: &str) -> (String, String) {
+        (rule.to_string(), field.to_string())
+    }
+
+    #[test]
+    fn test_dependency_rules() {
+        let found = rules(
+            r#"
+[dependencies]
+node = "^18"
+openssl = { version = "3", manager = "apt" }
+
+[dev-dependencies]
+node = "^20"
+
+[platform]
+platforms = ["macos", "windows"]
+
+[conflicts]
+openssl = "use libressl"
+"#,
+        );
+        assert_eq!(
+            found,
+            vec![
+                pair("conflicting-dependency", "conflicts.openssl"),
+                pair("incompatible-ranges", "dev-dependencies.node"),
+                pair("unavailable-manager", "dependencies.openssl.manager"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_script_and_hook_rules() {
+        let found = rules(
+            r#"
+capabilities = [{ sys-exec = ["npm"] }]
+
+[dependencies]
+node = "20"
+
+[scripts]
+build = "NODE_ENV=production npm run build && cargo build"
+test = ["npx vitest", "./bin/pytest.sh"]
+
+[hooks]
+post_install = "npm ci"
+pre_activate = "curl -fsSL https://example.com/setup.sh | sh"
+"#,
+        );
+        assert_eq!(
+            found,
+            vec![
+                pair("undeclared-tool", "scripts.build"),
+                pair("missing-capability", "hooks.pre_activate"),
+                pair("missing-capability", "hooks.pre_activate"),
+                pair("missing-capability", "hooks.pre_activate"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hooks_without_capabilities() {
+        let found = rules(
+            r#"
+[dependencies]
+node = "20"
+
+[hooks]
+post_install = "npm ci && curl -fsSL https://example.com | sh"
+"#,
+        );
+        assert!(found.is_empty());
+    }
+
+    #[test]
+    fn test_programs_respect_quotes() {
+        assert_eq!(
+            super::programs(r#"echo "a && b; c" | grep 'x|y' && FOO=1 ./bin/run"#),
+            vec!["echo", "grep", "run"]
+        );
+        assert_eq!(
+            super::programs(r"echo a\;b || npm test"),
+            vec!["echo", "npm"]
+        );
+    }
+
+    #[test]
+    fn test_checksums_and_suppression() {
+        let manifest = |allow: &str| {
+            format!(
+                r#"
+[dependencies]
+node = "20"
+
+[[assets]]
+name = "ok"
+url = "https://example.com/a.tgz"
+checksum = "sha256:{}"
+
+[[assets]]
+name = "short"
+url = "https://example.com/b.tgz"
+checksum = "sha256:abc123"
+
+[[assets]]
+name = "bare"
+url = "https://example.com/c.tgz"
+checksum = "d41d8cd98f00b204e9800998ecf8427e"
+
+[lints]
+allow = [{}]
+"#,
+                "a".repeat(64),
+                allow
+            )
+        };
+
+        assert_eq!(
+            rules(&manifest("")),
+            vec![
+                pair("malformed-checksum", "assets[1].checksum"),
+                pair("malformed-checksum", "assets[2].checksum"),
+            ]
+        );
+        assert!(rules(&manifest("\"malformed-checksum\"")).is_empty());
+
+        let typo: crate::EnhancedManifest =
+            toml::from_str(&manifest("\"malformed-checksums\"")).unwrap();
+        assert!(ManifestValidator::validate(&typo)
+            .issues
+            .iter()
+            .any(|i| i.field == "lints.allow[0]"));
+    }
+}
@@ -0,0 +1,299 @@
+use crate::{ConstraintKind, DependencySpec, Ecosystem, ManifestEditor};
+use anyhow::{Context, Result};
+use serde_json::Value;
+
+/// The manifest format version written by this release.
+///
+/// Bump it together with a new entry in [`MIGRATIONS`] whenever a field is renamed, moved
+/// or changes shape, and publish the new schema (`schema/v{N}.json`).
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+/// Manifest key holding the format version. Documents without it are taken to be current,
+/// unless they still have the shape an older version's migration upgrades.
+pub const SCHEMA_VERSION_KEY: &str = "schema-version";
+
+/// One upgrade step, from `from` to `from + 1`.
+pub struct Migration {
+    pub from: u32,
+    pub description: &'static str,
+    /// Whether the document still has the shape this step upgrades.
+    detect: fn(&ManifestEditor) -> bool,
+    apply: fn(&mut ManifestEditor) -> Result<()>,
+}
+
+/// Every step, oldest first. Steps edit the document in place so formatting survives.
+pub static MIGRATIONS: &[Migration] = &[
+    Migration {
+        from: 0,
+        description: "Rename the legacy `plugin` table to `project`",
+        detect: |editor| editor.contains(&["plugin"]),
+        apply: |editor| {
+            editor.rename(&["plugin"], "project")?;
+            Ok(())
+        },
+    },
+    Migration {
+        from: 1,
+        description:
+            "Spell out the caret ranges bare versions used to mean outside Cargo-style SemVer",
+        detect: |editor| !caret_bare_versions(editor).is_empty(),
+        apply: |editor| {
+            for (section, name, version) in caret_bare_versions(editor) {
+                let section: Vec<&str> = section.iter().map(String::as_str).collect();
+                editor.set_dependency(&section, &name, &version)?;
+            }
+            Ok(())
+        },
+    },
+];
+
+/// Bare versions that schema version 1 read as caret ranges and that now mean a release
+/// line (`node = "18.5"` is 18.5.x), with their explicit caret spelling.
+fn caret_bare_versions(editor: &ManifestEditor) -> Vec<(Vec<String>, String, String)> {
+    let mut sections: Vec<Vec<String>> = [
+        "dependencies",
+        "dev-dependencies",
+        "test-dependencies",
+        "build-dependencies",
+    ]
+    .iter()
+    .map(|section| vec![section.to_string()])
+    .collect();
+    for table in ["target", "group"] {
+        if let Some(Value::Object(entries)) = editor.get(&[table]) {
+            for key in entries.keys() {
+                sections.push(vec![
+                    table.to_string(),
+                    key.clone(),
+                    "dependencies".to_string(),
+                ]);
+            }
+        }
+    }
+
+    let mut found = Vec::new();
+    for section in sections {
+        let path: Vec<&str> = section.iter().map(String::as_str).collect();
+        let Some(Value::Object(dependencies)) = editor.get(&path) else {
+            continue;
+        };
+        for (name, spec) in dependencies {
+            let Ok(spec) = serde_json::from_value::<DependencySpec>(spec) else {
+                continue;
+            };
+            // A major version alone (`20`) means the same under both readings.
+            let constraint = spec.constraint();
+            if matches!(
+                constraint.kind(),
+                ConstraintKind::Bare { minor: Some(_), .. }
+            ) && Ecosystem::for_dependency(&name, spec.manager()) != Ecosystem::Semver
+            {
+                let version = format!("^{}", constraint.as_str().trim_start_matches('v'));
+                found.push((section.clone(), name, version));
+            }
+        }
+    }
+    found
+}
+
+/// What a migration run changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MigrationReport {
+    pub from: u32,
+    pub to: u32,
+    pub applied: Vec<&'static str>,
+}
+
+impl MigrationReport {
+    pub fn is_empty(&self) -> bool {
+        self.from == self.to
+    }
+}
+
+/// Upgrades manifests written for older schema versions.
+pub struct ManifestMigrator;
+
+impl ManifestMigrator {
+    /// The schema version a document declares, or the one its shape implies when it
+    /// declares none.
+    pub fn version(editor: &ManifestEditor) -> Result<u32> {
+        match editor.get(&[SCHEMA_VERSION_KEY]) {
+            None => Ok(MIGRATIONS
+                .iter()
+                .find(|m| (m.detect)(editor))
+                .map_or(CURRENT_SCHEMA_VERSION, |m| m.from)),
+            Some(value) => value
+                .as_u64()
+                .and_then(|v| u32::try_from(v).ok())
+                .with_context(|| format!("Invalid {}: {}", SCHEMA_VERSION_KEY, value)),
+        }
+    }
+
+    /// The steps between the document's version and [`CURRENT_SCHEMA_VERSION`] that would
+    /// actually change it.
+    pub fn pending(editor: &ManifestEditor) -> Result<Vec<&'static Migration>> {
+        let from = Self::version(editor)?;
+        Ok(MIGRATIONS
+            .iter()
+            .filter(|m| m.from >= from && (m.detect)(editor))
+            .collect())
+    }
+
+    /// Apply every step between the document's version and [`CURRENT_SCHEMA_VERSION`],
+    /// recording the new version in the document.
+    pub fn migrate(editor: &mut ManifestEditor) -> Result<MigrationReport> {
+        Self::run(editor, true)
+    }
+
+    /// Apply the steps without writing `schema-version`, so an old document can be read
+    /// without shifting any lines (diagnostics keep pointing at the right place).
+    pub fn upgrade(editor: &mut ManifestEditor) -> Result<MigrationReport> {
+        Self::run(editor, false)
+    }
+
+    fn run(editor: &mut ManifestEditor, stamp: bool) -> Result<MigrationReport> {
+        let from = Self::version(editor)?;
+        anyhow::ensure!(
+            from <= CURRENT_SCHEMA_VERSION,
+            "Manifest {} {} is newer than this release supports ({}); please upgrade env-architect",
+            SCHEMA_VERSION_KEY,
+            from,
+            CURRENT_SCHEMA_VERSION
+        );
+
+        let mut applied = Vec::new();
+        for version in from..CURRENT_SCHEMA_VERSION {
+            let migration = MIGRATIONS
+                .iter()
+                .find(|m| m.from == version)
+                .with_context(|| format!("No migration from schema version {}", version))?;
+
+            (migration.apply)(editor).with_context(|| {
+                format!(
+                    "Migration {} -> {} failed: {}",
+                    version,
+                    version + 1,
+                    migration.description
+                )
+            })?;
+            if stamp {
+                editor.set(&[SCHEMA_VERSION_KEY], version + 1)?;
+            }
+            applied.push(migration.description);
+        }
+
+        Ok(MigrationReport {
+            from,
+            to: CURRENT_SCHEMA_VERSION.max(from),
+            applied,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ManifestFormat;
+
+    #[test]
+    fn test_migrations_are_contiguous() {
+        for (i, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.from, i as u32);
+        }
+        assert_eq!(MIGRATIONS.len() as u32, CURRENT_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_migrate_legacy_toml() {
+        let mut editor = ManifestEditor::new(
+            "# legacy manifest\n[plugin]\nname = \"demo\"\n\n[dependencies]\nnode = \"^20\"\n",
+            ManifestFormat::Toml,
+        )
+        .unwrap();
+
+        let report = ManifestMigrator::migrate(&mut editor).unwrap();
+        assert_eq!((report.from, report.to, report.applied.len()), (0, 2, 2));
+        assert_eq!(
+            editor.to_string(),
+            "schema-version = 2\n# legacy manifest\n[project]\nname = \"demo\"\n\n[dependencies]\nnode = \"^20\"\n"
+        );
+
+        let manifest = editor.source().parse().unwrap();
+        assert_eq!(manifest.schema_version, Some(2));
+        assert_eq!(manifest.project.name, "demo");
+
+        // Already current: nothing to do.
+        assert!(ManifestMigrator::migrate(&mut editor).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_migrate_bare_versions() {
+        let source = r#"[project]
+name = "demo"
+
+[dependencies]
+node = "18.5"
+python = { version = "v3.11", manager = "pip" }
+ripgrep = "14.1"
+deno = "2"
+
+[target.linux.dependencies]
+go = "1.22"
+"#;
+        let mut editor = ManifestEditor::new(source, ManifestFormat::Toml).unwrap();
+        assert_eq!(ManifestMigrator::version(&editor).unwrap(), 1);
+
+        // Loading keeps the caret meaning without touching the file.
+        let manifest = editor.source().parse().unwrap();
+        assert_eq!(manifest.dependencies["node"].constraint().as_str(), "^18.5");
+        assert_eq!(manifest.schema_version, Some(CURRENT_SCHEMA_VERSION));
+
+        let report = ManifestMigrator::migrate(&mut editor).unwrap();
+        assert_eq!((report.from, report.to, report.applied.len()), (1, 2, 1));
+        assert_eq!(
+            editor.to_string(),
+            source
+                .replacen("[project]", "schema-version = 2\n[project]", 1)
+                .replace("\"18.5\"", "\"^18.5\"")
+                .replace("\"v3.11\"", "\"^3.11\"")
+                .replace("\"1.22\"", "\"^1.22\"")
+        );
+
+        // From schema version 2 on, a bare version is the release line.
+        let declared = ManifestEditor::new(
+            "schema-version = 2\n[dependencies]\nnode = \"18.5\"\n",
+            ManifestFormat::Toml,
+        )
+        .unwrap();
+        let manifest = declared.source().parse().unwrap();
+        assert_eq!(manifest.dependencies["node"].constraint().as_str(), "18.5");
+    }
+
+    #[test]
+    fn test_unversioned_manifests_are_current() {
+        let mut editor =
+            ManifestEditor::new("[project]\nname = \"demo\"\n", ManifestFormat::Toml).unwrap();
+        assert_eq!(
+            ManifestMigrator::version(&editor).unwrap(),
+            CURRENT_SCHEMA_VERSION
+        );
+        assert!(ManifestMigrator::pending(&editor).unwrap().is_empty());
+        assert!(ManifestMigrator::migrate(&mut editor).unwrap().is_empty());
+        assert_eq!(
+            editor.source().parse().unwrap().schema_version,
+            crate::EnhancedManifest::default().schema_version
+        );
+
+        let stale =
+            ManifestEditor::new("schema-version = 0\n[project]\n", ManifestFormat::Toml).unwrap();
+        assert_eq!(ManifestMigrator::version(&stale).unwrap(), 0);
+        assert!(ManifestMigrator::pending(&stale).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rejects_newer_versions() {
+        let mut editor =
+            ManifestEditor::new("{ \"schema-version\": 99 }", ManifestFormat::Json).unwrap();
+        assert!(ManifestMigrator::migrate(&mut editor).is_err());
+    }
+}
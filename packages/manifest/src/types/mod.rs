@@ -1,20 +1,52 @@
+pub mod cfg;
+pub mod checksum;
 pub mod config;
+pub mod constraint;
 pub mod dependencies;
+pub mod dotenv;
+pub mod edit;
+pub mod effective;
+pub mod env;
+pub mod families;
 pub mod global;
 pub mod intelligence;
+pub mod interpolation;
+pub mod lints;
 pub mod metadata;
+pub mod migration;
+pub mod patch;
+pub mod pins;
 pub mod platform;
+pub mod policy;
+pub mod scoring;
 pub mod scripts;
 pub mod security;
 pub mod service;
+pub mod source;
 pub mod validation;
 
+pub use cfg::*;
+pub use checksum::*;
 pub use config::*;
+pub use constraint::*;
 pub use dependencies::*;
+pub use dotenv::*;
+pub use edit::*;
+pub use effective::*;
+pub use env::*;
+pub use families::*;
 pub use intelligence::*;
+pub use interpolation::*;
+pub use lints::*;
 pub use metadata::*;
+pub use migration::*;
+pub use patch::*;
+pub use pins::*;
 pub use platform::*;
+pub use policy::*;
+pub use scoring::*;
 pub use scripts::*;
 pub use security::*;
 pub use service::*;
+pub use source::*;
 pub use validation::*;
@@ -0,0 +1,359 @@
+use crate::ManifestEditor;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// One RFC 6902 operation. Paths are JSON Pointers (RFC 6901) into the document,
+/// e.g. `/dependencies/node` or `/scripts/build/deps/-`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: Value },
+}
+
+/// The `patch` of a `config-update` action.
+///
+/// A JSON array is an RFC 6902 JSON Patch. Anything else is an RFC 7396 merge patch,
+/// written in JSON, TOML or YAML: its keys are set over the target, recursing into
+/// tables, and `null` removes a key. TOML has no null, so TOML merge patches can only
+/// add and change keys.
+///
+/// ```toml
+/// [dependencies]
+/// node = "^20"
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigPatch {
+    Json(Vec<PatchOperation>),
+    Merge(Map<String, Value>),
+}
+
+impl ConfigPatch {
+    pub fn parse(patch: &str) -> Result<Self> {
+        let merge = match serde_json::from_str::<Value>(patch) {
+            Ok(operations @ Value::Array(_)) => {
+                let operations =
+                    serde_json::from_value(operations).context("Invalid JSON Patch")?;
+                return Ok(ConfigPatch::Json(operations));
+            }
+            Ok(value) => Some(value),
+            Err(_) => toml::from_str::<Value>(patch)
+                .ok()
+                .or_else(|| serde_yaml::from_str::<Value>(patch).ok()),
+        };
+        match merge {
+            Some(Value::Object(map)) => Ok(ConfigPatch::Merge(map)),
+            _ => {
+                anyhow::bail!("Patch is neither a JSON Patch nor a JSON, TOML or YAML merge patch")
+            }
+        }
+    }
+
+    /// Apply the patch through `editor`, so untouched parts of the file keep their
+    /// formatting. On error the editor may hold a partly applied patch; don't save it.
+    pub fn apply(&self, editor: &mut ManifestEditor) -> Result<()> {
+        match self {
+            ConfigPatch::Json(operations) => operations
+                .iter()
+                .try_for_each(|operation| apply_operation(editor, operation)),
+            ConfigPatch::Merge(map) => merge(editor, &mut Vec::new(), map),
+        }
+    }
+}
+
+fn apply_operation(editor: &mut ManifestEditor, operation: &PatchOperation) -> Result<()> {
+    match operation {
+        PatchOperation::Add { path, value } => add(editor, &segments(path)?, value.clone()),
+        PatchOperation::Remove { path } => remove(editor, &segments(path)?).map(drop),
+        PatchOperation::Replace { path, value } => {
+            let path = segments(path)?;
+            anyhow::ensure!(
+                get(editor, &path).is_some(),
+                "Cannot replace '{}': it does not exist",
+                pointer(&path)
+            );
+            replace(editor, &path, value.clone())
+        }
+        PatchOperation::Move { from, path } => {
+            let (from, path) = (segments(from)?, segments(path)?);
+            anyhow::ensure!(
+                !path.starts_with(&from) || path == from,
+                "Cannot move '{}' into itself",
+                pointer(&from)
+            );
+            let value = remove(editor, &from)?;
+            add(editor, &path, value)
+        }
+        PatchOperation::Copy { from, path } => {
+            let from = segments(from)?;
+            let value = get(editor, &from)
+                .with_context(|| format!("Cannot copy '{}': it does not exist", pointer(&from)))?;
+            add(editor, &segments(path)?, value)
+        }
+        PatchOperation::Test { path, value } => {
+            let path = segments(path)?;
+            anyhow::ensure!(
+                get(editor, &path).as_ref() == Some(value),
+                "Test failed: '{}' is not {}",
+                pointer(&path),
+                value
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Set every key of `patch` under `prefix`, recursing into tables that exist.
+fn merge(
+    editor: &mut ManifestEditor,
+    prefix: &mut Vec<String>,
+    patch: &Map<String, Value>,
+) -> Result<()> {
+    for (key, value) in patch {
+        prefix.push(key.clone());
+        let keys: Vec<&str> = prefix.iter().map(String::as_str).collect();
+        match value {
+            Value::Null => {
+                editor.remove(&keys)?;
+            }
+            Value::Object(map)
+                if !map.is_empty() && editor.get(&keys).is_none_or(|v| v.is_object()) =>
+            {
+                merge(editor, prefix, map)?;
+            }
+            _ => editor.set(&keys, value.clone())?,
+        }
+        prefix.pop();
+    }
+    Ok(())
+}
+
+/// Decode an RFC 6901 pointer into its segments.
+fn segments(pointer: &str) -> Result<Vec<String>> {
+    if pointer.is_empty() {
+        return Ok(Vec::new());
+    }
+    let rest = pointer
+        .strip_prefix('/')
+        .with_context(|| format!("Invalid JSON Pointer '{}': must start with '/'", pointer))?;
+    Ok(rest
+        .split('/')
+        .map(|s| s.replace("~1", "/").replace("~0", "~"))
+        .collect())
+}
+
+fn pointer(segments: &[String]) -> String {
+    segments
+        .iter()
+        .map(|s| format!("/{}", s.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+/// How many leading segments name the first array on the way to `path`, if any.
+/// The editor addresses keys only, so anything inside an array is edited by
+/// rewriting that array.
+fn array_ancestor(editor: &ManifestEditor, path: &[String]) -> Option<usize> {
+    for depth in 1..path.len() {
+        let keys: Vec<&str> = path[..depth].iter().map(String::as_str).collect();
+        match editor.get(&keys)? {
+            Value::Array(_) => return Some(depth),
+            Value::Object(_) => continue,
+            _ => return None,
+        }
+    }
+    None
+}
+
+fn get(editor: &ManifestEditor, path: &[String]) -> Option<Value> {
+    let depth = array_ancestor(editor, path).unwrap_or(path.len());
+    let keys: Vec<&str> = path[..depth].iter().map(String::as_str).collect();
+    let value = editor.get(&keys)?;
+    value.pointer(&pointer(&path[depth..])).cloned()
+}
+
+/// Rewrite the array holding `path` with `edit` applied at the pointer below it.
+fn edit_array<T>(
+    editor: &mut ManifestEditor,
+    path: &[String],
+    depth: usize,
+    edit: impl FnOnce(&mut Value, &[String]) -> Result<T>,
+) -> Result<T> {
+    let keys: Vec<&str> = path[..depth].iter().map(String::as_str).collect();
+    let mut array = editor.get(&keys).expect("array ancestor exists");
+    let result = edit(&mut array, &path[depth..])?;
+    editor.set(&keys, array)?;
+    Ok(result)
+}
+
+fn add(editor: &mut ManifestEditor, path: &[String], value: Value) -> Result<()> {
+    let Some((last, parent)) = path.split_last() else {
+        anyhow::bail!("Cannot replace the whole document");
+    };
+    if let Some(depth) = array_ancestor(editor, path) {
+        return edit_array(editor, path, depth, |array, rest| {
+            value_add(array, rest, value)
+        });
+    }
+    anyhow::ensure!(
+        get(editor, parent).is_some_and(|v| v.is_object()),
+        "Cannot add '{}': '{}' is not a table",
+        pointer(path),
+        pointer(parent)
+    );
+    let mut keys: Vec<&str> = parent.iter().map(String::as_str).collect();
+    keys.push(last);
+    editor.set(&keys, value)
+}
+
+fn remove(editor: &mut ManifestEditor, path: &[String]) -> Result<Value> {
+    anyhow::ensure!(!path.is_empty(), "Cannot remove the whole document");
+    let value = get(editor, path)
+        .with_context(|| format!("Cannot remove '{}': it does not exist", pointer(path)))?;
+    match array_ancestor(editor, path) {
+        Some(depth) => edit_array(editor, path, depth, value_remove).map(drop)?,
+        None => {
+            let keys: Vec<&str> = path.iter().map(String::as_str).collect();
+            editor.remove(&keys)?;
+        }
+    }
+    Ok(value)
+}
+
+fn replace(editor: &mut ManifestEditor, path: &[String], value: Value) -> Result<()> {
+    anyhow::ensure!(!path.is_empty(), "Cannot replace the whole document");
+    match array_ancestor(editor, path) {
+        Some(depth) => edit_array(editor, path, depth, |array, rest| {
+            *array
+                .pointer_mut(&pointer(rest))
+                .expect("replaced path exists") = value;
+            Ok(())
+        }),
+        None => {
+            let keys: Vec<&str> = path.iter().map(String::as_str).collect();
+            editor.set(&keys, value)
+        }
+    }
+}
+
+/// RFC 6902 `add` on an in-memory value: `-` appends, an index inserts.
+fn value_add(root: &mut Value, path: &[String], value: Value) -> Result<()> {
+    let (last, parent) = path.split_last().expect("path below an array");
+    match root.pointer_mut(&pointer(parent)) {
+        Some(Value::Array(items)) => {
+            let index = if last == "-" {
+                items.len()
+            } else {
+                array_index(last, items.len() + 1)?
+            };
+            items.insert(index, value);
+        }
+        Some(Value::Object(map)) => {
+            map.insert(last.clone(), value);
+        }
+        _ => anyhow::bail!(
+            "Cannot add below '{}': not a table or array",
+            pointer(parent)
+        ),
+    }
+    Ok(())
+}
+
+fn value_remove(root: &mut Value, path: &[String]) -> Result<Value> {
+    let (last, parent) = path.split_last().expect("path below an array");
+    match root.pointer_mut(&pointer(parent)) {
+        Some(Value::Array(items)) => {
+            let index = array_index(last, items.len())?;
+            Ok(items.remove(index))
+        }
+        Some(Value::Object(map)) => map.remove(last).context("Key does not exist"),
+        _ => anyhow::bail!(
+            "Cannot remove below '{}': not a table or array",
+            pointer(parent)
+        ),
+    }
+}
+
+/// An array index below `bound`, as RFC 6901 writes it (no leading zeros).
+fn array_index(segment: &str, bound: usize) -> Result<usize> {
+    let index = segment
+        .parse::<usize>()
+        .ok()
+        .filter(|_| segment == "0" || !segment.starts_with('0'))
+        .with_context(|| format!("Invalid array index '{}'", segment))?;
+    anyhow::ensure!(index < bound, "Array index {} is out of bounds", index);
+    Ok(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ManifestFormat;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn test_json_patch_keeps_formatting() {
+        let mut editor = ManifestEditor::new(
+            "# Team setup\n[dependencies]\nnode = \"^18\" # LTS\npython = \"^3.11\"\n\n[dev-dependencies]\neslint = \"^8\"\n\n[project]\nauthors = [\"ana\"]\n",
+            ManifestFormat::Toml,
+        )
+        .unwrap();
+
+        let patch = ConfigPatch::parse(
+            r#"[
+                { "op": "test", "path": "/dependencies/node", "value": "^18" },
+                { "op": "replace", "path": "/dependencies/node", "value": "^20" },
+                { "op": "move", "from": "/dependencies/python", "path": "/dev-dependencies/python" },
+                { "op": "add", "path": "/project/authors/-", "value": "bo" },
+                { "op": "remove", "path": "/project/authors/0" }
+            ]"#,
+        )
+        .unwrap();
+        patch.apply(&mut editor).unwrap();
+
+        assert_eq!(
+            editor.to_string(),
+            "# Team setup\n[dependencies]\nnode = \"^20\" # LTS\n\n[dev-dependencies]\neslint = \"^8\"\npython = \"^3.11\"\n\n[project]\nauthors = [\"bo\"]\n"
+        );
+
+        let failing = ConfigPatch::parse(
+            r#"[{ "op": "test", "path": "/dependencies/node", "value": "^18" }]"#,
+        )
+        .unwrap();
+        assert!(failing.apply(&mut editor).is_err());
+        let missing = ConfigPatch::parse(r#"[{ "op": "remove", "path": "/env/FOO" }]"#).unwrap();
+        assert!(missing.apply(&mut editor).is_err());
+    }
+
+    #[test]
+    fn test_merge_patches() {
+        let mut editor = ManifestEditor::new(
+            "dependencies:\n  node: \"^18\" # LTS\n  python: \"^3.11\"\n",
+            ManifestFormat::Yaml,
+        )
+        .unwrap();
+        ConfigPatch::parse("dependencies:\n  node: \"^20\"\n  python: ~\nenv:\n  CI: \"1\"\n")
+            .unwrap()
+            .apply(&mut editor)
+            .unwrap();
+        assert_eq!(editor.get(&["dependencies", "node"]), Some("^20".into()));
+        assert!(!editor.contains(&["dependencies", "python"]));
+        assert_eq!(editor.get(&["env", "CI"]), Some("1".into()));
+
+        let patch = ConfigPatch::parse("[dependencies]\ngo = \"^1.22\"\n").unwrap();
+        let mut editor = ManifestEditor::new(
+            "{\n  \"dependencies\": {\n    \"node\": \"^18\"\n  }\n}\n",
+            ManifestFormat::Json,
+        )
+        .unwrap();
+        patch.apply(&mut editor).unwrap();
+        assert_eq!(editor.get(&["dependencies", "go"]), Some("^1.22".into()));
+        assert_eq!(editor.get(&["dependencies", "node"]), Some("^18".into()));
+
+        assert!(ConfigPatch::parse("just words").is_err());
+    }
+}
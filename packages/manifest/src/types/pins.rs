@@ -0,0 +1,413 @@
+use crate::{DependencySpec, Ecosystem, VersionConstraint};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Version pin files that `env import` reads, most specific first. When two files pin the
+/// same tool, the earlier one wins.
+pub const PIN_FILES: &[&str] = &[
+    ".nvmrc",
+    ".node-version",
+    ".python-version",
+    "rust-toolchain.toml",
+    "rust-toolchain",
+    "go.mod",
+    ".mise.toml",
+    ".tool-versions",
+    "package.json",
+];
+
+/// A tool version read from a pin file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionPin {
+    pub tool: String,
+    pub version: VersionConstraint,
+    /// The file it came from, e.g. `.nvmrc`.
+    pub source: String,
+}
+
+/// What one pin file yielded.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PinFile {
+    pub pins: Vec<VersionPin>,
+    /// Entries that couldn't be mapped to a version constraint, with the reason.
+    pub skipped: Vec<String>,
+}
+
+impl PinFile {
+    fn pin(&mut self, source: &str, tool: &str, raw: &str) {
+        let tool = tool_name(tool);
+        let raw = raw.trim();
+        // nvm and asdf accept `v20`; the manifest keeps the bare number.
+        let raw = match raw.strip_prefix('v') {
+            Some(rest) if rest.starts_with(|c: char| c.is_ascii_digit()) => rest,
+            _ => raw,
+        };
+
+        if raw == "system" || raw.starts_with("ref:") || raw.starts_with("path:") {
+            self.skipped
+                .push(format!("{} {}: not a version", tool, raw));
+            return;
+        }
+
+        match VersionConstraint::parse(&raw.to_ascii_lowercase()) {
+            Ok(version) => self.pins.push(VersionPin {
+                tool,
+                version,
+                source: source.to_string(),
+            }),
+            Err(e) => self.skipped.push(format!("{} {}: {}", tool, raw, e)),
+        }
+    }
+}
+
+/// Read the pins from one file, picking the parser by file name (see [`PIN_FILES`]).
+pub fn parse_pin_file(file: &str, content: &str) -> PinFile {
+    let mut out = PinFile::default();
+    match file {
+        ".nvmrc" | ".node-version" => {
+            if let Some(version) = first_line(content) {
+                out.pin(file, "node", version);
+            }
+        }
+        ".python-version" => {
+            // pyenv allows several versions; the first is the default `python`.
+            if let Some(version) = first_line(content) {
+                out.pin(file, "python", version);
+            }
+        }
+        "rust-toolchain" if !content.trim_start().starts_with('[') => {
+            if let Some(channel) = first_line(content) {
+                out.pin(file, "rust", channel);
+            }
+        }
+        "rust-toolchain" | "rust-toolchain.toml" => match toml::from_str::<Value>(content) {
+            Ok(doc) => {
+                if let Some(channel) = doc.pointer("/toolchain/channel").and_then(Value::as_str) {
+                    out.pin(file, "rust", channel);
+                }
+            }
+            Err(e) => out.skipped.push(format!("invalid TOML: {}", e.message())),
+        },
+        "go.mod" => {
+            // `toolchain go1.21.5` pins exactly; `go 1.21` is only a minimum.
+            let directive = |name: &str| {
+                content.lines().find_map(|line| {
+                    let line = line.split("//").next().unwrap_or_default().trim();
+                    line.strip_prefix(name)
+                        .filter(|rest| rest.starts_with([' ', '\t']))
+                        .map(str::trim)
+                })
+            };
+            if let Some(toolchain) = directive("toolchain") {
+                out.pin(file, "go", toolchain.trim_start_matches("go"));
+            } else if let Some(version) = directive("go") {
+                out.pin(file, "go", &format!(">={}", version));
+            }
+        }
+        ".tool-versions" => {
+            for line in content.lines() {
+                let line = line.split('#').next().unwrap_or_default();
+                let mut fields = line.split_whitespace();
+                // asdf falls back to later versions; the first is the one in use.
+                if let (Some(tool), Some(version)) = (fields.next(), fields.next()) {
+                    out.pin(file, tool, version);
+                }
+            }
+        }
+        ".mise.toml" => match toml::from_str::<Value>(content) {
+            Ok(doc) => {
+                let tools = doc.get("tools").and_then(Value::as_object);
+                for (tool, spec) in tools.into_iter().flatten() {
+                    let version = match spec {
+                        Value::Array(versions) => versions.first(),
+                        Value::Object(table) => table.get("version"),
+                        other => Some(other),
+                    };
+                    match version.and_then(Value::as_str) {
+                        Some(version) => out.pin(file, tool, version),
+                        None => out.skipped.push(format!("{}: no version", tool)),
+                    }
+                }
+            }
+            Err(e) => out.skipped.push(format!("invalid TOML: {}", e.message())),
+        },
+        "package.json" => match serde_json::from_str::<Value>(content) {
+            Ok(doc) => {
+                let engines = doc.get("engines").and_then(Value::as_object);
+                for (tool, range) in engines.into_iter().flatten() {
+                    match range.as_str().map(npm_range) {
+                        Some(Ok(range)) => out.pin("package.json engines", tool, &range),
+                        Some(Err(reason)) => out.skipped.push(format!("{} {}", tool, reason)),
+                        None => out.skipped.push(format!("{}: not a string", tool)),
+                    }
+                }
+            }
+            Err(e) => out.skipped.push(format!("invalid JSON: {}", e)),
+        },
+        _ => {}
+    }
+    out
+}
+
+/// The canonical manifest name for a tool, e.g. asdf's `nodejs` is `node`.
+fn tool_name(name: &str) -> String {
+    match name.to_ascii_lowercase().as_str() {
+        "nodejs" => "node".to_string(),
+        "golang" => "go".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn first_line(content: &str) -> Option<&str> {
+    content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty() && !line.starts_with('#'))
+}
+
+/// Rewrite an npm range (`>= 18 <21`) with the commas SemVer requirements need.
+fn npm_range(range: &str) -> Result<String, String> {
+    if range.contains("||") || range.contains(" - ") {
+        return Err(format!(
+            "{}: `||` and hyphen ranges aren't supported",
+            range
+        ));
+    }
+
+    let mut clauses: Vec<String> = Vec::new();
+    let mut pending_op = String::new();
+    for token in range.split_whitespace() {
+        if token.chars().all(|c| "<>=~^".contains(c)) {
+            pending_op.push_str(token);
+        } else {
+            clauses.push(format!("{}{}", std::mem::take(&mut pending_op), token));
+        }
+    }
+    Ok(clauses.join(", "))
+}
+
+/// One tool pinned differently by two files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PinConflict<'a> {
+    pub tool: &'a str,
+    /// The pin that wins (earliest in [`PIN_FILES`]).
+    pub chosen: &'a VersionPin,
+    pub other: &'a VersionPin,
+}
+
+/// Every pin found in a project's pin files.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PinImport {
+    /// In [`PIN_FILES`] order.
+    pub pins: Vec<VersionPin>,
+    /// `(file, reason)` for entries that couldn't be imported.
+    pub skipped: Vec<(String, String)>,
+}
+
+impl PinImport {
+    /// Read every pin file present in `root`.
+    pub fn scan(root: &Path) -> std::io::Result<Self> {
+        let mut files = Vec::new();
+        for file in PIN_FILES {
+            match std::fs::read_to_string(root.join(file)) {
+                Ok(content) => files.push((*file, content)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(Self::from_files(
+            files
+                .iter()
+                .map(|(file, content)| (*file, content.as_str())),
+        ))
+    }
+
+    /// Import from `(file name, content)` pairs.
+    pub fn from_files<'a>(files: impl IntoIterator<Item = (&'a str, &'a str)>) -> Self {
+        let mut files: Vec<_> = files.into_iter().collect();
+        files.sort_by_key(|(file, _)| {
+            PIN_FILES
+                .iter()
+                .position(|f| f == file)
+                .unwrap_or(PIN_FILES.len())
+        });
+
+        let mut import = Self::default();
+        for (file, content) in files {
+            let parsed = parse_pin_file(file, content);
+            import.pins.extend(parsed.pins);
+            import.skipped.extend(
+                parsed
+                    .skipped
+                    .into_iter()
+                    .map(|reason| (file.to_string(), reason)),
+            );
+        }
+        import
+    }
+
+    /// The winning pin for each tool.
+    pub fn chosen(&self) -> BTreeMap<&str, &VersionPin> {
+        let mut chosen = BTreeMap::new();
+        for pin in &self.pins {
+            chosen.entry(pin.tool.as_str()).or_insert(pin);
+        }
+        chosen
+    }
+
+    /// Pins that no version can satisfy together with the winning pin for their tool.
+    /// Channels (`lts/iron`, `stable`) can't be compared offline, so a channel that
+    /// differs from the winner counts as a conflict.
+    pub fn conflicts(&self) -> Vec<PinConflict<'_>> {
+        let chosen = self.chosen();
+        self.pins
+            .iter()
+            .filter_map(|pin| {
+                let winner = chosen[pin.tool.as_str()];
+                if std::ptr::eq(winner, pin) || winner.version == pin.version {
+                    return None;
+                }
+                let ecosystem = Ecosystem::for_dependency(&pin.tool, None);
+                let compatible = match (
+                    winner.version.range(ecosystem),
+                    pin.version.range(ecosystem),
+                ) {
+                    (Some(a), Some(b)) => a.intersects(&b),
+                    _ => false,
+                };
+                (!compatible).then_some(PinConflict {
+                    tool: &pin.tool,
+                    chosen: winner,
+                    other: pin,
+                })
+            })
+            .collect()
+    }
+
+    /// The winning pins as `[dependencies]` entries.
+    pub fn dependencies(&self) -> BTreeMap<String, DependencySpec> {
+        self.chosen()
+            .into_iter()
+            .map(|(tool, pin)| {
+                (
+                    tool.to_string(),
+                    DependencySpec::Simple(pin.version.clone()),
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn versions(file: &str, content: &str) -> Vec<(String, String)> {
+        parse_pin_file(file, content)
+            .pins
+            .into_iter()
+            .map(|pin| (pin.tool, pin.version.to_string()))
+            .collect()
+    }
+
+    fn pair(tool: &str, version: &str) -> (String, String) {
+        (tool.to_string(), version.to_string())
+    }
+
+    #[test]
+    fn test_parse_pin_files() {
+        assert_eq!(
+            versions(".nvmrc", "v18.17.0\n"),
+            vec![pair("node", "18.17.0")]
+        );
+        assert_eq!(
+            versions(".nvmrc", "lts/Hydrogen"),
+            vec![pair("node", "lts/hydrogen")]
+        );
+        assert_eq!(
+            versions(".python-version", "3.11.4\n3.10.12\n"),
+            vec![pair("python", "3.11.4")]
+        );
+        assert_eq!(
+            versions("rust-toolchain.toml", "[toolchain]\nchannel = \"1.75.0\"\n"),
+            vec![pair("rust", "1.75.0")]
+        );
+        assert_eq!(
+            versions("rust-toolchain", "stable\n"),
+            vec![pair("rust", "stable")]
+        );
+        assert_eq!(
+            versions("go.mod", "module example.com/x\n\ngo 1.21\n"),
+            vec![pair("go", ">=1.21")]
+        );
+        assert_eq!(
+            versions(
+                "go.mod",
+                "module x\ngo 1.21\ntoolchain go1.21.5 // pinned\n"
+            ),
+            vec![pair("go", "1.21.5")]
+        );
+        assert_eq!(
+            versions(
+                ".tool-versions",
+                "nodejs 20.9.0 18.18.0 # lts\ngolang 1.22.0\n"
+            ),
+            vec![pair("node", "20.9.0"), pair("go", "1.22.0")]
+        );
+        assert_eq!(
+            versions(
+                ".mise.toml",
+                "[tools]\nnode = \"20\"\npython = [\"3.12\", \"3.11\"]\nrust = { version = \"stable\" }\n"
+            ),
+            vec![pair("node", "20"), pair("python", "3.12"), pair("rust", "stable")]
+        );
+        assert_eq!(
+            versions(
+                "package.json",
+                r#"{"engines": {"node": ">= 18 <21", "npm": "^10"}}"#
+            ),
+            vec![pair("node", ">=18, <21"), pair("npm", "^10")]
+        );
+    }
+
+    #[test]
+    fn test_unmappable_entries_are_skipped() {
+        let parsed = parse_pin_file(
+            ".tool-versions",
+            "python system\nnodejs 20.1.0\nruby ref:main\n",
+        );
+        assert_eq!(parsed.pins.len(), 1);
+        assert_eq!(parsed.skipped.len(), 2);
+
+        let parsed = parse_pin_file("package.json", r#"{"engines": {"node": "^16 || ^18"}}"#);
+        assert!(parsed.pins.is_empty());
+        assert!(parsed.skipped[0].contains("||"));
+
+        assert!(parse_pin_file(".mise.toml", "[tools").pins.is_empty());
+    }
+
+    #[test]
+    fn test_precedence_and_conflicts() {
+        let import = PinImport::from_files([
+            ("package.json", r#"{"engines": {"node": ">=18"}}"#),
+            (".tool-versions", "nodejs 16.20.0\npython 3.11.4\n"),
+            (".nvmrc", "20.9.0"),
+            (".python-version", "3.11.4"),
+        ]);
+
+        let chosen = import.chosen();
+        assert_eq!(chosen["node"].source, ".nvmrc");
+        assert_eq!(chosen["python"].source, ".python-version");
+
+        // `>=18` agrees with 20.9.0 and identical pins never conflict.
+        let conflicts = import.conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].tool, "node");
+        assert_eq!(conflicts[0].other.source, ".tool-versions");
+
+        let deps = import.dependencies();
+        assert_eq!(deps["node"].constraint().as_str(), "20.9.0");
+        assert_eq!(deps.len(), 2);
+    }
+}
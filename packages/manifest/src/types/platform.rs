@@ -1,4 +1,5 @@
 use schemars::JsonSchema;
+use semver::{Version, VersionReq};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -45,12 +46,228 @@ pub enum CpuArchitecture {
     #[serde(alias = "arm64")]
     Arm64,
     Arm,
+    #[serde(alias = "i686", alias = "i386")]
+    X86,
     Wasm32,
     Riscv64,
     #[serde(rename = "*")]
     Any,
 }
 
+impl OperatingSystem {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OperatingSystem::Linux => "linux",
+            OperatingSystem::Macos => "macos",
+            OperatingSystem::Windows => "windows",
+            OperatingSystem::Freebsd => "freebsd",
+            OperatingSystem::Openbsd => "openbsd",
+            OperatingSystem::Netbsd => "netbsd",
+            OperatingSystem::Dragonfly => "dragonfly",
+            OperatingSystem::Ios => "ios",
+            OperatingSystem::Android => "android",
+            OperatingSystem::Any => "*",
+        }
+    }
+
+    /// Whether this constraint entry admits `actual` (`*` admits everything).
+    pub fn admits(&self, actual: OperatingSystem) -> bool {
+        *self == OperatingSystem::Any || *self == actual
+    }
+}
+
+impl CpuArchitecture {
+    /// Collapse aliases: `amd64` is `x86_64`, `arm64` is `aarch64`.
+    pub fn canonical(&self) -> Self {
+        match self {
+            CpuArchitecture::Amd64 => CpuArchitecture::X86_64,
+            CpuArchitecture::Arm64 => CpuArchitecture::Aarch64,
+            other => *other,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self.canonical() {
+            CpuArchitecture::X86_64 => "x86_64",
+            CpuArchitecture::Aarch64 => "aarch64",
+            CpuArchitecture::Arm => "arm",
+            CpuArchitecture::X86 => "x86",
+            CpuArchitecture::Wasm32 => "wasm32",
+            CpuArchitecture::Riscv64 => "riscv64",
+            _ => "*",
+        }
+    }
+
+    /// Whether this constraint entry admits `actual`, treating aliases as equal.
+    pub fn admits(&self, actual: CpuArchitecture) -> bool {
+        *self == CpuArchitecture::Any || self.canonical() == actual.canonical()
+    }
+}
+
+/// Read an OS version as SemVer, ignoring zero padding and build suffixes:
+/// `22.04` is 22.4.0, `6.5.0-14-generic` is 6.5.0, `10.0.22631` stays as is.
+pub fn normalize_os_version(raw: &str) -> Option<Version> {
+    let start = raw.find(|c: char| c.is_ascii_digit())?;
+    let rest = &raw[start..];
+    let end = rest
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(rest.len());
+
+    let mut parts = rest[..end]
+        .split('.')
+        .filter(|p| !p.is_empty())
+        .map(|p| p.parse::<u64>());
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().transpose().ok()?.unwrap_or(0);
+    let patch = parts.next().transpose().ok()?.unwrap_or(0);
+    Some(Version::new(major, minor, patch))
+}
+
+/// Parse an OS version requirement such as `>=12.0` or `>=22.04`; zero-padded
+/// components (which SemVer rejects) are read as plain numbers.
+pub fn parse_os_version_req(raw: &str) -> Result<VersionReq, semver::Error> {
+    let mut normalized = String::with_capacity(raw.len());
+    let mut digits = String::new();
+    for c in raw.chars().chain(std::iter::once('\0')) {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        if !digits.is_empty() {
+            let trimmed = digits.trim_start_matches('0');
+            normalized.push_str(if trimmed.is_empty() { "0" } else { trimmed });
+            digits.clear();
+        }
+        if c != '\0' {
+            normalized.push(c);
+        }
+    }
+    VersionReq::parse(&normalized)
+}
+
+/// The concrete platform a manifest is being evaluated for.
+///
+/// Used to select `[target.*]` dependency tables when computing the effective manifest.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct TargetPlatform {
+    pub os: OperatingSystem,
+    pub arch: CpuArchitecture,
+    /// C library / ABI, when known (`cfg(libc = "musl")`).
+    #[serde(default)]
+    pub env: Option<TargetEnv>,
+    /// OS release as reported by the system (`14.2.1`, `22.04`), for `platform.requirements`.
+    #[serde(default)]
+    pub os_version: Option<String>,
+}
+
+/// C library / ABI of a target (Rust's `target_env`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TargetEnv {
+    Gnu,
+    Musl,
+    Msvc,
+}
+
+impl TargetEnv {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TargetEnv::Gnu => "gnu",
+            TargetEnv::Musl => "musl",
+            TargetEnv::Msvc => "msvc",
+        }
+    }
+}
+
+impl std::str::FromStr for TargetEnv {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "gnu" | "glibc" => Ok(TargetEnv::Gnu),
+            "musl" => Ok(TargetEnv::Musl),
+            "msvc" => Ok(TargetEnv::Msvc),
+            _ => Err(format!("Unknown target env '{}'", s)),
+        }
+    }
+}
+
+impl TargetPlatform {
+    pub fn new(os: OperatingSystem, arch: CpuArchitecture) -> Self {
+        Self {
+            os,
+            arch,
+            env: None,
+            os_version: None,
+        }
+    }
+
+    pub fn with_os_version(mut self, version: impl Into<String>) -> Self {
+        self.os_version = Some(version.into());
+        self
+    }
+
+    pub fn with_env(mut self, env: TargetEnv) -> Self {
+        self.env = Some(env);
+        self
+    }
+
+    /// The platform this binary is running on.
+    pub fn current() -> Self {
+        let os =
+            serde_json::from_value(serde_json::Value::String(std::env::consts::OS.to_string()))
+                .unwrap_or(OperatingSystem::Any);
+        let arch = serde_json::from_value(serde_json::Value::String(
+            std::env::consts::ARCH.to_string(),
+        ))
+        .unwrap_or(CpuArchitecture::Any);
+        let env = if cfg!(target_env = "musl") {
+            Some(TargetEnv::Musl)
+        } else if cfg!(target_env = "gnu") {
+            Some(TargetEnv::Gnu)
+        } else if cfg!(target_env = "msvc") {
+            Some(TargetEnv::Msvc)
+        } else {
+            None
+        };
+
+        Self {
+            os,
+            arch,
+            env,
+            os_version: None,
+        }
+    }
+
+    /// Check whether a triple-like `[target.<key>]` table applies to this platform
+    /// (`cfg(...)` keys go through [`crate::TargetSelector`]).
+    ///
+    /// The key is split on `-` and every component naming a known OS or architecture
+    /// must match (e.g. `linux`, `aarch64`, `x86_64-apple-darwin`). Unrecognized
+    /// components such as vendor or ABI are ignored.
+    pub fn matches_target_key(&self, key: &str) -> bool {
+        let mut recognized = false;
+
+        for part in key.split('-') {
+            let value = serde_json::Value::String(part.to_lowercase());
+
+            if let Ok(os) = serde_json::from_value::<OperatingSystem>(value.clone()) {
+                recognized = true;
+                if !os.admits(self.os) {
+                    return false;
+                }
+            } else if let Ok(arch) = serde_json::from_value::<CpuArchitecture>(value) {
+                recognized = true;
+                if !arch.admits(self.arch) {
+                    return false;
+                }
+            }
+        }
+
+        recognized
+    }
+}
+
 fn default_all_platforms() -> Vec<OperatingSystem> {
     vec![OperatingSystem::Any]
 }
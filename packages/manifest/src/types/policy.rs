@@ -0,0 +1,128 @@
+use crate::{EnhancedManifest, ValidationResult};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// What to do with a conflict when nobody is there to pick a resolution.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum PolicyRule {
+    /// The top-ranked recommendation.
+    #[default]
+    Best,
+    /// Install the required version next to the existing one.
+    Alongside,
+    /// Replace the installed version.
+    Replace,
+    /// Keep an installed version that partially matches.
+    UseExisting,
+    /// Shim the published artifact into this project only.
+    AutoShim,
+    /// Leave the conflict unresolved and carry on.
+    Skip,
+    /// Stop with an error.
+    Fail,
+}
+
+impl fmt::Display for PolicyRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PolicyRule::Best => "best",
+            PolicyRule::Alongside => "alongside",
+            PolicyRule::Replace => "replace",
+            PolicyRule::UseExisting => "use-existing",
+            PolicyRule::AutoShim => "auto-shim",
+            PolicyRule::Skip => "skip",
+            PolicyRule::Fail => "fail",
+        };
+        f.write_str(name)
+    }
+}
+
+/// `[resolution-policy]`: how `resolve` settles conflicts non-interactively (CI, or
+/// `--non-interactive`). Also accepted standalone via `--policy <file>`.
+///
+/// ```toml
+/// [resolution-policy]
+/// default = "alongside"
+/// tools = { python = "fail" }
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ResolutionPolicy {
+    /// Rule for tools without their own (default `best`).
+    #[serde(default)]
+    pub default: PolicyRule,
+
+    /// Per-tool rules.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub tools: HashMap<String, PolicyRule>,
+}
+
+impl ResolutionPolicy {
+    /// The rule for `tool` and where it came from (`tools.node` or `default`).
+    pub fn rule_for(&self, tool: &str) -> (PolicyRule, String) {
+        match self.tools.get(tool) {
+            Some(rule) => (*rule, format!("tools.{}", tool)),
+            None => (self.default, "default".to_string()),
+        }
+    }
+}
+
+/// Warn about per-tool rules that name no dependency, usually a typo.
+pub fn validate_resolution_policy(manifest: &EnhancedManifest, result: &mut ValidationResult) {
+    let Some(policy) = &manifest.resolution_policy else {
+        return;
+    };
+    let mut tools: Vec<&String> = policy.tools.keys().collect();
+    tools.sort();
+    for tool in tools {
+        let declared = [
+            &manifest.dependencies,
+            &manifest.dev_dependencies,
+            &manifest.test_dependencies,
+            &manifest.build_dependencies,
+        ]
+        .iter()
+        .any(|deps| deps.contains_key(tool));
+        if !declared {
+            result.add_warning(
+                format!("resolution-policy.tools.{}", tool),
+                format!("'{}' is not a dependency, so this rule never applies", tool),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_rules() {
+        let policy: ResolutionPolicy =
+            toml::from_str("default = \"alongside\"\ntools = { python = \"fail\" }\n").unwrap();
+        assert_eq!(
+            policy.rule_for("python"),
+            (PolicyRule::Fail, "tools.python".to_string())
+        );
+        assert_eq!(
+            policy.rule_for("node"),
+            (PolicyRule::Alongside, "default".to_string())
+        );
+        assert_eq!(
+            ResolutionPolicy::default().rule_for("node").0,
+            PolicyRule::Best
+        );
+        assert!(toml::from_str::<ResolutionPolicy>("default = \"yolo\"\n").is_err());
+
+        let manifest = EnhancedManifest {
+            resolution_policy: Some(policy),
+            ..Default::default()
+        };
+        let mut result = ValidationResult::new();
+        validate_resolution_policy(&manifest, &mut result);
+        assert_eq!(result.issues[0].field, "resolution-policy.tools.python");
+    }
+}
@@ -0,0 +1,159 @@
+use crate::ValidationResult;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Starting weights for `[scoring]`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum ScoringPreset {
+    /// Avoid risk first, then breaking other projects.
+    #[default]
+    Balanced,
+    /// Throwaway machines: get done fast, disk and other projects barely matter.
+    Ci,
+    /// Shared laptops: never touch what other projects use, keep everything undoable.
+    Isolation,
+}
+
+impl ScoringPreset {
+    pub fn weights(self) -> ScoringWeights {
+        let (risk, disk, time, affected_projects, reversibility) = match self {
+            ScoringPreset::Balanced => (3.0, 1.0, 1.0, 2.0, 2.0),
+            ScoringPreset::Ci => (2.0, 0.5, 4.0, 0.5, 0.5),
+            ScoringPreset::Isolation => (3.0, 0.5, 0.5, 4.0, 3.0),
+        };
+        ScoringWeights {
+            risk,
+            disk,
+            time,
+            affected_projects,
+            reversibility,
+        }
+    }
+}
+
+/// How much each objective counts when ranking conflict resolutions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ScoringWeights {
+    pub risk: f64,
+    pub disk: f64,
+    pub time: f64,
+    pub affected_projects: f64,
+    pub reversibility: f64,
+}
+
+impl Default for ScoringWeights {
+    fn default() -> Self {
+        ScoringPreset::Balanced.weights()
+    }
+}
+
+/// `[scoring]`: a preset plus per-objective weight overrides, in the manifest or the
+/// user config (`scoring = { preset = "ci", disk = 0 }`).
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default, JsonSchema)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct ScoringConfig {
+    /// Weights to start from (default `balanced`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preset: Option<ScoringPreset>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub risk: Option<f64>,
+
+    /// Extra disk space used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disk: Option<f64>,
+
+    /// Time to install.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<f64>,
+
+    /// Other projects on this machine that would break.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub affected_projects: Option<f64>,
+
+    /// Whether the change can be undone without reinstalling.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reversibility: Option<f64>,
+}
+
+impl ScoringConfig {
+    /// `other` layered over this one: its preset and every weight it sets win.
+    pub fn merge(&self, other: &ScoringConfig) -> ScoringConfig {
+        ScoringConfig {
+            preset: other.preset.or(self.preset),
+            risk: other.risk.or(self.risk),
+            disk: other.disk.or(self.disk),
+            time: other.time.or(self.time),
+            affected_projects: other.affected_projects.or(self.affected_projects),
+            reversibility: other.reversibility.or(self.reversibility),
+        }
+    }
+
+    pub fn weights(&self) -> ScoringWeights {
+        let preset = self.preset.unwrap_or_default().weights();
+        ScoringWeights {
+            risk: self.risk.unwrap_or(preset.risk),
+            disk: self.disk.unwrap_or(preset.disk),
+            time: self.time.unwrap_or(preset.time),
+            affected_projects: self.affected_projects.unwrap_or(preset.affected_projects),
+            reversibility: self.reversibility.unwrap_or(preset.reversibility),
+        }
+    }
+}
+
+/// Check `[scoring]`: weights must be finite and non-negative, and not all zero.
+pub fn validate_scoring(scoring: &ScoringConfig, result: &mut ValidationResult) {
+    let weights = [
+        ("risk", scoring.risk),
+        ("disk", scoring.disk),
+        ("time", scoring.time),
+        ("affected-projects", scoring.affected_projects),
+        ("reversibility", scoring.reversibility),
+    ];
+    for (name, weight) in weights {
+        if let Some(weight) = weight.filter(|w| !w.is_finite() || *w < 0.0) {
+            result.add_error(
+                format!("scoring.{}", name),
+                format!("Weight must be a non-negative number, got {}", weight),
+            );
+        }
+    }
+
+    let w = scoring.weights();
+    if w.risk + w.disk + w.time + w.affected_projects + w.reversibility == 0.0 {
+        result.add_error("scoring", "At least one weight must be above zero");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scoring_layers_and_validation() {
+        let user: ScoringConfig = toml::from_str("preset = \"isolation\"\ndisk = 2.0\n").unwrap();
+        let project: ScoringConfig = toml::from_str("preset = \"ci\"\n").unwrap();
+
+        let weights = user.merge(&project).weights();
+        assert_eq!(weights.time, 4.0);
+        assert_eq!(weights.disk, 2.0);
+        assert_eq!(
+            ScoringConfig::default().weights(),
+            ScoringWeights::default()
+        );
+
+        let mut result = ValidationResult::new();
+        let bad: ScoringConfig = toml::from_str("time = -1.0\n").unwrap();
+        validate_scoring(&bad, &mut result);
+        let zero: ScoringConfig = toml::from_str(
+            "risk = 0.0\ndisk = 0.0\ntime = 0.0\naffected-projects = 0.0\nreversibility = 0.0\n",
+        )
+        .unwrap();
+        validate_scoring(&zero, &mut result);
+        let fields: Vec<&str> = result.issues.iter().map(|i| i.field.as_str()).collect();
+        assert_eq!(fields, ["scoring.time", "scoring"]);
+
+        assert!(toml::from_str::<ScoringConfig>("speed = 1.0\n").is_err());
+    }
+}
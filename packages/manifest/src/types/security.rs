@@ -35,6 +35,24 @@ pub enum Capability {
     EnvRead(Vec<String>),
 }
 
+/// Whether any `network` capability lets code reach `host`. Entries are exact host
+/// names, `*.example.com` for its subdomains, or `*` for anywhere.
+pub fn network_allows(capabilities: &[Capability], host: &str) -> bool {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    capabilities.iter().any(|capability| match capability {
+        Capability::Network(hosts) => hosts.iter().any(|allowed| {
+            let allowed = allowed.trim_end_matches('.').to_ascii_lowercase();
+            match allowed.strip_prefix("*.") {
+                Some(domain) => host
+                    .strip_suffix(domain)
+                    .is_some_and(|sub| sub.ends_with('.') && sub.len() > 1),
+                None => allowed == "*" || allowed == host,
+            }
+        }),
+        _ => false,
+    })
+}
+
 pub fn deserialize_capability_list<'de, D>(
     deserializer: D,
 ) -> Result<Option<Vec<Capability>>, D::Error>
@@ -152,4 +170,22 @@ mod tests {
         assert!(caps.contains(&Capability::UiInteract));
         assert!(caps.contains(&Capability::Network(vec!["github.com".to_string()])));
     }
+
+    #[test]
+    fn test_network_allows() {
+        let caps = vec![
+            Capability::UiInteract,
+            Capability::Network(vec!["github.com".to_string(), "*.nodejs.org".to_string()]),
+        ];
+        assert!(network_allows(&caps, "GitHub.com"));
+        assert!(network_allows(&caps, "dl.nodejs.org"));
+        assert!(!network_allows(&caps, "nodejs.org"));
+        assert!(!network_allows(&caps, "evilnodejs.org"));
+        assert!(!network_allows(&caps, "api.github.com"));
+        assert!(!network_allows(&[Capability::UiInteract], "github.com"));
+        assert!(network_allows(
+            &[Capability::Network(vec!["*".to_string()])],
+            "example.com"
+        ));
+    }
 }
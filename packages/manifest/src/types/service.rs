@@ -1,3 +1,4 @@
+use crate::EnvValue;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -18,7 +19,7 @@ pub struct ServiceDef {
 
     /// Environment variables specific to this service.
     #[serde(default)]
-    pub env: HashMap<String, String>,
+    pub env: HashMap<String, EnvValue>,
 }
 
 impl ServiceDef {
@@ -0,0 +1,817 @@
+use crate::{
+    EnhancedManifest, ManifestEditor, ManifestMigrator, ManifestValidator, ValidationResult,
+    CURRENT_SCHEMA_VERSION, SCHEMA_VERSION_KEY,
+};
+use anyhow::{Context, Result};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// Supported manifest formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ManifestFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl ManifestFormat {
+    /// Detect the format from a file extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "json" => Some(ManifestFormat::Json),
+            "yaml" | "yml" => Some(ManifestFormat::Yaml),
+            "toml" => Some(ManifestFormat::Toml),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ManifestFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ManifestFormat::Json => write!(f, "JSON"),
+            ManifestFormat::Yaml => write!(f, "YAML"),
+            ManifestFormat::Toml => write!(f, "TOML"),
+        }
+    }
+}
+
+/// A location in a manifest file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct SourceSpan {
+    /// The manifest file, when parsed from disk.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+
+    /// 1-based line number.
+    pub line: usize,
+
+    /// 1-based column (in characters).
+    pub column: usize,
+
+    /// Byte offset of the start of the span.
+    pub offset: usize,
+
+    /// Length of the span in bytes.
+    pub length: usize,
+
+    /// The full source line the span starts on.
+    pub snippet: String,
+}
+
+impl fmt::Display for SourceSpan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(f, "{}:{}:{}", path.display(), self.line, self.column),
+            None => write!(f, "{}:{}", self.line, self.column),
+        }
+    }
+}
+
+/// A manifest failed to parse.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+#[error("Failed to parse {format} manifest{}: {message}", .span.as_ref().map(|s| format!(" at {}", s)).unwrap_or_default())]
+pub struct ParseError {
+    pub format: ManifestFormat,
+    pub message: String,
+    pub span: Option<SourceSpan>,
+}
+
+/// Manifest text together with the location of every key in it.
+///
+/// Parsing keeps the source around so parse errors and validation issues can point at the
+/// offending line and column. TOML spans come from `toml_edit`; JSON and YAML are indexed
+/// by a lightweight scanner, since their serde parsers only report error positions.
+#[derive(Debug, Clone)]
+pub struct ManifestSource {
+    pub path: Option<PathBuf>,
+    pub content: String,
+    pub format: ManifestFormat,
+    spans: HashMap<String, Range<usize>>,
+}
+
+impl ManifestSource {
+    pub fn new(content: impl Into<String>, format: ManifestFormat) -> Self {
+        let content = content.into();
+        let mut spans = HashMap::new();
+        match format {
+            ManifestFormat::Toml => index_toml(&content, &mut spans),
+            ManifestFormat::Json => index_json(&content, &mut spans),
+            ManifestFormat::Yaml => index_yaml(&content, &mut spans),
+        }
+
+        Self {
+            path: None,
+            content,
+            format,
+            spans,
+        }
+    }
+
+    /// Read a manifest file, detecting the format from its extension.
+    pub fn read(path: &Path) -> Result<Self> {
+        let format = ManifestFormat::from_path(path)
+            .with_context(|| format!("Unsupported manifest format: {}", path.display()))?;
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read manifest file: {}", path.display()))?;
+
+        Ok(Self::new(content, format).with_path(path))
+    }
+
+    pub fn with_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Deserialize the manifest, upgrading documents written for an older schema version.
+    pub fn parse(&self) -> Result<EnhancedManifest, ParseError> {
+        let Some(version) = self.outdated_version()? else {
+            return self.deserialize();
+        };
+
+        let error = |e: anyhow::Error| ParseError {
+            format: self.format,
+            message: format!("{:#}", e),
+            span: self.span_of(SCHEMA_VERSION_KEY),
+        };
+        let mut editor = ManifestEditor::new(&self.content, self.format).map_err(error)?;
+        ManifestMigrator::upgrade(&mut editor).map_err(error)?;
+
+        let upgraded = Self {
+            content: editor.to_string(),
+            ..self.clone()
+        };
+        let mut manifest = upgraded.deserialize()?;
+        if version < CURRENT_SCHEMA_VERSION {
+            manifest.schema_version = Some(CURRENT_SCHEMA_VERSION);
+        }
+        Ok(manifest)
+    }
+
+    /// The document's schema version when it differs from [`CURRENT_SCHEMA_VERSION`].
+    /// Syntax errors are left for deserialization to report.
+    pub fn outdated_version(&self) -> Result<Option<u32>, ParseError> {
+        let Ok(editor) = ManifestEditor::new(&self.content, self.format) else {
+            return Ok(None);
+        };
+        match ManifestMigrator::version(&editor) {
+            Ok(CURRENT_SCHEMA_VERSION) => Ok(None),
+            Ok(version) => Ok(Some(version)),
+            Err(e) => Err(ParseError {
+                format: self.format,
+                message: e.to_string(),
+                span: self.span_of(SCHEMA_VERSION_KEY),
+            }),
+        }
+    }
+
+    /// Whether `env-architect migrate` would change anything besides the version stamp.
+    fn has_pending_migrations(&self) -> bool {
+        ManifestEditor::new(&self.content, self.format)
+            .ok()
+            .and_then(|editor| ManifestMigrator::pending(&editor).ok())
+            .is_some_and(|steps| !steps.is_empty())
+    }
+
+    fn deserialize(&self) -> Result<EnhancedManifest, ParseError> {
+        let (message, span) = match self.format {
+            ManifestFormat::Toml => match toml::from_str(&self.content) {
+                Ok(manifest) => return Ok(manifest),
+                Err(e) => (
+                    e.message().to_string(),
+                    e.span().map(|range| self.span(range)),
+                ),
+            },
+            ManifestFormat::Json => match serde_json::from_str(&self.content) {
+                Ok(manifest) => return Ok(manifest),
+                Err(e) => {
+                    let suffix = format!(" at line {} column {}", e.line(), e.column());
+                    let message = e.to_string();
+                    let message = message.strip_suffix(&suffix).unwrap_or(&message);
+                    (
+                        message.to_string(),
+                        self.span_at_line_column(e.line(), e.column()),
+                    )
+                }
+            },
+            ManifestFormat::Yaml => match serde_yaml::from_str(&self.content) {
+                Ok(manifest) => return Ok(manifest),
+                Err(e) => {
+                    let message = e.to_string();
+                    match e.location() {
+                        Some(location) => {
+                            let suffix = format!(
+                                " at line {} column {}",
+                                location.line(),
+                                location.column()
+                            );
+                            (
+                                message
+                                    .strip_suffix(&suffix)
+                                    .unwrap_or(&message)
+                                    .to_string(),
+                                self.span_at_line_column(location.line(), location.column()),
+                            )
+                        }
+                        None => (message, None),
+                    }
+                }
+            },
+        };
+
+        Err(ParseError {
+            format: self.format,
+            message,
+            span,
+        })
+    }
+
+    /// Parse and validate, attaching a source location to every issue that has one.
+    pub fn validate(&self) -> Result<(EnhancedManifest, ValidationResult), ParseError> {
+        let manifest = self.parse()?;
+        let mut result = ManifestValidator::validate(&manifest);
+        if let Some(version) = self
+            .outdated_version()?
+            .filter(|_| self.has_pending_migrations())
+        {
+            result.add_warning(
+                SCHEMA_VERSION_KEY,
+                format!(
+                    "Manifest uses schema version {}; run `env-architect migrate` to upgrade it to {}",
+                    version, CURRENT_SCHEMA_VERSION
+                ),
+            );
+        }
+        self.annotate(&mut result);
+        Ok((manifest, result))
+    }
+
+    /// Fill in `location` for issues whose field can be found in the source.
+    pub fn annotate(&self, result: &mut ValidationResult) {
+        for issue in &mut result.issues {
+            if issue.location.is_none() {
+                issue.location = self.span_of(&issue.field);
+            }
+        }
+    }
+
+    /// Locate a dotted field path (`profiles.dev.env.PATH`, `scripts.build[0]`).
+    ///
+    /// Falls back to the closest enclosing key when the exact field isn't written in the
+    /// file, e.g. an issue about a missing entry points at its parent table.
+    pub fn span_of(&self, field: &str) -> Option<SourceSpan> {
+        let mut key = field.replace('[', ".").replace(']', "");
+        loop {
+            if let Some(range) = self.spans.get(&key) {
+                return Some(self.span(range.clone()));
+            }
+            key = key.rsplit_once('.')?.0.to_string();
+        }
+    }
+
+    /// Build a span for a byte range of the content.
+    pub fn span(&self, range: Range<usize>) -> SourceSpan {
+        let offset = floor_char_boundary(&self.content, range.start.min(self.content.len()));
+        let line_start = self.content[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = self.content[offset..]
+            .find('\n')
+            .map_or(self.content.len(), |i| offset + i);
+
+        SourceSpan {
+            path: self.path.clone(),
+            line: self.content[..offset].matches('\n').count() + 1,
+            column: self.content[line_start..offset].chars().count() + 1,
+            offset,
+            length: range.end.saturating_sub(range.start),
+            snippet: self.content[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string(),
+        }
+    }
+
+    fn span_at_line_column(&self, line: usize, column: usize) -> Option<SourceSpan> {
+        if line == 0 {
+            return None;
+        }
+        let line_start = if line == 1 {
+            0
+        } else {
+            self.content
+                .match_indices('\n')
+                .nth(line - 2)
+                .map(|(i, _)| i + 1)?
+        };
+        let line_text = self.content[line_start..].lines().next().unwrap_or("");
+        let column_offset = line_text
+            .char_indices()
+            .nth(column.saturating_sub(1))
+            .map_or(line_text.len(), |(i, _)| i);
+
+        let offset = line_start + column_offset;
+        Some(self.span(offset..offset + 1))
+    }
+}
+
+fn floor_char_boundary(s: &str, mut index: usize) -> usize {
+    while !s.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+fn child_path(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+fn index_toml(content: &str, spans: &mut HashMap<String, Range<usize>>) {
+    if let Ok(doc) = toml_edit::ImDocument::parse(content) {
+        index_toml_table(doc.as_table(), "", spans);
+    }
+}
+
+fn index_toml_table(
+    table: &toml_edit::Table,
+    path: &str,
+    spans: &mut HashMap<String, Range<usize>>,
+) {
+    for (name, _) in table.iter() {
+        if let Some((key, item)) = table.get_key_value(name) {
+            let child = child_path(path, name);
+            if let Some(range) = key.span().or_else(|| item.span()) {
+                spans.insert(child.clone(), range);
+            }
+            index_toml_item(item, &child, spans);
+        }
+    }
+}
+
+fn index_toml_item(item: &toml_edit::Item, path: &str, spans: &mut HashMap<String, Range<usize>>) {
+    match item {
+        toml_edit::Item::Table(table) => index_toml_table(table, path, spans),
+        toml_edit::Item::ArrayOfTables(tables) => {
+            for (i, table) in tables.iter().enumerate() {
+                let child = child_path(path, &i.to_string());
+                if let Some(range) = table.span() {
+                    spans.insert(child.clone(), range);
+                }
+                index_toml_table(table, &child, spans);
+            }
+        }
+        toml_edit::Item::Value(value) => index_toml_value(value, path, spans),
+        toml_edit::Item::None => {}
+    }
+}
+
+fn index_toml_value(
+    value: &toml_edit::Value,
+    path: &str,
+    spans: &mut HashMap<String, Range<usize>>,
+) {
+    match value {
+        toml_edit::Value::InlineTable(table) => {
+            for (name, _) in table.iter() {
+                if let Some((key, item)) = table.get_key_value(name) {
+                    let child = child_path(path, name);
+                    if let Some(range) = key.span().or_else(|| item.span()) {
+                        spans.insert(child.clone(), range);
+                    }
+                    index_toml_item(item, &child, spans);
+                }
+            }
+        }
+        toml_edit::Value::Array(array) => {
+            for (i, value) in array.iter().enumerate() {
+                let child = child_path(path, &i.to_string());
+                if let Some(range) = value.span() {
+                    spans.insert(child.clone(), range);
+                }
+                index_toml_value(value, &child, spans);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn index_json(content: &str, spans: &mut HashMap<String, Range<usize>>) {
+    if let Some(root) = JsonNode::parse(content) {
+        index_json_node(&root, "", spans);
+    }
+}
+
+fn index_json_node(node: &JsonNode, path: &str, spans: &mut HashMap<String, Range<usize>>) {
+    match node {
+        JsonNode::Object { members, .. } => {
+            for member in members {
+                let child = child_path(path, &member.key);
+                spans.insert(child.clone(), member.key_range.clone());
+                index_json_node(&member.value, &child, spans);
+            }
+        }
+        JsonNode::Array { items, .. } => {
+            for (i, item) in items.iter().enumerate() {
+                let child = child_path(path, &i.to_string());
+                spans.insert(child.clone(), item.range());
+                index_json_node(item, &child, spans);
+            }
+        }
+        JsonNode::Scalar { .. } => {}
+    }
+}
+
+/// A JSON document reduced to the byte ranges of its objects, keys and values.
+/// Used to locate and splice values without re-serializing the whole document.
+#[derive(Debug, Clone)]
+pub(crate) enum JsonNode {
+    Object {
+        range: Range<usize>,
+        members: Vec<JsonMember>,
+    },
+    Array {
+        range: Range<usize>,
+        items: Vec<JsonNode>,
+    },
+    Scalar {
+        range: Range<usize>,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct JsonMember {
+    pub key: String,
+    pub key_range: Range<usize>,
+    pub value: JsonNode,
+}
+
+impl JsonNode {
+    /// Parse a document. Returns `None` on syntax errors; `serde_json` reports those.
+    pub fn parse(content: &str) -> Option<JsonNode> {
+        let mut parser = JsonParser {
+            bytes: content.as_bytes(),
+            pos: 0,
+        };
+        parser.value()
+    }
+
+    pub fn range(&self) -> Range<usize> {
+        match self {
+            JsonNode::Object { range, .. }
+            | JsonNode::Array { range, .. }
+            | JsonNode::Scalar { range } => range.clone(),
+        }
+    }
+}
+
+struct JsonParser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl JsonParser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn value(&mut self) -> Option<JsonNode> {
+        self.skip_whitespace();
+        let start = self.pos;
+        match self.peek()? {
+            b'{' => self.object(),
+            b'[' => self.array(),
+            b'"' => {
+                self.string()?;
+                Some(JsonNode::Scalar {
+                    range: start..self.pos,
+                })
+            }
+            _ => {
+                while !matches!(
+                    self.peek(),
+                    None | Some(b',' | b']' | b'}' | b' ' | b'\t' | b'\n' | b'\r')
+                ) {
+                    self.pos += 1;
+                }
+                (self.pos > start).then_some(JsonNode::Scalar {
+                    range: start..self.pos,
+                })
+            }
+        }
+    }
+
+    fn object(&mut self) -> Option<JsonNode> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut members = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek()? {
+                b'}' => {
+                    self.pos += 1;
+                    return Some(JsonNode::Object {
+                        range: start..self.pos,
+                        members,
+                    });
+                }
+                b',' => self.pos += 1,
+                b'"' => {
+                    let key_start = self.pos;
+                    let key = self.string()?;
+                    let key_range = key_start..self.pos;
+
+                    self.skip_whitespace();
+                    if self.peek()? != b':' {
+                        return None;
+                    }
+                    self.pos += 1;
+                    let value = self.value()?;
+                    members.push(JsonMember {
+                        key,
+                        key_range,
+                        value,
+                    });
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    fn array(&mut self) -> Option<JsonNode> {
+        let start = self.pos;
+        self.pos += 1;
+        let mut items = Vec::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek()? {
+                b']' => {
+                    self.pos += 1;
+                    return Some(JsonNode::Array {
+                        range: start..self.pos,
+                        items,
+                    });
+                }
+                b',' => self.pos += 1,
+                _ => items.push(self.value()?),
+            }
+        }
+    }
+
+    /// Consume a string literal and return its (unescaped) contents.
+    fn string(&mut self) -> Option<String> {
+        self.pos += 1;
+        let mut out = Vec::new();
+        loop {
+            match self.peek()? {
+                b'"' => {
+                    self.pos += 1;
+                    return String::from_utf8(out).ok();
+                }
+                b'\\' => {
+                    out.push(*self.bytes.get(self.pos + 1)?);
+                    self.pos += 2;
+                }
+                byte => {
+                    out.push(byte);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Records the span of every block-style mapping key and sequence item in a YAML document.
+///
+/// This follows indentation only, which covers how manifests are written in practice; keys
+/// inside flow collections (`{ a: 1 }`) are attributed to their parent.
+fn index_yaml(content: &str, spans: &mut HashMap<String, Range<usize>>) {
+    let mut stack: Vec<(usize, String)> = Vec::new();
+    let mut sequence_counts: HashMap<String, usize> = HashMap::new();
+    let mut block_scalar_indent: Option<usize> = None;
+    let mut line_start = 0;
+
+    for raw_line in content.split_inclusive('\n') {
+        let offset = line_start;
+        line_start += raw_line.len();
+
+        let line = raw_line.trim_end_matches(['\n', '\r']);
+        let trimmed = line.trim_start_matches(' ');
+        let indent = line.len() - trimmed.len();
+
+        if let Some(scalar_indent) = block_scalar_indent {
+            if trimmed.is_empty() || indent > scalar_indent {
+                continue;
+            }
+            block_scalar_indent = None;
+        }
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with("---") {
+            continue;
+        }
+
+        let mut column = indent;
+        let mut rest = trimmed;
+
+        while rest == "-" || rest.starts_with("- ") {
+            while stack.last().is_some_and(|(i, _)| *i >= column) {
+                stack.pop();
+            }
+            let parent = yaml_path(&stack);
+            let count = sequence_counts.entry(parent.clone()).or_insert(0);
+            let child = child_path(&parent, &count.to_string());
+            *count += 1;
+
+            spans.insert(child.clone(), offset + column..offset + column + 1);
+            stack.push((column, child.rsplit('.').next().unwrap_or("").to_string()));
+
+            let after = rest[1..].trim_start_matches(' ');
+            column += rest.len() - after.len();
+            rest = after;
+        }
+
+        if let Some((key, value)) = yaml_key(rest) {
+            while stack.last().is_some_and(|(i, _)| *i >= column) {
+                stack.pop();
+            }
+            let child = child_path(&yaml_path(&stack), &key);
+            let key_len = rest.len() - value.len();
+            spans.insert(child, offset + column..offset + column + key_len);
+            stack.push((column, key));
+
+            let value = value.trim_start_matches(':').trim();
+            if value.starts_with('|') || value.starts_with('>') {
+                block_scalar_indent = Some(column);
+            }
+        }
+    }
+}
+
+fn yaml_path(stack: &[(usize, String)]) -> String {
+    stack
+        .iter()
+        .map(|(_, key)| key.as_str())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Split `key: value` into the key and the remainder starting at the colon.
+pub(crate) fn yaml_key(line: &str) -> Option<(String, &str)> {
+    let (key, rest) = match line.chars().next()? {
+        quote @ ('"' | '\'') => {
+            let end = line[1..].find(quote)? + 1;
+            (line[1..end].to_string(), &line[end + 1..])
+        }
+        '{' | '[' | '&' | '*' | '!' | '|' | '>' => return None,
+        _ => {
+            let end = line
+                .match_indices(':')
+                .map(|(i, _)| i)
+                .find(|&i| matches!(line.as_bytes().get(i + 1), None | Some(b' ')))?;
+            (line[..end].trim_end().to_string(), &line[end..])
+        }
+    };
+
+    rest.starts_with(':').then_some((key, rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ValidationLevel;
+
+    #[test]
+    fn test_toml_spans() {
+        let source = ManifestSource::new(
+            "[project]\nname = \"demo\"\n\n[profiles.dev.env]\nDEBUG = \"1\"\n",
+            ManifestFormat::Toml,
+        );
+
+        let span = source.span_of("profiles.dev.env.DEBUG").unwrap();
+        assert_eq!((span.line, span.column), (5, 1));
+        assert_eq!(span.snippet, "DEBUG = \"1\"");
+
+        let span = source.span_of("project.name").unwrap();
+        assert_eq!((span.line, span.column, span.length), (2, 1, 4));
+
+        // Unknown children fall back to the closest enclosing key.
+        assert_eq!(source.span_of("project.version").unwrap().line, 1);
+    }
+
+    #[test]
+    fn test_json_spans() {
+        let source = ManifestSource::new(
+            "{\n  \"scripts\": {\n    \"build\": [\"a\", \"b\"]\n  }\n}",
+            ManifestFormat::Json,
+        );
+
+        let span = source.span_of("scripts.build").unwrap();
+        assert_eq!((span.line, span.column), (3, 5));
+
+        let span = source.span_of("scripts.build[1]").unwrap();
+        assert_eq!((span.line, span.column, span.length), (3, 20, 3));
+    }
+
+    #[test]
+    fn test_yaml_spans() {
+        let source = ManifestSource::new(
+            "project:\n  name: demo\nscripts:\n  build:\n    - cargo build\n    - cargo test\nhooks:\n  post_install: |\n    fake: key\n  pre_install: echo\n",
+            ManifestFormat::Yaml,
+        );
+
+        assert_eq!(source.span_of("project.name").unwrap().line, 2);
+        assert_eq!(source.span_of("scripts.build[1]").unwrap().line, 6);
+        assert_eq!(source.span_of("hooks.pre_install").unwrap().line, 10);
+        assert_eq!(source.span_of("hooks.post_install.fake").unwrap().line, 8);
+    }
+
+    #[test]
+    fn test_parse_errors_carry_location() {
+        let toml = ManifestSource::new("[project]\nname = 5\n", ManifestFormat::Toml)
+            .with_path("env.toml");
+        let err = toml.parse().unwrap_err();
+        let span = err.span.unwrap();
+        assert_eq!((span.line, span.column), (2, 8));
+        assert_eq!(span.path.as_deref(), Some(Path::new("env.toml")));
+
+        let json = ManifestSource::new("{\n  \"bogus\": 1\n}", ManifestFormat::Json);
+        let err = json.parse().unwrap_err();
+        assert_eq!(err.span.unwrap().line, 2);
+        assert!(err.message.starts_with("unknown field `bogus`"));
+
+        let yaml = ManifestSource::new("dependencies:\n  node: [1\n", ManifestFormat::Yaml);
+        assert!(yaml.parse().unwrap_err().span.is_some());
+    }
+
+    #[test]
+    fn test_legacy_manifests_are_upgraded_on_parse() {
+        let source = ManifestSource::new("[plugin]\nname = \"demo\"\n", ManifestFormat::Toml);
+        let (manifest, result) = source.validate().unwrap();
+        assert_eq!(manifest.project.name, "demo");
+        assert_eq!(manifest.schema_version, Some(CURRENT_SCHEMA_VERSION));
+
+        assert!(result
+            .issues
+            .iter()
+            .any(|i| i.field == SCHEMA_VERSION_KEY && i.level == ValidationLevel::Warning));
+
+        for current in ["[project]\nname = \"demo\"\n", "schema-version = 0\n"] {
+            let (_, result) = ManifestSource::new(current, ManifestFormat::Toml)
+                .validate()
+                .unwrap();
+            assert!(result.issues.iter().all(|i| i.field != SCHEMA_VERSION_KEY));
+        }
+
+        let newer = ManifestSource::new("schema-version = 99\n", ManifestFormat::Toml);
+        assert_eq!(newer.parse().unwrap_err().span.unwrap().line, 1);
+    }
+
+    #[test]
+    fn test_validation_issues_are_located() {
+        let source = ManifestSource::new(
+            "[dependencies]\nnode = \"^20\"\n\n[group.docs]\ndependencies = {}\n",
+            ManifestFormat::Toml,
+        );
+        let (_, result) = source.validate().unwrap();
+
+        let issue = result
+            .issues
+            .iter()
+            .find(|i| i.field == "group.docs.dependencies")
+            .unwrap();
+        assert_eq!(issue.location.as_ref().unwrap().line, 5);
+    }
+
+    #[test]
+    fn test_unknown_cfg_keys_are_located() {
+        let source = ManifestSource::new(
+            "[dependencies]
+node = \"^20\"
+
+[target.'cfg(target_cpu = \"x\")'.dependencies]
+gcc = \"*\"
+",
+            ManifestFormat::Toml,
+        );
+        let (_, result) = source.validate().unwrap();
+
+        let issue = result
+            .issues
+            .iter()
+            .find(|i| i.field == "target.cfg(target_cpu = \"x\")")
+            .unwrap();
+        assert!(issue.message.contains("Unknown cfg key `target_cpu`"));
+        assert_eq!(issue.location.as_ref().unwrap().line, 4);
+    }
+}
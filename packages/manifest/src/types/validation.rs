@@ -1,8 +1,12 @@
-use crate::{CpuArchitecture, EnhancedManifest, OperatingSystem};
+use crate::{
+    normalize_os_version, parse_os_version_req, validate_families, validate_resolution_policy,
+    validate_scoring, EnhancedManifest, EnvValue, Rule, RuleChecker, SourceSpan, TargetPlatform,
+    TargetSelector,
+};
 use anyhow::Result;
 use schemars::JsonSchema;
-use semver::VersionReq;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, JsonSchema, Serialize, Deserialize)]
 pub enum ValidationLevel {
@@ -16,6 +20,12 @@ pub struct ValidationIssue {
     pub level: ValidationLevel,
     pub field: String,
     pub message: String,
+    /// Where the field is written, when the manifest was parsed from source.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<SourceSpan>,
+    /// ID of the semantic rule that raised the issue; see [`Rule`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rule: Option<String>,
 }
 
 #[derive(Debug, Clone, JsonSchema, Serialize, Deserialize)]
@@ -38,6 +48,8 @@ impl ValidationResult {
             level: ValidationLevel::Error,
             field: field.into(),
             message: message.into(),
+            location: None,
+            rule: None,
         });
     }
 
@@ -46,6 +58,8 @@ impl ValidationResult {
             level: ValidationLevel::Warning,
             field: field.into(),
             message: message.into(),
+            location: None,
+            rule: None,
         });
     }
 
@@ -54,6 +68,23 @@ impl ValidationResult {
             level: ValidationLevel::Info,
             field: field.into(),
             message: message.into(),
+            location: None,
+            rule: None,
+        });
+    }
+
+    /// Record an issue raised by `rule`, at the rule's level.
+    pub fn add_rule(&mut self, rule: Rule, field: impl Into<String>, message: impl Into<String>) {
+        let level = rule.level();
+        if level == ValidationLevel::Error {
+            self.valid = false;
+        }
+        self.issues.push(ValidationIssue {
+            level,
+            field: field.into(),
+            message: message.into(),
+            location: None,
+            rule: Some(rule.id().to_string()),
         });
     }
 
@@ -85,9 +116,16 @@ impl ManifestValidator {
         Self::validate_required_fields(manifest, &mut result);
         Self::validate_recommended_fields(manifest, &mut result);
         Self::validate_platform_constraints(manifest, &mut result);
+        Self::validate_targets(manifest, &mut result);
         Self::validate_dependencies(manifest, &mut result);
         Self::validate_profiles(manifest, &mut result);
         Self::validate_groups(manifest, &mut result);
+        Self::validate_env(manifest, &mut result);
+        validate_families(&manifest.families, &mut result);
+        if let Some(scoring) = &manifest.scoring {
+            validate_scoring(scoring, &mut result);
+        }
+        validate_resolution_policy(manifest, &mut result);
 
         result
     }
@@ -144,9 +182,9 @@ impl ManifestValidator {
     fn validate_platform_constraints(manifest: &EnhancedManifest, result: &mut ValidationResult) {
         if let Some(platform) = &manifest.platform {
             for (platform_key, version_req) in &platform.requirements {
-                if let Err(e) = VersionReq::parse(version_req) {
+                if let Err(e) = parse_os_version_req(version_req) {
                     result.add_error(
-                        format!("platform.requirements.{:?}", platform_key),
+                        format!("platform.requirements.{}", platform_key.as_str()),
                         format!("Invalid version requirement '{}': {}", version_req, e),
                     );
                 }
@@ -154,8 +192,24 @@ impl ManifestValidator {
         }
     }
 
-    fn validate_dependencies(_manifest: &EnhancedManifest, _result: &mut ValidationResult) {
-        // Dependencies are strict types now.
+    fn validate_targets(manifest: &EnhancedManifest, result: &mut ValidationResult) {
+        for key in manifest.target.keys() {
+            let field = format!("target.{}", key);
+            match TargetSelector::parse(key) {
+                Ok(TargetSelector::Cfg(expr)) => {
+                    for problem in expr.problems() {
+                        result.add_error(&field, problem);
+                    }
+                }
+                Ok(TargetSelector::Triple(_)) => {}
+                Err(e) => result.add_error(&field, format!("Invalid cfg expression: {}", e)),
+            }
+        }
+    }
+
+    fn validate_dependencies(manifest: &EnhancedManifest, result: &mut ValidationResult) {
+        // Shapes are enforced by the types; these are the cross-field rules.
+        RuleChecker::new(manifest, result).check(result);
     }
 
     fn validate_profiles(manifest: &EnhancedManifest, result: &mut ValidationResult) {
@@ -205,50 +259,208 @@ impl ManifestValidator {
         }
     }
 
-    pub fn check_platform_compatibility(manifest: &EnhancedManifest) -> Result<()> {
-        if let Some(platform) = &manifest.platform {
-            let current_os = std::env::consts::OS;
-            let current_arch = std::env::consts::ARCH;
-
-            let os_enum: OperatingSystem =
-                serde_json::from_value(serde_json::Value::String(current_os.to_string()))
-                    .unwrap_or(OperatingSystem::Any);
+    fn validate_env(manifest: &EnhancedManifest, result: &mut ValidationResult) {
+        Self::validate_env_map("env", &manifest.env, result);
+        for (name, profile) in &manifest.profiles {
+            Self::validate_env_map(&format!("profiles.{}.env", name), &profile.env, result);
+        }
+        for (name, service) in &manifest.services {
+            Self::validate_env_map(&format!("services.{}.env", name), &service.env, result);
+        }
+    }
 
-            let arch_enum: CpuArchitecture =
-                serde_json::from_value(serde_json::Value::String(current_arch.to_string()))
-                    .unwrap_or(CpuArchitecture::Any);
+    fn validate_env_map(
+        prefix: &str,
+        env: &HashMap<String, EnvValue>,
+        result: &mut ValidationResult,
+    ) {
+        for (key, value) in env {
+            let problem = match value {
+                EnvValue::Literal(_) => None,
+                EnvValue::Detailed(details) => details.conflicts(),
+                EnvValue::Secret(secret) => secret.conflicts(),
+            };
+            if let Some(problem) = problem {
+                result.add_error(format!("{}.{}", prefix, key), problem);
+            }
+        }
+    }
 
-            if !platform.platforms.contains(&OperatingSystem::Any)
-                && !platform.platforms.contains(&os_enum)
-            {
-                let valid_list: Vec<String> = platform
-                    .platforms
-                    .iter()
-                    .map(|p| format!("{:?}", p))
-                    .collect();
-                anyhow::bail!(
+    /// Check the manifest's `platform` constraints against the platform it is running on:
+    /// allowed OS and architecture lists, and per-OS version requirements.
+    pub fn check_platform(
+        manifest: &EnhancedManifest,
+        platform: &TargetPlatform,
+        result: &mut ValidationResult,
+    ) {
+        let Some(constraints) = &manifest.platform else {
+            return;
+        };
+
+        if !constraints.platforms.iter().any(|p| p.admits(platform.os)) {
+            let valid_list: Vec<&str> = constraints.platforms.iter().map(|p| p.as_str()).collect();
+            result.add_error(
+                "platform.platforms",
+                format!(
                     "Platform '{}' is not supported. Supported: {}",
-                    current_os,
+                    platform.os.as_str(),
                     valid_list.join(", ")
-                );
-            }
+                ),
+            );
+        }
 
-            if !platform.architectures.contains(&CpuArchitecture::Any)
-                && !platform.architectures.contains(&arch_enum)
-            {
-                let valid_list: Vec<String> = platform
-                    .architectures
-                    .iter()
-                    .map(|a| format!("{:?}", a))
-                    .collect();
-                anyhow::bail!(
+        if !constraints
+            .architectures
+            .iter()
+            .any(|a| a.admits(platform.arch))
+        {
+            let valid_list: Vec<&str> = constraints
+                .architectures
+                .iter()
+                .map(|a| a.as_str())
+                .collect();
+            result.add_error(
+                "platform.architectures",
+                format!(
                     "Architecture '{}' is not supported. Supported: {}",
-                    current_arch,
+                    platform.arch.as_str(),
                     valid_list.join(", ")
-                );
+                ),
+            );
+        }
+
+        for (os, raw_req) in &constraints.requirements {
+            if !os.admits(platform.os) {
+                continue;
+            }
+            // Unparseable requirements are reported by `validate`.
+            let Ok(req) = parse_os_version_req(raw_req) else {
+                continue;
+            };
+
+            let field = format!("platform.requirements.{}", os.as_str());
+            let reported = platform.os_version.as_deref().unwrap_or("unknown");
+            match platform
+                .os_version
+                .as_deref()
+                .and_then(normalize_os_version)
+            {
+                Some(version) if !req.matches(&version) => result.add_error(
+                    field,
+                    format!(
+                        "{} {} does not satisfy the required version '{}'",
+                        platform.os.as_str(),
+                        reported,
+                        raw_req
+                    ),
+                ),
+                Some(_) => {}
+                None => result.add_warning(
+                    field,
+                    format!(
+                        "Could not read the {} version ('{}') to check '{}'",
+                        platform.os.as_str(),
+                        reported,
+                        raw_req
+                    ),
+                ),
             }
         }
+    }
+
+    /// Fail with the first platform incompatibility, if any.
+    pub fn check_platform_compatibility(
+        manifest: &EnhancedManifest,
+        platform: &TargetPlatform,
+    ) -> Result<()> {
+        let mut result = ValidationResult::new();
+        Self::check_platform(manifest, platform, &mut result);
+
+        match result
+            .issues
+            .iter()
+            .find(|i| i.level == ValidationLevel::Error)
+        {
+            Some(issue) => anyhow::bail!("{}", issue.message),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CpuArchitecture, OperatingSystem};
+
+    fn manifest(platform: &str) -> EnhancedManifest {
+        toml::from_str(&format!(
+            "[dependencies]\nnode = \"^20\"\n\n[platform]\n{}",
+            platform
+        ))
+        .unwrap()
+    }
+
+    fn errors(manifest: &EnhancedManifest, platform: &TargetPlatform) -> Vec<String> {
+        let mut result = ValidationResult::new();
+        ManifestValidator::check_platform(manifest, platform, &mut result);
+        result
+            .issues
+            .into_iter()
+            .filter(|i| i.level == ValidationLevel::Error)
+            .map(|i| i.field)
+            .collect()
+    }
+
+    #[test]
+    fn test_architecture_aliases() {
+        let m =
+            manifest("platforms = [\"linux\", \"darwin\"]\narchitectures = [\"amd64\", \"arm64\"]");
+        let linux = TargetPlatform::new(OperatingSystem::Linux, CpuArchitecture::X86_64);
+        let mac = TargetPlatform::new(OperatingSystem::Macos, CpuArchitecture::Aarch64);
+        assert!(errors(&m, &linux).is_empty());
+        assert!(errors(&m, &mac).is_empty());
+
+        let windows = TargetPlatform::new(OperatingSystem::Windows, CpuArchitecture::Riscv64);
+        assert_eq!(
+            errors(&m, &windows),
+            vec!["platform.platforms", "platform.architectures"]
+        );
+    }
+
+    #[test]
+    fn test_os_version_requirements() {
+        let m = manifest("[platform.requirements]\nmacos = \">=12.0\"\nlinux = \">=22.04\"");
+        assert!(ManifestValidator::validate(&m)
+            .issues
+            .iter()
+            .all(|i| !i.field.starts_with("platform")));
+
+        let old_mac = TargetPlatform::new(OperatingSystem::Macos, CpuArchitecture::Aarch64)
+            .with_os_version("11.7.10");
+        assert_eq!(errors(&m, &old_mac), vec!["platform.requirements.macos"]);
+        assert!(ManifestValidator::check_platform_compatibility(&m, &old_mac).is_err());
+
+        let ubuntu = TargetPlatform::new(OperatingSystem::Linux, CpuArchitecture::X86_64)
+            .with_os_version("22.04");
+        assert!(errors(&m, &ubuntu).is_empty());
+        let focal = ubuntu.clone().with_os_version("20.04.6 LTS");
+        assert_eq!(errors(&m, &focal), vec!["platform.requirements.linux"]);
+
+        // A rolling release has no version to check: warn, don't block.
+        let arch = TargetPlatform::new(OperatingSystem::Linux, CpuArchitecture::X86_64);
+        assert!(errors(&m, &arch).is_empty());
+        assert!(ManifestValidator::check_platform_compatibility(&m, &arch).is_ok());
+        let mut result = ValidationResult::new();
+        ManifestValidator::check_platform(&m, &arch, &mut result);
+        assert_eq!(result.issues[0].level, ValidationLevel::Warning);
+    }
 
-        Ok(())
+    #[test]
+    fn test_normalize_os_versions() {
+        let v = |s| normalize_os_version(s).map(|v| v.to_string());
+        assert_eq!(v("22.04").as_deref(), Some("22.4.0"));
+        assert_eq!(v("6.5.0-14-generic").as_deref(), Some("6.5.0"));
+        assert_eq!(v("14.2.1 (23C71)").as_deref(), Some("14.2.1"));
+        assert_eq!(v("Rolling Release"), None);
     }
 }
@@ -1,6 +1,7 @@
 use crate::check::{Check, CheckResult};
 use crate::diagnosis::{Diagnostic, Severity};
 use env_architect_sdk::host;
+use env_architect_sdk::parse_dotenv;
 // use env_architect_sdk::prelude::*;
 
 pub struct CoreCheck;
@@ -62,10 +63,17 @@ impl Check for EnvCheck {
         // Read .env file content first
         let mut defined_vars = std::collections::HashSet::new();
         if let Ok(content) = host::read_file(".env") {
-            for line in content.lines() {
-                if let Some((key, _)) = line.split_once('=') {
-                    defined_vars.insert(key.trim().to_string());
-                }
+            match parse_dotenv(&content) {
+                Ok(vars) => defined_vars.extend(vars.into_iter().map(|(key, _)| key)),
+                Err(e) => issues.push(
+                    Diagnostic::new(
+                        Severity::Warning,
+                        "DOTENV_INVALID",
+                        "Malformed .env file",
+                        &format!(".env {}", e),
+                    )
+                    .with_advice("Tools reading .env may load it differently or not at all."),
+                ),
             }
         }
 
@@ -59,7 +59,7 @@ impl Plugin for PythonPlugin {
         // 2. Set environment requirements
         plan.manifest
             .env
-            .insert("python".to_string(), version_req.clone());
+            .insert("python".to_string(), version_req.clone().into());
 
         // 3. Determine Installation Strategy
         // Recommendation: use a manageable python
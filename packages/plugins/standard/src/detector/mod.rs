@@ -1,4 +1,5 @@
 use env_architect_sdk::prelude::*; // For InstallPlan, Result
+use env_architect_sdk::{host, parse_pin_file, DependencySpec, VersionConstraint, PIN_FILES};
 
 pub mod node;
 pub mod python;
@@ -17,6 +18,22 @@ pub trait LanguageDetector {
     fn plan(&self) -> Result<InstallPlan>;
 }
 
+/// The version the repository pins for `tool` (.nvmrc, .tool-versions, ...), read with
+/// the same parser as `env import`; `fallback` when nothing pins it.
+pub fn pinned_version(tool: &str, fallback: &str) -> DependencySpec {
+    let pinned = PIN_FILES.iter().find_map(|file| {
+        let content = host::read_file(*file).ok()?;
+        parse_pin_file(file, &content)
+            .pins
+            .into_iter()
+            .find(|pin| pin.tool == tool)
+    });
+    DependencySpec::Simple(match pinned {
+        Some(pin) => pin.version,
+        None => VersionConstraint::parse(fallback).unwrap_or_default(),
+    })
+}
+
 /// Main entry point for detection
 pub fn detect_all() -> Result<InstallPlan> {
     let mut final_plan = InstallPlan::default();
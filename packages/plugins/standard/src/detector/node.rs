@@ -1,4 +1,4 @@
-use crate::detector::LanguageDetector;
+use crate::detector::{pinned_version, LanguageDetector};
 use env_architect_sdk::host;
 use env_architect_sdk::prelude::*;
 
@@ -6,16 +6,18 @@ pub struct NodeDetector;
 
 impl LanguageDetector for NodeDetector {
     fn detect(&self) -> Result<bool> {
-        Ok(host::get_env("package.json").is_some() || host::read_file("package.json").is_ok())
+        Ok(host::get_env("package.json").is_some()
+            || host::read_file("package.json").is_ok()
+            || host::read_file(".nvmrc").is_ok())
     }
 
     fn plan(&self) -> Result<InstallPlan> {
         let mut plan = InstallPlan::default();
 
-        // Add Node.js requirement to manifest.env
+        // Node.js requirement, from .nvmrc / engines when pinned
         plan.manifest
-            .env
-            .insert("node".to_string(), "18.x".to_string());
+            .dependencies
+            .insert("node".to_string(), pinned_version("node", "18.x"));
 
         // Add install command
         if host::read_file("yarn.lock").is_ok() {
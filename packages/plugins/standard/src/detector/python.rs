@@ -1,4 +1,4 @@
-use crate::detector::LanguageDetector;
+use crate::detector::{pinned_version, LanguageDetector};
 use env_architect_sdk::host;
 use env_architect_sdk::prelude::*;
 
@@ -8,14 +8,15 @@ impl LanguageDetector for PythonDetector {
     fn detect(&self) -> Result<bool> {
         Ok(host::read_file("requirements.txt").is_ok()
             || host::read_file("Pipfile").is_ok()
-            || host::read_file("pyproject.toml").is_ok())
+            || host::read_file("pyproject.toml").is_ok()
+            || host::read_file(".python-version").is_ok())
     }
 
     fn plan(&self) -> Result<InstallPlan> {
         let mut plan = InstallPlan::default();
         plan.manifest
-            .env
-            .insert("python".to_string(), "3.10".to_string());
+            .dependencies
+            .insert("python".to_string(), pinned_version("python", "3.10"));
 
         if host::read_file("Pipfile").is_ok() {
             plan.instructions.push("pipenv install".to_string());
@@ -1,4 +1,4 @@
-use crate::detector::LanguageDetector;
+use crate::detector::{pinned_version, LanguageDetector};
 use env_architect_sdk::host;
 use env_architect_sdk::prelude::*;
 
@@ -12,8 +12,8 @@ impl LanguageDetector for RustDetector {
     fn plan(&self) -> Result<InstallPlan> {
         let mut plan = InstallPlan::default();
         plan.manifest
-            .env
-            .insert("rust".to_string(), "stable".to_string());
+            .dependencies
+            .insert("rust".to_string(), pinned_version("rust", "stable"));
         plan.instructions.push("cargo build".to_string());
         Ok(plan)
     }
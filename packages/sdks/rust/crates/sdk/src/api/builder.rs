@@ -1,8 +1,8 @@
 use crate::contract::reexports::{
     Asset, Capability, CpuArchitecture, DependencySpec, EnhancedManifest, OperatingSystem,
-    PlatformConstraints, ServiceDef,
+    PlatformConstraints, ServiceDef, VersionConstraint,
 };
-use semver::{Version, VersionReq};
+use semver::Version;
 /// A fluent builder for constructing `EnhancedManifest`s.
 ///
 /// This builder provides a convenient, method-chaining API to create complex
@@ -112,11 +112,13 @@ impl EnvBuilder {
     ///
     /// # Arguments
     /// * `name` - The package name of the dependency.
-    /// * `version_req` - A SemVer version requirement string (e.g., "^1.0").
+    /// * `version_req` - A version constraint, in SemVer or the tool's native syntax (e.g., "^1.0", "~=3.11").
     pub fn add_dependency(mut self, name: &str, version_req: &str) -> Self {
         self.manifest.dependencies.insert(
             name.to_string(),
-            DependencySpec::Simple(VersionReq::parse(version_req).expect("Invalid VersionReq")),
+            DependencySpec::Simple(
+                VersionConstraint::parse(version_req).expect("Invalid version constraint"),
+            ),
         );
         self
     }
@@ -125,7 +127,9 @@ impl EnvBuilder {
     pub fn add_dev_dependency(mut self, name: &str, version_req: &str) -> Self {
         self.manifest.dev_dependencies.insert(
             name.to_string(),
-            DependencySpec::Simple(VersionReq::parse(version_req).expect("Invalid VersionReq")),
+            DependencySpec::Simple(
+                VersionConstraint::parse(version_req).expect("Invalid version constraint"),
+            ),
         );
         self
     }
@@ -77,7 +77,14 @@ export interface PackageMetadata {
 
 export type ResolutionAction =
 	| { "managed-install": { manager: string; command: string } }
-	| { "auto-shim": { url: string; binary_name: string } }
+	| {
+			"auto-shim": {
+				url: string;
+				binary_name: string;
+				version: string;
+				sha256: string;
+			};
+	  }
 	| { "config-update": { path: string; patch: string } }
 	| { "manual-prompt": { message: string; instructions: string } };
 